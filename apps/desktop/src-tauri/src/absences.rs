@@ -0,0 +1,459 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::api::{ApiRequest, Z8ApiClient};
+use crate::notify;
+use crate::state::AppState;
+
+/// Cache key for the last fetched categories, absences and balance
+pub const ABSENCES_CACHE_KEY: &str = "absences";
+/// Cache key for the absence states seen on the last check
+const ABSENCE_STATUSES_CACHE_KEY: &str = "absence_statuses";
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbsenceCategory {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub category_type: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub color: Option<String>,
+    #[serde(default)]
+    pub requires_approval: bool,
+    pub counts_against_vacation: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayPeriod {
+    FullDay,
+    Am,
+    Pm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AbsenceStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// An absence of the current year and its approval state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Absence {
+    pub id: String,
+    pub start_date: NaiveDate,
+    pub start_period: DayPeriod,
+    pub end_date: NaiveDate,
+    pub end_period: DayPeriod,
+    pub status: AbsenceStatus,
+    pub notes: Option<String>,
+    pub category: AbsenceCategory,
+    pub approved_at: Option<DateTime<Utc>>,
+    pub rejection_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VacationBalance {
+    pub year: i32,
+    pub total_days: f64,
+    pub used_days: f64,
+    pub pending_days: f64,
+    pub remaining_days: f64,
+    pub carryover_days: Option<f64>,
+    pub carryover_expiry_date: Option<DateTime<Utc>>,
+}
+
+/// Everything the absence view needs in one round trip
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbsenceOverview {
+    pub categories: Vec<AbsenceCategory>,
+    pub absences: Vec<Absence>,
+    /// `None` when the organization has no vacation allowance configured
+    pub vacation_balance: Option<VacationBalance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AbsenceRequest {
+    pub category_id: String,
+    pub start_date: NaiveDate,
+    pub start_period: DayPeriod,
+    pub end_date: NaiveDate,
+    pub end_period: DayPeriod,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfServiceSource {
+    TimeCorrection,
+    Absence,
+    TravelExpense,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SelfServiceStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Cancelled,
+}
+
+/// One of the user's requests across absences, corrections and expenses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfServiceRequest {
+    pub id: String,
+    pub source_type: SelfServiceSource,
+    pub source_id: String,
+    pub status: SelfServiceStatus,
+    pub submitted_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub title: String,
+    pub subtitle: String,
+    pub decision_reason: Option<String>,
+    pub available_actions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfServiceCounts {
+    pub pending: u32,
+    pub required_fixes: u32,
+    pub recent_decisions: u32,
+    pub total: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MyRequests {
+    pub items: Vec<SelfServiceRequest>,
+    pub counts: SelfServiceCounts,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RequestAbsenceData {
+    absence_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RequestAbsenceResponse {
+    data: RequestAbsenceData,
+}
+
+pub struct AbsenceService {
+    api: Z8ApiClient,
+}
+
+impl AbsenceService {
+    pub fn new(api: Z8ApiClient) -> Self {
+        Self { api }
+    }
+
+    /// Fetches categories, this year's absences and the vacation balance
+    pub async fn overview(&self) -> Result<AbsenceOverview> {
+        let response = self
+            .api
+            .send(ApiRequest::get("/api/desktop/absences"))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Failed to fetch absences: {}", response.status));
+        }
+
+        response.json()
+    }
+
+    /// Requests an absence and returns its id
+    pub async fn request(&self, request: &AbsenceRequest) -> Result<String> {
+        let response = self
+            .api
+            .send(ApiRequest::post(
+                "/api/desktop/absences",
+                serde_json::to_value(request)?,
+            ))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Absence request failed: {}", response.body));
+        }
+
+        let payload: RequestAbsenceResponse = response.json()?;
+        Ok(payload.data.absence_id)
+    }
+
+    /// Withdraws a request that has not been decided yet
+    pub async fn cancel(&self, absence_id: &str) -> Result<()> {
+        let path = format!("/api/desktop/absences/{}/cancel", absence_id);
+        let response = self
+            .api
+            .send(ApiRequest::post(&path, serde_json::json!({})))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Failed to cancel absence: {}", response.body));
+        }
+
+        Ok(())
+    }
+
+    /// Lists the user's requests of every kind with their status
+    pub async fn my_requests(&self) -> Result<MyRequests> {
+        let response = self
+            .api
+            .send(ApiRequest::get("/api/desktop/my-requests"))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Failed to fetch requests: {}", response.status));
+        }
+
+        response.json()
+    }
+}
+
+/// Absences a manager decided since the previous check, given the states seen then
+fn decided<'a>(
+    previous: &HashMap<String, AbsenceStatus>,
+    absences: &'a [Absence],
+) -> Vec<&'a Absence> {
+    absences
+        .iter()
+        .filter(|absence| absence.status != AbsenceStatus::Pending)
+        .filter(|absence| previous.get(&absence.id) == Some(&AbsenceStatus::Pending))
+        .collect()
+}
+
+fn notification(absence: &Absence) -> (&'static str, String) {
+    let period = if absence.start_date == absence.end_date {
+        absence.start_date.format("%b %-d").to_string()
+    } else {
+        format!(
+            "{} – {}",
+            absence.start_date.format("%b %-d"),
+            absence.end_date.format("%b %-d")
+        )
+    };
+
+    match absence.status {
+        AbsenceStatus::Approved => (
+            "Absence approved",
+            format!("Your {} on {} was approved.", absence.category.name, period),
+        ),
+        _ => (
+            "Absence rejected",
+            match &absence.rejection_reason {
+                Some(reason) => format!(
+                    "Your {} on {} was rejected: {}",
+                    absence.category.name, period, reason
+                ),
+                None => format!("Your {} on {} was rejected.", absence.category.name, period),
+            },
+        ),
+    }
+}
+
+/// Polls absence requests and notifies when a manager approves or rejects one
+pub async fn start_absence_watcher(app_handle: AppHandle) {
+    log::info!("Starting absence watcher");
+
+    let state = app_handle.state::<Arc<AppState>>();
+    let absence_service = AbsenceService::new(state.api.clone());
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        if state.get_session_token().is_none() || state.get_webapp_url().is_empty() {
+            continue;
+        }
+
+        let overview = match absence_service.overview().await {
+            Ok(overview) => overview,
+            Err(e) => {
+                log::warn!("Failed to check absences: {}", e);
+                continue;
+            }
+        };
+
+        let previous: HashMap<String, AbsenceStatus> = {
+            let queue = state.offline_queue.lock();
+            queue
+                .get_cached(ABSENCE_STATUSES_CACHE_KEY)
+                .ok()
+                .flatten()
+                .map(|cached| cached.value)
+                .unwrap_or_default()
+        };
+
+        for absence in decided(&previous, &overview.absences) {
+            log::info!("Absence {} was {:?}", absence.id, absence.status);
+            let (title, body) = notification(absence);
+            notify::send(&app_handle, title, &body);
+            let _ = app_handle.emit("absence_decided", absence);
+        }
+
+        let statuses: HashMap<&str, AbsenceStatus> = overview
+            .absences
+            .iter()
+            .map(|absence| (absence.id.as_str(), absence.status))
+            .collect();
+
+        let mut queue = state.offline_queue.lock();
+        if let Err(e) = queue.put_cached(ABSENCE_STATUSES_CACHE_KEY, &statuses) {
+            log::warn!("Failed to cache absence states: {}", e);
+        }
+        if let Err(e) = queue.put_cached(ABSENCES_CACHE_KEY, &overview) {
+            log::warn!("Failed to cache absences: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decided, AbsenceOverview, AbsenceRequest, AbsenceService, AbsenceStatus, DayPeriod,
+    };
+    use crate::api::testing::fake_client;
+    use std::collections::HashMap;
+
+    fn overview() -> AbsenceOverview {
+        serde_json::from_value(serde_json::json!({
+            "categories": [{
+                "id": "cat-1",
+                "name": "Vacation",
+                "type": "vacation",
+                "description": null,
+                "color": "#22c55e",
+                "requiresApproval": true,
+                "countsAgainstVacation": true
+            }],
+            "absences": [
+                {
+                    "id": "a",
+                    "employeeId": "emp-1",
+                    "startDate": "2026-10-19",
+                    "startPeriod": "full_day",
+                    "endDate": "2026-10-21",
+                    "endPeriod": "am",
+                    "status": "approved",
+                    "notes": null,
+                    "sickDetail": null,
+                    "category": {
+                        "id": "cat-1",
+                        "name": "Vacation",
+                        "type": "vacation",
+                        "color": "#22c55e",
+                        "countsAgainstVacation": true
+                    },
+                    "approvedBy": "emp-2",
+                    "approvedAt": "2026-10-15T09:00:00.000Z",
+                    "rejectionReason": null,
+                    "createdAt": "2026-10-14T08:00:00.000Z"
+                },
+                {
+                    "id": "b",
+                    "employeeId": "emp-1",
+                    "startDate": "2026-11-02",
+                    "startPeriod": "full_day",
+                    "endDate": "2026-11-02",
+                    "endPeriod": "full_day",
+                    "status": "pending",
+                    "notes": "Dentist",
+                    "sickDetail": null,
+                    "category": {
+                        "id": "cat-1",
+                        "name": "Vacation",
+                        "type": "vacation",
+                        "color": "#22c55e",
+                        "countsAgainstVacation": true
+                    },
+                    "approvedBy": null,
+                    "approvedAt": null,
+                    "rejectionReason": null,
+                    "createdAt": "2026-10-15T08:00:00.000Z"
+                }
+            ],
+            "vacationBalance": {
+                "year": 2026,
+                "totalDays": 30,
+                "usedDays": 12.5,
+                "pendingDays": 1,
+                "remainingDays": 16.5
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn reports_only_absences_that_left_pending() {
+        let overview = overview();
+        assert_eq!(overview.absences[0].end_period, DayPeriod::Am);
+        assert_eq!(
+            overview.vacation_balance.as_ref().map(|b| b.remaining_days),
+            Some(16.5)
+        );
+
+        let previous = HashMap::from([
+            ("a".to_string(), AbsenceStatus::Pending),
+            ("b".to_string(), AbsenceStatus::Pending),
+        ]);
+        let ids: Vec<&str> = decided(&previous, &overview.absences)
+            .into_iter()
+            .map(|absence| absence.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["a"]);
+
+        // Nothing is announced on the very first check
+        assert!(decided(&HashMap::new(), &overview.absences).is_empty());
+    }
+
+    #[tokio::test]
+    async fn requests_an_absence() {
+        let (client, transport) = fake_client();
+        transport.respond(
+            200,
+            serde_json::json!({ "success": true, "data": { "absenceId": "abs-1" } }),
+        );
+
+        let request = AbsenceRequest {
+            category_id: "cat-1".to_string(),
+            start_date: "2026-11-02".parse().unwrap(),
+            start_period: DayPeriod::FullDay,
+            end_date: "2026-11-03".parse().unwrap(),
+            end_period: DayPeriod::Pm,
+            notes: None,
+        };
+        let absence_id = AbsenceService::new(client).request(&request).await.unwrap();
+        assert_eq!(absence_id, "abs-1");
+
+        let sent = &transport.requests()[0];
+        assert!(sent.url.ends_with("/api/desktop/absences"));
+        assert_eq!(
+            sent.body,
+            Some(serde_json::json!({
+                "categoryId": "cat-1",
+                "startDate": "2026-11-02",
+                "startPeriod": "full_day",
+                "endDate": "2026-11-03",
+                "endPeriod": "pm"
+            }))
+        );
+    }
+}
@@ -0,0 +1,765 @@
+use anyhow::Result;
+use parking_lot::RwLock;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use crate::network::NetworkConfig;
+
+pub const APP_TYPE_HEADER_NAME: &str = "X-Z8-App-Type";
+pub const DESKTOP_APP_TYPE: &str = "desktop";
+/// Identifies the registered device on authenticated requests
+pub const DEVICE_ID_HEADER_NAME: &str = "X-Z8-Device-Id";
+/// Error code the webapp returns once the device was revoked
+const DEVICE_REVOKED_CODE: &str = "device_revoked";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const POOL_MAX_IDLE_PER_HOST: usize = 4;
+
+/// Timeout for requests that don't fit one of the more specific budgets below
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Status polls run often and should fail fast so the UI doesn't hang
+pub const STATUS_TIMEOUT: Duration = Duration::from_secs(10);
+/// Punches get a little longer before we give up and queue them offline
+pub const PUNCH_TIMEOUT: Duration = Duration::from_secs(20);
+/// Login code exchange and token validation
+pub const AUTH_TIMEOUT: Duration = Duration::from_secs(15);
+/// Streamed responses are reopened after this long, so a stale connection can't linger
+pub const STREAM_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl From<Method> for reqwest::Method {
+    fn from(method: Method) -> Self {
+        match method {
+            Method::Get => reqwest::Method::GET,
+            Method::Post => reqwest::Method::POST,
+            Method::Put => reqwest::Method::PUT,
+            Method::Patch => reqwest::Method::PATCH,
+            Method::Delete => reqwest::Method::DELETE,
+        }
+    }
+}
+
+/// Errors raised by the client itself rather than returned by the webapp
+#[derive(Debug)]
+pub enum ApiError {
+    NotConfigured,
+    NotAuthenticated,
+    /// The user or an admin revoked this device; the session is no longer valid
+    DeviceRevoked,
+    Network(String),
+    /// Sent, but the answer never arrived; the webapp may or may not have applied it
+    UnknownOutcome(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotConfigured => write!(f, "Webapp URL not configured"),
+            Self::NotAuthenticated => write!(f, "Not authenticated"),
+            Self::DeviceRevoked => write!(f, "This device was signed out remotely"),
+            Self::Network(message) => write!(f, "network error: {}", message),
+            Self::UnknownOutcome(message) => {
+                write!(f, "no answer from the webapp: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Returns true when the request never reached the webapp, so the action can be queued offline
+pub fn is_network_error(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<ApiError>(), Some(ApiError::Network(_)))
+}
+
+/// Returns true when a write was sent but not answered. Queueing it could apply it
+/// twice, so callers ask the webapp what happened instead.
+pub fn is_unknown_outcome(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<ApiError>(),
+        Some(ApiError::UnknownOutcome(_))
+    )
+}
+
+/// A request relative to the webapp base URL
+#[derive(Debug, Clone)]
+pub struct ApiRequest {
+    method: Method,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Option<serde_json::Value>,
+    timeout: Duration,
+    authenticated: bool,
+}
+
+impl ApiRequest {
+    pub fn new(method: Method, path: &str) -> Self {
+        Self {
+            method,
+            path: path.to_string(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+            timeout: DEFAULT_TIMEOUT,
+            authenticated: true,
+        }
+    }
+
+    pub fn get(path: &str) -> Self {
+        Self::new(Method::Get, path)
+    }
+
+    pub fn post(path: &str, body: serde_json::Value) -> Self {
+        Self::new(Method::Post, path).json(body)
+    }
+
+    pub fn query(mut self, key: &str, value: &str) -> Self {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn json(mut self, body: serde_json::Value) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sends the request without the session bearer token (e.g. the login code exchange)
+    pub fn unauthenticated(mut self) -> Self {
+        self.authenticated = false;
+        self
+    }
+}
+
+/// A fully resolved request as handed to the transport
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<serde_json::Value>,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl ApiResponse {
+    fn is_device_revoked(&self) -> bool {
+        matches!(self.status, 401 | 403)
+            && serde_json::from_str::<serde_json::Value>(&self.body)
+                .ok()
+                .is_some_and(|body| body["code"] == DEVICE_REVOKED_CODE)
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_str(&self.body)?)
+    }
+}
+
+/// A response whose body arrives over time, e.g. server-sent events
+pub struct ApiStream {
+    pub status: u16,
+    chunks: mpsc::Receiver<Result<Vec<u8>>>,
+}
+
+impl ApiStream {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The next piece of the body; `None` once the server closed it
+    pub async fn next_chunk(&mut self) -> Option<Result<Vec<u8>>> {
+        self.chunks.recv().await
+    }
+
+    async fn read_to_end(&mut self) -> Result<String> {
+        let mut body = Vec::new();
+        while let Some(chunk) = self.next_chunk().await {
+            body.extend(chunk?);
+        }
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+}
+
+/// Sends resolved requests over the wire. Swapped for a fake in tests.
+pub trait ApiTransport: Send + Sync {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<ApiResponse>>;
+
+    /// Like `send`, but hands the body over as it arrives
+    fn open_stream(&self, request: TransportRequest) -> BoxFuture<'_, Result<ApiStream>>;
+}
+
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(network: &NetworkConfig) -> Result<Self> {
+        let builder = reqwest::Client::builder()
+            .user_agent(user_agent())
+            .connect_timeout(CONNECT_TIMEOUT)
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+            .timeout(DEFAULT_TIMEOUT);
+
+        let client = network.apply(builder)?.build()?;
+
+        Ok(Self { client })
+    }
+
+    fn builder(&self, request: &TransportRequest) -> reqwest::RequestBuilder {
+        let mut builder = self
+            .client
+            .request(request.method.into(), &request.url)
+            .timeout(request.timeout);
+
+        for (name, value) in &request.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+
+        if let Some(body) = &request.body {
+            builder = builder.json(body);
+        }
+
+        builder
+    }
+}
+
+impl ApiTransport for ReqwestTransport {
+    fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<ApiResponse>> {
+        Box::pin(async move {
+            let method = request.method;
+            let response = self
+                .builder(&request)
+                .send()
+                .await
+                .map_err(|e| classify_reqwest_error(e, method))?;
+            let status = response.status().as_u16();
+            let body = response
+                .text()
+                .await
+                .map_err(|e| classify_reqwest_error(e, method))?;
+
+            Ok(ApiResponse { status, body })
+        })
+    }
+
+    fn open_stream(&self, request: TransportRequest) -> BoxFuture<'_, Result<ApiStream>> {
+        Box::pin(async move {
+            let method = request.method;
+            let mut response = self
+                .builder(&request)
+                .send()
+                .await
+                .map_err(|e| classify_reqwest_error(e, method))?;
+            let status = response.status().as_u16();
+
+            // Dropping the stream drops the receiver, which ends the reader
+            let (sender, chunks) = mpsc::channel(16);
+            tokio::spawn(async move {
+                loop {
+                    let chunk = match response.chunk().await {
+                        Ok(Some(chunk)) => Ok(chunk.to_vec()),
+                        Ok(None) => break,
+                        Err(e) => Err(classify_reqwest_error(e, method)),
+                    };
+                    let failed = chunk.is_err();
+                    if sender.send(chunk).await.is_err() || failed {
+                        break;
+                    }
+                }
+            });
+
+            Ok(ApiStream { status, chunks })
+        })
+    }
+}
+
+/// Failing to connect means nothing was sent. A timeout or broken response after
+/// that is harmless for a read, but a write may already have been applied.
+fn classify_reqwest_error(error: reqwest::Error, method: Method) -> anyhow::Error {
+    let unanswered = error.is_timeout() || error.is_request() || error.is_body();
+    if error.is_connect() || (unanswered && method == Method::Get) {
+        ApiError::Network(error_chain(&error)).into()
+    } else if unanswered {
+        ApiError::UnknownOutcome(error_chain(&error)).into()
+    } else {
+        error.into()
+    }
+}
+
+/// Joins an error with all of its sources; reqwest hides the TLS or proxy cause otherwise
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+
+    message
+}
+
+pub fn user_agent() -> String {
+    format!(
+        "z8-timer/{} ({}; {})",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+}
+
+type RevokedCallback = Arc<dyn Fn() + Send + Sync>;
+
+struct ApiClientInner {
+    base_url: RwLock<String>,
+    session_token: RwLock<Option<String>>,
+    device_id: RwLock<Option<String>>,
+    transport: RwLock<Arc<dyn ApiTransport>>,
+    on_device_revoked: RwLock<Option<RevokedCallback>>,
+}
+
+/// Shared client for every call to the webapp.
+///
+/// Owns the base URL, the bearer token and the pooled transport. Clones share
+/// the same state, so a login or settings change is seen by every holder.
+#[derive(Clone)]
+pub struct Z8ApiClient {
+    inner: Arc<ApiClientInner>,
+}
+
+impl Z8ApiClient {
+    pub fn new(
+        base_url: &str,
+        session_token: Option<String>,
+        network: &NetworkConfig,
+    ) -> Result<Self> {
+        Ok(Self::with_transport(
+            base_url,
+            session_token,
+            Arc::new(ReqwestTransport::new(network)?),
+        ))
+    }
+
+    pub fn with_transport(
+        base_url: &str,
+        session_token: Option<String>,
+        transport: Arc<dyn ApiTransport>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(ApiClientInner {
+                base_url: RwLock::new(base_url.to_string()),
+                session_token: RwLock::new(session_token),
+                device_id: RwLock::new(None),
+                transport: RwLock::new(transport),
+                on_device_revoked: RwLock::new(None),
+            }),
+        }
+    }
+
+    /// Returns a detached client that uses `session_token` instead of the shared one.
+    /// Used to validate a fresh token before it is stored.
+    pub fn with_session_token(&self, session_token: String) -> Self {
+        Self::with_transport(
+            &self.base_url(),
+            Some(session_token),
+            self.inner.transport.read().clone(),
+        )
+    }
+
+    /// Rebuilds the transport after proxy or certificate settings change.
+    /// Requests already in flight finish on the old connection pool.
+    pub fn reconfigure(&self, network: &NetworkConfig) -> Result<()> {
+        let transport = ReqwestTransport::new(network)?;
+        *self.inner.transport.write() = Arc::new(transport);
+        Ok(())
+    }
+
+    pub fn base_url(&self) -> String {
+        self.inner.base_url.read().clone()
+    }
+
+    pub fn set_base_url(&self, base_url: &str) {
+        *self.inner.base_url.write() = base_url.to_string();
+    }
+
+    pub fn session_token(&self) -> Option<String> {
+        self.inner.session_token.read().clone()
+    }
+
+    pub fn set_session_token(&self, session_token: Option<String>) {
+        *self.inner.session_token.write() = session_token;
+    }
+
+    pub fn device_id(&self) -> Option<String> {
+        self.inner.device_id.read().clone()
+    }
+
+    pub fn set_device_id(&self, device_id: Option<String>) {
+        *self.inner.device_id.write() = device_id;
+    }
+
+    /// Runs `callback` when the webapp rejects a request because the device was revoked
+    pub fn on_device_revoked(&self, callback: impl Fn() + Send + Sync + 'static) {
+        *self.inner.on_device_revoked.write() = Some(Arc::new(callback));
+    }
+
+    fn resolve(&self, request: ApiRequest) -> Result<TransportRequest> {
+        let base_url = self.base_url();
+        if base_url.is_empty() {
+            return Err(ApiError::NotConfigured.into());
+        }
+
+        let mut url = url::Url::parse(&format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            request.path.trim_start_matches('/')
+        ))?;
+
+        if !request.query.is_empty() {
+            url.query_pairs_mut().extend_pairs(&request.query);
+        }
+
+        let mut headers = vec![(
+            APP_TYPE_HEADER_NAME.to_string(),
+            DESKTOP_APP_TYPE.to_string(),
+        )];
+
+        if request.authenticated {
+            let token = self.session_token().ok_or(ApiError::NotAuthenticated)?;
+            headers.push(("Authorization".to_string(), format!("Bearer {}", token)));
+            if let Some(device_id) = self.device_id() {
+                headers.push((DEVICE_ID_HEADER_NAME.to_string(), device_id));
+            }
+        }
+
+        headers.extend(request.headers);
+
+        Ok(TransportRequest {
+            method: request.method,
+            url: url.to_string(),
+            headers,
+            body: request.body,
+            timeout: request.timeout,
+        })
+    }
+
+    /// Sends a request and returns the raw response, whatever its status
+    pub async fn send(&self, request: ApiRequest) -> Result<ApiResponse> {
+        let request = self.resolve(request)?;
+        let transport = self.inner.transport.read().clone();
+        let response = transport.send(request).await?;
+
+        self.check_device_revoked(&response)?;
+        Ok(response)
+    }
+
+    /// Opens a streamed response. The body of an error status is read up front, so
+    /// the returned stream only has chunks on success.
+    pub async fn open_stream(&self, request: ApiRequest) -> Result<ApiStream> {
+        let request = self.resolve(request)?;
+        let transport = self.inner.transport.read().clone();
+        let mut stream = transport.open_stream(request).await?;
+
+        if !stream.is_success() {
+            let response = ApiResponse {
+                status: stream.status,
+                body: stream.read_to_end().await?,
+            };
+            self.check_device_revoked(&response)?;
+        }
+
+        Ok(stream)
+    }
+
+    fn check_device_revoked(&self, response: &ApiResponse) -> Result<()> {
+        if response.is_device_revoked() {
+            let callback = self.inner.on_device_revoked.read().clone();
+            if let Some(callback) = callback {
+                callback();
+            }
+            return Err(ApiError::DeviceRevoked.into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::{
+        ApiError, ApiResponse, ApiStream, ApiTransport, BoxFuture, TransportRequest, Z8ApiClient,
+    };
+    use anyhow::Result;
+    use parking_lot::Mutex;
+    use std::collections::VecDeque;
+    use std::sync::Arc;
+
+    impl TransportRequest {
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// Records every request and replays canned responses in order
+    #[derive(Default)]
+    pub struct FakeTransport {
+        responses: Mutex<VecDeque<std::result::Result<ApiResponse, ApiError>>>,
+        requests: Mutex<Vec<TransportRequest>>,
+    }
+
+    impl FakeTransport {
+        pub fn respond(&self, status: u16, body: serde_json::Value) {
+            self.responses.lock().push_back(Ok(ApiResponse {
+                status,
+                body: body.to_string(),
+            }));
+        }
+
+        /// Queues a response with a body that isn't JSON, e.g. server-sent events
+        pub fn respond_text(&self, status: u16, body: &str) {
+            self.responses.lock().push_back(Ok(ApiResponse {
+                status,
+                body: body.to_string(),
+            }));
+        }
+
+        pub fn fail_network(&self) {
+            let error = ApiError::Network("connection refused".to_string());
+            self.responses.lock().push_back(Err(error));
+        }
+
+        /// Fails like a request that was sent but timed out waiting for the answer
+        pub fn fail_unanswered(&self) {
+            let error = ApiError::UnknownOutcome("operation timed out".to_string());
+            self.responses.lock().push_back(Err(error));
+        }
+
+        pub fn requests(&self) -> Vec<TransportRequest> {
+            self.requests.lock().clone()
+        }
+    }
+
+    impl ApiTransport for FakeTransport {
+        fn send(&self, request: TransportRequest) -> BoxFuture<'_, Result<ApiResponse>> {
+            self.requests.lock().push(request);
+            let response = self.responses.lock().pop_front();
+
+            Box::pin(async move {
+                match response {
+                    Some(Ok(response)) => Ok(response),
+                    Some(Err(error)) => Err(error.into()),
+                    None => Err(anyhow::anyhow!("FakeTransport has no response queued")),
+                }
+            })
+        }
+
+        /// Streams the next canned response's body as a single chunk
+        fn open_stream(&self, request: TransportRequest) -> BoxFuture<'_, Result<ApiStream>> {
+            Box::pin(async move {
+                let response = self.send(request).await?;
+                let (sender, chunks) = tokio::sync::mpsc::channel(1);
+                let _ = sender.try_send(Ok(response.body.into_bytes()));
+
+                Ok(ApiStream {
+                    status: response.status,
+                    chunks,
+                })
+            })
+        }
+    }
+
+    pub fn fake_client() -> (Z8ApiClient, Arc<FakeTransport>) {
+        let transport = Arc::new(FakeTransport::default());
+        let client = Z8ApiClient::with_transport(
+            "https://z8.example/",
+            Some("session-token".to_string()),
+            transport.clone(),
+        );
+        (client, transport)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::fake_client;
+    use super::{
+        is_network_error, is_unknown_outcome, ApiRequest, Method, Z8ApiClient, AUTH_TIMEOUT,
+    };
+    use crate::network::NetworkConfig;
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn resolves_url_headers_and_timeout() {
+        let (client, transport) = fake_client();
+        transport.respond(200, serde_json::json!({ "ok": true }));
+
+        let request = ApiRequest::get("/api/time-entries")
+            .header("Idempotency-Key", "key-1")
+            .timeout(AUTH_TIMEOUT);
+        let response = client.send(request).await.unwrap();
+        assert!(response.is_success());
+
+        let sent = &transport.requests()[0];
+        assert_eq!(sent.method, Method::Get);
+        assert_eq!(sent.url, "https://z8.example/api/time-entries");
+        assert_eq!(sent.header("x-z8-app-type"), Some("desktop"));
+        assert_eq!(sent.header("authorization"), Some("Bearer session-token"));
+        assert_eq!(sent.header("idempotency-key"), Some("key-1"));
+        assert_eq!(sent.timeout, AUTH_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn omits_bearer_for_unauthenticated_requests_and_requires_it_otherwise() {
+        let (client, transport) = fake_client();
+        client.set_session_token(None);
+        transport.respond(200, serde_json::json!({}));
+
+        client
+            .send(
+                ApiRequest::post("/api/auth/app-exchange", serde_json::json!({})).unauthenticated(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(transport.requests()[0].header("authorization"), None);
+
+        let error = client
+            .send(ApiRequest::get("/api/time-entries/status"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Not authenticated");
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn flags_transport_failures_as_network_errors() {
+        let (client, transport) = fake_client();
+        transport.fail_network();
+        transport.respond(500, serde_json::json!({ "error": "boom" }));
+
+        let network = client
+            .send(ApiRequest::get("/api/time-entries/status"))
+            .await
+            .unwrap_err();
+        assert!(is_network_error(&network));
+
+        let server = client
+            .send(ApiRequest::get("/api/time-entries/status"))
+            .await
+            .unwrap();
+        assert_eq!(server.status, 500);
+        assert!(!server.is_success());
+    }
+
+    #[tokio::test]
+    async fn unanswered_writes_are_not_reported_as_offline() {
+        // Accepts connections but never answers, like a webapp stuck after committing
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let silent = format!("http://{}", listener.local_addr().unwrap());
+        let closed = {
+            let unused = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", unused.local_addr().unwrap())
+        };
+
+        let request = |method| {
+            ApiRequest::new(method, "/api/time-entries")
+                .unauthenticated()
+                .timeout(Duration::from_millis(300))
+        };
+        let client = Z8ApiClient::new(&silent, None, &NetworkConfig::default()).unwrap();
+
+        let write = client.send(request(Method::Post)).await.unwrap_err();
+        assert!(is_unknown_outcome(&write));
+        assert!(!is_network_error(&write));
+
+        let read = client.send(request(Method::Get)).await.unwrap_err();
+        assert!(is_network_error(&read));
+
+        client.set_base_url(&closed);
+        let refused = client.send(request(Method::Post)).await.unwrap_err();
+        assert!(is_network_error(&refused));
+        drop(listener);
+    }
+
+    #[tokio::test]
+    async fn sends_the_device_id_and_reports_revocation() {
+        let (client, transport) = fake_client();
+        let revoked = Arc::new(AtomicBool::new(false));
+        client.on_device_revoked({
+            let revoked = revoked.clone();
+            move || revoked.store(true, Ordering::SeqCst)
+        });
+        client.set_device_id(Some("device-1".to_string()));
+
+        transport.respond(200, serde_json::json!({}));
+        client
+            .send(ApiRequest::get("/api/time-entries/status"))
+            .await
+            .unwrap();
+        assert_eq!(
+            transport.requests()[0].header("x-z8-device-id"),
+            Some("device-1")
+        );
+
+        // A plain 401 is an expired session, not a revoked device
+        transport.respond(401, serde_json::json!({ "error": "Unauthorized" }));
+        client
+            .send(ApiRequest::get("/api/time-entries/status"))
+            .await
+            .unwrap();
+        assert!(!revoked.load(Ordering::SeqCst));
+
+        transport.respond(
+            403,
+            serde_json::json!({ "error": "Device revoked", "code": "device_revoked" }),
+        );
+        let error = client
+            .send(ApiRequest::get("/api/time-entries/status"))
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "This device was signed out remotely");
+        assert!(revoked.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,437 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::api::{ApiRequest, Z8ApiClient};
+use crate::notify;
+use crate::state::AppState;
+use crate::tray;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(2 * 60);
+/// Pending items fetched for the tray and for new-request notifications
+const INBOX_PAGE_SIZE: usize = 20;
+/// More new requests than this are announced with a single summary notification
+const MAX_INDIVIDUAL_NOTIFICATIONS: usize = 3;
+
+/// Tray menu ids for the per-item quick actions; the approval id follows the prefix
+pub const MENU_APPROVE_PREFIX: &str = "approval_approve:";
+pub const MENU_REJECT_PREFIX: &str = "approval_reject:";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRequester {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    pub image: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalDisplay {
+    pub title: String,
+    pub subtitle: String,
+    pub summary: String,
+}
+
+/// A request waiting in the manager's approval inbox
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalItem {
+    pub id: String,
+    /// e.g. `absence_entry`, `time_entry`, `shift_request`
+    pub approval_type: String,
+    pub entity_id: String,
+    pub type_name: String,
+    pub requester: ApprovalRequester,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub priority: String,
+    pub display: ApprovalDisplay,
+    /// `false` when the request needs reconciliation in the webapp before a decision
+    #[serde(default)]
+    pub is_actionable: Option<bool>,
+    #[serde(default)]
+    pub warning: Option<String>,
+}
+
+impl ApprovalItem {
+    /// Short label used in the tray and in notifications
+    pub fn label(&self) -> String {
+        format!(
+            "{}: {} ({})",
+            self.requester.name, self.display.title, self.display.subtitle
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalPage {
+    pub items: Vec<ApprovalItem>,
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+    pub total: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkDecisionSuccess {
+    pub id: String,
+    pub approval_type: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkDecisionFailure {
+    pub id: String,
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkDecisionResult {
+    pub succeeded: Vec<BulkDecisionSuccess>,
+    pub failed: Vec<BulkDecisionFailure>,
+}
+
+pub struct ApprovalService {
+    api: Z8ApiClient,
+}
+
+impl ApprovalService {
+    pub fn new(api: Z8ApiClient) -> Self {
+        Self { api }
+    }
+
+    /// Pending requests per approval type; `None` when the user can't approve anything
+    pub async fn counts(&self) -> Result<Option<HashMap<String, u32>>> {
+        let response = self
+            .api
+            .send(ApiRequest::get("/api/approvals/inbox/counts"))
+            .await?;
+
+        if response.status == 403 {
+            return Ok(None);
+        }
+        if !response.is_success() {
+            return Err(anyhow!(
+                "Failed to fetch approval counts: {}",
+                response.status
+            ));
+        }
+
+        Ok(Some(response.json()?))
+    }
+
+    /// Lists pending requests, oldest SLA first as sorted by the webapp
+    pub async fn list(&self, cursor: Option<&str>, limit: usize) -> Result<ApprovalPage> {
+        let mut request = ApiRequest::get("/api/approvals/inbox")
+            .query("status", "pending")
+            .query("limit", &limit.to_string());
+        if let Some(cursor) = cursor {
+            request = request.query("cursor", cursor);
+        }
+
+        let response = self.api.send(request).await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Failed to fetch approvals: {}", response.status));
+        }
+
+        response.json()
+    }
+
+    pub async fn approve(&self, approval_id: &str) -> Result<()> {
+        let path = format!("/api/approvals/inbox/{}/approve", approval_id);
+        let response = self
+            .api
+            .send(ApiRequest::post(&path, serde_json::json!({})))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Approval failed: {}", response.body));
+        }
+
+        Ok(())
+    }
+
+    pub async fn reject(&self, approval_id: &str, reason: &str) -> Result<()> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            bail!("A reason is required to reject a request");
+        }
+
+        let path = format!("/api/approvals/inbox/{}/reject", approval_id);
+        let response = self
+            .api
+            .send(ApiRequest::post(
+                &path,
+                serde_json::json!({ "reason": reason }),
+            ))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Rejection failed: {}", response.body));
+        }
+
+        Ok(())
+    }
+
+    pub async fn bulk_approve(&self, approval_ids: &[String]) -> Result<BulkDecisionResult> {
+        let response = self
+            .api
+            .send(ApiRequest::post(
+                "/api/approvals/inbox/bulk-approve",
+                serde_json::json!({ "approvalIds": approval_ids }),
+            ))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Bulk approval failed: {}", response.body));
+        }
+
+        response.json()
+    }
+
+    pub async fn bulk_reject(
+        &self,
+        approval_ids: &[String],
+        reason: &str,
+    ) -> Result<BulkDecisionResult> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            bail!("A reason is required to reject requests");
+        }
+
+        let response = self
+            .api
+            .send(ApiRequest::post(
+                "/api/approvals/inbox/bulk-reject",
+                serde_json::json!({ "approvalIds": approval_ids, "reason": reason }),
+            ))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Bulk rejection failed: {}", response.body));
+        }
+
+        response.json()
+    }
+}
+
+/// Requests not seen on an earlier check
+fn new_items<'a>(seen: &HashSet<String>, items: &'a [ApprovalItem]) -> Vec<&'a ApprovalItem> {
+    items
+        .iter()
+        .filter(|item| !seen.contains(&item.id))
+        .collect()
+}
+
+/// Re-reads the pending inbox and mirrors it in the tray menu
+pub async fn refresh_tray(app_handle: &AppHandle) -> Result<Vec<ApprovalItem>> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let service = ApprovalService::new(state.api.clone());
+    let page = service.list(None, INBOX_PAGE_SIZE).await?;
+
+    tray::update_approvals(app_handle, page.total, &page.items)?;
+    let _ = app_handle.emit("approvals_updated", page.total);
+    Ok(page.items)
+}
+
+/// Handles the approve and reject entries of the tray's approvals submenu
+pub fn handle_menu_event(app_handle: &AppHandle, menu_id: &str) {
+    if let Some(approval_id) = menu_id.strip_prefix(MENU_REJECT_PREFIX) {
+        // Rejections need a reason, so they are finished in the window
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        let _ = app_handle.emit("open_approval_reject", approval_id);
+        return;
+    }
+
+    let Some(approval_id) = menu_id.strip_prefix(MENU_APPROVE_PREFIX) else {
+        return;
+    };
+
+    let app_handle = app_handle.clone();
+    let approval_id = approval_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<Arc<AppState>>();
+        let service = ApprovalService::new(state.api.clone());
+
+        match service.approve(&approval_id).await {
+            Ok(()) => {
+                notify::send(&app_handle, "Request approved", "The request was approved.");
+                let _ = app_handle.emit("approval_decided", &approval_id);
+            }
+            Err(e) => {
+                log::error!("Failed to approve {} from tray: {}", approval_id, e);
+                notify::send(&app_handle, "Approval failed", &e.to_string());
+            }
+        }
+
+        if let Err(e) = refresh_tray(&app_handle).await {
+            log::warn!("Failed to refresh approvals: {}", e);
+        }
+    });
+}
+
+/// Polls the approval inbox counts, keeps the tray badge current and
+/// notifies about requests that arrived since the last check
+pub async fn start_approval_watcher(app_handle: AppHandle) {
+    log::info!("Starting approval watcher");
+
+    let state = app_handle.state::<Arc<AppState>>();
+    let service = ApprovalService::new(state.api.clone());
+
+    let mut last_total: Option<u32> = None;
+    let mut seen: Option<HashSet<String>> = None;
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        if state.get_session_token().is_none() || state.get_webapp_url().is_empty() {
+            continue;
+        }
+
+        let counts = match service.counts().await {
+            Ok(Some(counts)) => counts,
+            Ok(None) => {
+                // Not an approver in this organization
+                if last_total.take().is_some() {
+                    let _ = tray::update_approvals(&app_handle, 0, &[]);
+                }
+                seen = None;
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Failed to check approval counts: {}", e);
+                continue;
+            }
+        };
+
+        let total: u32 = counts.values().sum();
+        if last_total == Some(total) {
+            continue;
+        }
+
+        let items = match refresh_tray(&app_handle).await {
+            Ok(items) => items,
+            Err(e) => {
+                log::warn!("Failed to refresh approvals: {}", e);
+                continue;
+            }
+        };
+        last_total = Some(total);
+
+        // The first check only records what is already waiting
+        if let Some(seen) = &seen {
+            let arrived = new_items(seen, &items);
+            if arrived.len() > MAX_INDIVIDUAL_NOTIFICATIONS {
+                notify::send(
+                    &app_handle,
+                    "New approval requests",
+                    &format!("{} new requests are waiting for you.", arrived.len()),
+                );
+            } else {
+                for item in &arrived {
+                    notify::send(&app_handle, "New approval request", &item.label());
+                }
+            }
+            for item in arrived {
+                let _ = app_handle.emit("approval_requested", item);
+            }
+        }
+
+        seen = Some(items.into_iter().map(|item| item.id).collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{new_items, ApprovalItem, ApprovalService};
+    use crate::api::testing::fake_client;
+    use std::collections::HashSet;
+
+    fn item(id: &str) -> ApprovalItem {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "approvalType": "absence_entry",
+            "entityId": format!("entity-{}", id),
+            "typeName": "Absence",
+            "requester": {
+                "id": "emp-1",
+                "userId": "user-1",
+                "name": "Alex Doe",
+                "email": "alex@example.com",
+                "image": null,
+                "teamId": null
+            },
+            "approverId": "emp-2",
+            "organizationId": "org-1",
+            "status": "pending",
+            "createdAt": "2026-10-14T08:00:00.000Z",
+            "resolvedAt": null,
+            "priority": "normal",
+            "sla": { "deadline": null, "status": "on_time", "hoursRemaining": null },
+            "display": {
+                "title": "Vacation",
+                "subtitle": "Oct 19-21, 2026",
+                "summary": "3 days"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn finds_requests_that_arrived_since_the_last_check() {
+        let items = [item("a"), item("b")];
+        assert_eq!(items[0].label(), "Alex Doe: Vacation (Oct 19-21, 2026)");
+
+        let seen: HashSet<String> = HashSet::from(["a".to_string()]);
+        let arrived: Vec<&str> = new_items(&seen, &items)
+            .into_iter()
+            .map(|item| item.id.as_str())
+            .collect();
+        assert_eq!(arrived, vec!["b"]);
+    }
+
+    #[tokio::test]
+    async fn rejects_require_a_reason_and_forbidden_counts_mean_no_inbox() {
+        let (client, transport) = fake_client();
+        let service = ApprovalService::new(client);
+
+        assert!(service.reject("a", "  ").await.is_err());
+        assert!(transport.requests().is_empty());
+
+        transport.respond(403, serde_json::json!({ "error": "Forbidden" }));
+        assert_eq!(service.counts().await.unwrap(), None);
+
+        transport.respond(
+            200,
+            serde_json::json!({ "succeeded": [], "failed": [
+                { "id": "a", "code": "stale", "message": "Already decided" }
+            ] }),
+        );
+        let result = service
+            .bulk_reject(&["a".to_string()], "Overlaps with team event")
+            .await
+            .unwrap();
+        assert_eq!(result.failed[0].code, "stale");
+        assert_eq!(
+            transport.requests()[1].body,
+            Some(serde_json::json!({
+                "approvalIds": ["a"],
+                "reason": "Overlaps with team event"
+            }))
+        );
+    }
+}
@@ -1,17 +1,21 @@
 use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use rand::RngCore;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 use url::Url;
 
+use crate::api::{
+    is_network_error, ApiRequest, Z8ApiClient, AUTH_TIMEOUT, DESKTOP_APP_TYPE, STATUS_TIMEOUT,
+};
+use crate::clock::ClockService;
+use crate::device::DeviceRegistration;
+use crate::notify;
 use crate::state::AppState;
 use crate::tray;
 
-const APP_TYPE_HEADER_NAME: &str = "X-Z8-App-Type";
-const DESKTOP_APP_TYPE: &str = "desktop";
 const DESKTOP_CALLBACK_URL: &str = "z8://auth/callback";
 const PKCE_VERIFIER_BYTE_LENGTH: usize = 32;
 
@@ -27,15 +31,40 @@ enum CallbackResult {
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct AppExchangeResponse {
     token: String,
+    /// Missing when the webapp doesn't register devices yet
+    #[serde(default)]
+    device_id: Option<String>,
+}
+
+/// The signed-in user as the window shows it. The session token stays in Rust.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub email: String,
+    #[serde(default)]
+    pub image: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SessionPayload {
+    user: Profile,
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        build_desktop_login_url, exchange_app_callback_code, parse_callback_result, CallbackResult,
+        build_desktop_login_url, exchange_app_callback_code, fetch_profile, parse_callback_result,
+        CallbackResult,
     };
+    use crate::api::testing::fake_client;
+    use crate::api::Z8ApiClient;
+    use crate::device::DeviceRegistration;
+    use crate::network::NetworkConfig;
     use std::io::{Read, Write};
     use std::net::TcpListener;
     use std::thread;
@@ -71,6 +100,29 @@ mod tests {
         assert_eq!(parse_callback_result(&url), None);
     }
 
+    #[tokio::test]
+    async fn reads_the_profile_from_the_session() {
+        let (client, transport) = fake_client();
+        transport.respond(
+            200,
+            serde_json::json!({
+                "session": { "id": "session-1", "token": "session-token" },
+                "user": { "id": "user-1", "name": "Ada", "email": "ada@example.com", "image": null },
+            }),
+        );
+        transport.respond(200, serde_json::Value::Null);
+
+        let profile = fetch_profile(&client).await.unwrap();
+        assert_eq!(profile.email, "ada@example.com");
+        assert_eq!(
+            transport.requests()[0].url,
+            "https://z8.example/api/auth/get-session"
+        );
+
+        // An expired session comes back as `null`
+        assert!(fetch_profile(&client).await.is_err());
+    }
+
     #[test]
     fn builds_desktop_app_login_url_with_pkce_challenge() {
         let url = build_desktop_login_url("https://ui.z8-time.app/", "CODE-CHALLENGE")
@@ -96,26 +148,44 @@ mod tests {
             assert!(request.starts_with("POST /api/auth/app-exchange HTTP/1.1\r\n"));
             assert!(request.contains("x-z8-app-type: desktop\r\n"));
             assert!(request.contains("content-type: application/json\r\n"));
-            assert!(request.contains("{\"code\":\"ONE-TIME-CODE\",\"verifier\":\"CODE-VERIFIER\"}"));
-
+            let (_, body) = request.split_once("\r\n\r\n").expect("request body");
+            let body: serde_json::Value = serde_json::from_str(body).expect("json body");
+            assert_eq!(body["code"], "ONE-TIME-CODE");
+            assert_eq!(body["verifier"], "CODE-VERIFIER");
+            assert_eq!(body["device"]["publicKey"], "PUBLIC-KEY");
+            assert_eq!(body["device"]["signature"], "SIGNATURE");
+
+            let response = r#"{"token":"session-token","deviceId":"device-1"}"#;
             stream
                 .write_all(
-                    b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 25\r\nconnection: close\r\n\r\n{\"token\":\"session-token\"}",
+                    format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        response.len(),
+                        response
+                    )
+                    .as_bytes(),
                 )
                 .expect("write response");
         });
 
-        let client = reqwest::Client::new();
-        let token = exchange_app_callback_code(
-            &client,
+        let api = Z8ApiClient::new(
             &format!("http://{}", address),
-            "ONE-TIME-CODE",
-            "CODE-VERIFIER",
+            None,
+            &NetworkConfig::default(),
         )
-        .await
-        .expect("exchange should succeed");
-
-        assert_eq!(token, "session-token");
+        .expect("build api client");
+        let device = DeviceRegistration {
+            public_key: "PUBLIC-KEY".to_string(),
+            signature: "SIGNATURE".to_string(),
+            name: "Desk".to_string(),
+            platform: "linux".to_string(),
+        };
+        let exchange = exchange_app_callback_code(&api, "ONE-TIME-CODE", "CODE-VERIFIER", &device)
+            .await
+            .expect("exchange should succeed");
+
+        assert_eq!(exchange.token, "session-token");
+        assert_eq!(exchange.device_id.as_deref(), Some("device-1"));
         server.join().expect("server should complete");
     }
 }
@@ -131,7 +201,6 @@ pub async fn handle_deep_link_callback(app_handle: &AppHandle, url: &Url) -> Res
                 return Err(anyhow!("Webapp URL not configured"));
             }
 
-            let client = reqwest::Client::new();
             let verifier = match state.take_pending_app_auth_verifier() {
                 Some(verifier) => verifier,
                 None => {
@@ -141,14 +210,15 @@ pub async fn handle_deep_link_callback(app_handle: &AppHandle, url: &Url) -> Res
                 }
             };
 
-            let token = exchange_app_callback_code(&client, &webapp_url, &code, &verifier)
+            let device = state.device_key.registration(&verifier);
+            let exchange = exchange_app_callback_code(&state.api, &code, &verifier, &device)
                 .await
                 .map_err(|error| {
                     let _ = app_handle.emit("auth_error", error.to_string());
                     error
                 })?;
 
-            handle_oauth_callback(app_handle, token).await
+            handle_oauth_callback(app_handle, exchange.token, exchange.device_id).await
         }
         Some(CallbackResult::Error(message)) => {
             log::error!("OAuth callback error: {}", message);
@@ -237,39 +307,59 @@ fn parse_callback_result(url: &Url) -> Option<CallbackResult> {
     None
 }
 
+/// Trades the one-time code for a session token and registers the device key
 async fn exchange_app_callback_code(
-    client: &reqwest::Client,
-    webapp_url: &str,
+    api: &Z8ApiClient,
     code: &str,
     verifier: &str,
-) -> Result<String> {
-    let response = client
-        .post(format!(
-            "{}/api/auth/app-exchange",
-            webapp_url.trim_end_matches('/')
-        ))
-        .header(APP_TYPE_HEADER_NAME, DESKTOP_APP_TYPE)
-        .json(&serde_json::json!({ "code": code, "verifier": verifier }))
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        log::error!("Code exchange failed: {}", response.status());
+    device: &DeviceRegistration,
+) -> Result<AppExchangeResponse> {
+    let request = ApiRequest::post(
+        "/api/auth/app-exchange",
+        serde_json::json!({ "code": code, "verifier": verifier, "device": device }),
+    )
+    .unauthenticated()
+    .timeout(AUTH_TIMEOUT);
+
+    let response = api.send(request).await?;
+
+    if !response.is_success() {
+        log::error!("Code exchange failed: {}", response.status);
         return Err(anyhow!("Code exchange failed"));
     }
 
-    let payload: AppExchangeResponse = response.json().await?;
+    let payload: AppExchangeResponse = response.json()?;
     if payload.token.is_empty() {
         return Err(anyhow!(
             "Code exchange response did not include a session token"
         ));
     }
 
-    Ok(payload.token)
+    Ok(payload)
+}
+
+/// The signed-in user, from the webapp's session endpoint
+pub async fn fetch_profile(api: &Z8ApiClient) -> Result<Profile> {
+    let response = api
+        .send(ApiRequest::get("/api/auth/get-session").timeout(STATUS_TIMEOUT))
+        .await?;
+
+    if !response.is_success() {
+        return Err(anyhow!("Failed to fetch profile: {}", response.status));
+    }
+
+    response
+        .json::<Option<SessionPayload>>()?
+        .map(|session| session.user)
+        .ok_or_else(|| anyhow!("Session not found"))
 }
 
 /// Validates and stores the session token after the browser redirects back.
-pub async fn handle_oauth_callback(app_handle: &AppHandle, token: String) -> Result<()> {
+pub async fn handle_oauth_callback(
+    app_handle: &AppHandle,
+    token: String,
+    device_id: Option<String>,
+) -> Result<()> {
     let state = app_handle.state::<Arc<AppState>>();
 
     // Validate the token by fetching clock status
@@ -279,35 +369,40 @@ pub async fn handle_oauth_callback(app_handle: &AppHandle, token: String) -> Res
     }
 
     // Validate token by making an authenticated request
-    let client = reqwest::Client::new();
-    let response = client
-        .get(format!(
-            "{}/api/time-entries/status",
-            webapp_url.trim_end_matches('/')
-        ))
-        .header("Authorization", format!("Bearer {}", token))
-        .send()
-        .await?;
+    let api = state.api.with_session_token(token.clone());
+    api.set_device_id(device_id.clone());
+    let clock_service = ClockService::new(api.clone());
+
+    match clock_service.get_status().await {
+        Ok(_) => {
+            // Token is valid - now store it
+            state.set_session_token(Some(token));
+            state.set_device_id(device_id);
+
+            // The window gets the profile, never the token
+            match fetch_profile(&api).await {
+                Ok(profile) => state.set_profile(Some(profile)),
+                Err(e) => log::warn!("Failed to fetch profile: {}", e),
+            }
 
-    if response.status().is_success() {
-        // Token is valid - now store it
-        state.set_session_token(Some(token.clone()));
+            // Emit success event to frontend
+            app_handle.emit("auth_success", ())?;
 
-        // Emit success event to frontend
-        app_handle.emit("auth_success", token)?;
+            // Focus the main window
+            if let Some(window) = app_handle.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
 
-        // Focus the main window
-        if let Some(window) = app_handle.get_webview_window("main") {
-            let _ = window.show();
-            let _ = window.set_focus();
+            log::info!("OAuth authentication successful");
+            Ok(())
+        }
+        Err(error) if is_network_error(&error) => Err(error),
+        Err(error) => {
+            log::error!("Token validation failed: {}", error);
+            app_handle.emit("auth_error", "Token validation failed")?;
+            Err(anyhow!("Token validation failed"))
         }
-
-        log::info!("OAuth authentication successful");
-        Ok(())
-    } else {
-        log::error!("Token validation failed: {}", response.status());
-        app_handle.emit("auth_error", "Token validation failed")?;
-        Err(anyhow!("Token validation failed"))
     }
 }
 
@@ -315,6 +410,9 @@ pub async fn handle_oauth_callback(app_handle: &AppHandle, token: String) -> Res
 pub fn logout(app_handle: &AppHandle) -> Result<()> {
     let state = app_handle.state::<Arc<AppState>>();
     state.set_session_token(None);
+    state.set_device_id(None);
+    state.forget_clock_status();
+    state.set_profile(None);
     state.set_clocked_in(false);
 
     // Update tray icon to gray
@@ -326,3 +424,24 @@ pub fn logout(app_handle: &AppHandle) -> Result<()> {
     log::info!("User logged out");
     Ok(())
 }
+
+/// Signs out locally after the webapp rejected this device. Queued punches stay
+/// in the offline queue until the user signs in again.
+pub fn handle_device_revoked(app_handle: &AppHandle) {
+    let state = app_handle.state::<Arc<AppState>>();
+    // Requests in flight may all report the revocation; act once
+    if state.get_session_token().is_none() {
+        return;
+    }
+
+    log::warn!("This device was revoked; signing out");
+    if let Err(e) = logout(app_handle) {
+        log::error!("Failed to sign out revoked device: {}", e);
+    }
+    let _ = app_handle.emit("device_revoked", ());
+    notify::send(
+        app_handle,
+        "z8 Timer",
+        "This device was signed out remotely. Sign in again to keep tracking time.",
+    );
+}
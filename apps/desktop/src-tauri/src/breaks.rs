@@ -0,0 +1,517 @@
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::api::{ApiRequest, Z8ApiClient};
+use crate::clock::{ClockService, TimeEntry};
+use crate::notify;
+use crate::state::AppState;
+use crate::timeline::{self, DayTimeline};
+use crate::timezone;
+
+/// Cache key for the employee's effective break policy in the local store
+pub const BREAK_POLICY_CACHE_KEY: &str = "break_policy";
+/// Cache key for today's server entries, so reminders keep running offline
+const TODAY_ENTRIES_CACHE_KEY: &str = "today_entries";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const POLICY_REFRESH_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const ENTRIES_REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_ALERT_BEFORE_LIMIT_MINUTES: i64 = 15;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakRule {
+    pub working_minutes_threshold: i64,
+    pub required_break_minutes: i64,
+}
+
+/// The break and working-time limits that apply to the signed-in employee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakPolicy {
+    pub id: String,
+    pub name: String,
+    pub max_daily_minutes: Option<i64>,
+    pub max_uninterrupted_minutes: Option<i64>,
+    /// How far ahead of a limit the reminder fires
+    pub alert_before_limit_minutes: i64,
+    pub break_rules: Vec<BreakRule>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkPolicyResponse {
+    policy: Option<WorkPolicy>,
+    /// Minutes the contract or work schedule asks for in the requested week
+    #[serde(default)]
+    weekly_target_minutes: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkPolicy {
+    id: String,
+    name: String,
+    regulation: Option<Regulation>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Regulation {
+    max_daily_minutes: Option<i64>,
+    max_uninterrupted_minutes: Option<i64>,
+    alert_before_limit_minutes: Option<i64>,
+    #[serde(default)]
+    break_rules: Vec<BreakRule>,
+}
+
+impl WorkPolicyResponse {
+    fn into_break_policy(self) -> Option<BreakPolicy> {
+        // A policy without a regulation only covers scheduling, there is nothing to remind about
+        let policy = self.policy?;
+        let regulation = policy.regulation?;
+
+        let mut break_rules = regulation.break_rules;
+        break_rules.sort_by_key(|rule| rule.working_minutes_threshold);
+
+        Some(BreakPolicy {
+            id: policy.id,
+            name: policy.name,
+            max_daily_minutes: regulation.max_daily_minutes,
+            max_uninterrupted_minutes: regulation.max_uninterrupted_minutes,
+            alert_before_limit_minutes: regulation
+                .alert_before_limit_minutes
+                .unwrap_or(DEFAULT_ALERT_BEFORE_LIMIT_MINUTES),
+            break_rules,
+        })
+    }
+}
+
+pub struct BreakPolicyService {
+    api: Z8ApiClient,
+}
+
+impl BreakPolicyService {
+    pub fn new(api: Z8ApiClient) -> Self {
+        Self { api }
+    }
+
+    /// Fetches the effective policy; `None` when no working-time regulation applies
+    pub async fn fetch(&self) -> Result<Option<BreakPolicy>> {
+        let payload = self
+            .work_policy(ApiRequest::get("/api/desktop/work-policy"))
+            .await?;
+        Ok(payload.into_break_policy())
+    }
+
+    /// Minutes the employee's contract or work schedule asks for in the week starting
+    /// on `week_start`, in the device's timezone; `None` when neither sets a target
+    pub async fn weekly_target_minutes(&self, week_start: NaiveDate) -> Result<Option<i64>> {
+        let mut request =
+            ApiRequest::get("/api/desktop/work-policy").query("weekStart", &week_start.to_string());
+        if let Some(zone) = timezone::device_timezone() {
+            request = request.query("timezone", zone.name());
+        }
+
+        let payload = self.work_policy(request).await?;
+        Ok(payload.weekly_target_minutes)
+    }
+
+    async fn work_policy(&self, request: ApiRequest) -> Result<WorkPolicyResponse> {
+        let response = self.api.send(request).await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Failed to fetch work policy: {}", response.status));
+        }
+
+        response.json()
+    }
+}
+
+/// A reminder ahead of a break requirement or working-time limit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum BreakReminder {
+    #[serde(rename_all = "camelCase")]
+    BreakDue {
+        required_break_minutes: i64,
+        missing_break_minutes: i64,
+        minutes_left: i64,
+    },
+    #[serde(rename_all = "camelCase")]
+    MaxUninterrupted {
+        limit_minutes: i64,
+        minutes_left: i64,
+    },
+    #[serde(rename_all = "camelCase")]
+    MaxDaily {
+        limit_minutes: i64,
+        minutes_left: i64,
+    },
+}
+
+impl BreakReminder {
+    /// Identifies the limit being approached so each one is only announced once
+    fn key(&self, day: &DayTimeline) -> String {
+        match self {
+            Self::BreakDue {
+                required_break_minutes,
+                ..
+            } => format!("break:{}", required_break_minutes),
+            // Worked time at the start of the session stays constant while it runs
+            Self::MaxUninterrupted { .. } => format!(
+                "uninterrupted:{}",
+                day.worked_minutes - day.current_session_minutes.unwrap_or(0)
+            ),
+            Self::MaxDaily { .. } => "daily".to_string(),
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::BreakDue { .. } => "Break due soon",
+            Self::MaxUninterrupted { .. } => "Time for a break",
+            Self::MaxDaily { .. } => "Daily working limit approaching",
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::BreakDue {
+                required_break_minutes,
+                missing_break_minutes,
+                minutes_left,
+            } => format!(
+                "In {} min you need {} min of break in total today ({} min still missing).",
+                minutes_left, required_break_minutes, missing_break_minutes
+            ),
+            Self::MaxUninterrupted {
+                limit_minutes,
+                minutes_left,
+            } => format!(
+                "You may work at most {} min without a break. {} min left.",
+                limit_minutes, minutes_left
+            ),
+            Self::MaxDaily {
+                limit_minutes,
+                minutes_left,
+            } => format!(
+                "You reach the maximum of {} h {} min today in {} min.",
+                limit_minutes / 60,
+                limit_minutes % 60,
+                minutes_left
+            ),
+        }
+    }
+}
+
+/// Reminders for limits the running work period reaches within the alert window
+pub fn due_reminders(policy: &BreakPolicy, day: &DayTimeline) -> Vec<BreakReminder> {
+    if !day.is_clocked_in {
+        return Vec::new();
+    }
+
+    let alert_before = policy.alert_before_limit_minutes.max(1);
+    let within_window = |minutes_left: i64| minutes_left > 0 && minutes_left <= alert_before;
+    let mut reminders = Vec::new();
+
+    for rule in &policy.break_rules {
+        let minutes_left = rule.working_minutes_threshold - day.worked_minutes;
+        let missing_break_minutes = rule.required_break_minutes - day.break_minutes;
+        if within_window(minutes_left) && missing_break_minutes > 0 {
+            reminders.push(BreakReminder::BreakDue {
+                required_break_minutes: rule.required_break_minutes,
+                missing_break_minutes,
+                minutes_left,
+            });
+        }
+    }
+
+    if let (Some(limit_minutes), Some(session_minutes)) = (
+        policy.max_uninterrupted_minutes,
+        day.current_session_minutes,
+    ) {
+        let minutes_left = limit_minutes - session_minutes;
+        if within_window(minutes_left) {
+            reminders.push(BreakReminder::MaxUninterrupted {
+                limit_minutes,
+                minutes_left,
+            });
+        }
+    }
+
+    if let Some(limit_minutes) = policy.max_daily_minutes {
+        let minutes_left = limit_minutes - day.worked_minutes;
+        if within_window(minutes_left) {
+            reminders.push(BreakReminder::MaxDaily {
+                limit_minutes,
+                minutes_left,
+            });
+        }
+    }
+
+    reminders
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TodayEntries {
+    date: NaiveDate,
+    entries: Vec<TimeEntry>,
+}
+
+/// Checks the running work period against the break policy once a minute.
+/// Uses the cached policy and entries plus queued punches, so reminders keep firing offline.
+pub async fn start_break_reminders(app_handle: AppHandle) {
+    log::info!("Starting break reminder scheduler");
+
+    let state = app_handle.state::<Arc<AppState>>();
+    let policy_service = BreakPolicyService::new(state.api.clone());
+    let clock_service = ClockService::new(state.api.clone());
+
+    let mut policy_fetched_at: Option<Instant> = None;
+    let mut entries_fetched: Option<(Instant, bool)> = None;
+    let mut sent: HashSet<String> = HashSet::new();
+    let mut sent_date: Option<NaiveDate> = None;
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        if !state.settings.read().break_reminders_enabled {
+            continue;
+        }
+
+        let online = state.get_session_token().is_some() && !state.get_webapp_url().is_empty();
+        let is_clocked_in = state.is_clocked_in();
+        let now = Utc::now();
+        let (today, start, end) = timeline::today_range(now);
+
+        if online && policy_fetched_at.is_none_or(|at| at.elapsed() >= POLICY_REFRESH_INTERVAL) {
+            match policy_service.fetch().await {
+                Ok(policy) => {
+                    policy_fetched_at = Some(Instant::now());
+                    let mut queue = state.offline_queue.lock();
+                    if let Err(e) = queue.put_cached(BREAK_POLICY_CACHE_KEY, &policy) {
+                        log::warn!("Failed to cache break policy: {}", e);
+                    }
+                }
+                Err(e) => log::warn!("Failed to refresh break policy: {}", e),
+            }
+        }
+
+        if !is_clocked_in {
+            continue;
+        }
+
+        let policy = {
+            let queue = state.offline_queue.lock();
+            queue.get_cached::<Option<BreakPolicy>>(BREAK_POLICY_CACHE_KEY)
+        };
+        let policy = match policy {
+            Ok(Some(cached)) => match cached.value {
+                Some(policy) => policy,
+                None => continue,
+            },
+            Ok(None) => continue,
+            Err(e) => {
+                log::warn!("Failed to read cached break policy: {}", e);
+                continue;
+            }
+        };
+
+        // Refetch after a punch as well, so a new session is seen without waiting
+        let entries_stale = entries_fetched.is_none_or(|(at, was_clocked_in)| {
+            at.elapsed() >= ENTRIES_REFRESH_INTERVAL || was_clocked_in != is_clocked_in
+        });
+        if online && entries_stale {
+            match clock_service.list_entries(start, end).await {
+                Ok(entries) => {
+                    entries_fetched = Some((Instant::now(), is_clocked_in));
+                    let mut queue = state.offline_queue.lock();
+                    let today_entries = TodayEntries {
+                        date: today,
+                        entries,
+                    };
+                    if let Err(e) = queue.put_cached(TODAY_ENTRIES_CACHE_KEY, &today_entries) {
+                        log::warn!("Failed to cache today's entries: {}", e);
+                    }
+                }
+                Err(e) => log::warn!("Failed to refresh today's entries: {}", e),
+            }
+        }
+
+        let (server_entries, queued) = {
+            let queue = state.offline_queue.lock();
+            let server_entries = match queue.get_cached::<TodayEntries>(TODAY_ENTRIES_CACHE_KEY) {
+                Ok(Some(cached)) if cached.value.date == today => cached.value.entries,
+                Ok(_) => Vec::new(),
+                Err(e) => {
+                    log::warn!("Failed to read cached entries: {}", e);
+                    Vec::new()
+                }
+            };
+            let queued = queue.get_pending().unwrap_or_else(|e| {
+                log::warn!("Failed to read pending queue: {}", e);
+                Vec::new()
+            });
+            (server_entries, queued)
+        };
+
+        let day = timeline::build_today(server_entries, &queued, now);
+
+        if sent_date != Some(today) {
+            sent.clear();
+            sent_date = Some(today);
+        }
+
+        for reminder in due_reminders(&policy, &day) {
+            if !sent.insert(reminder.key(&day)) {
+                continue;
+            }
+
+            log::info!("Break reminder: {:?}", reminder);
+            notify::send(&app_handle, reminder.title(), &reminder.message());
+            let _ = app_handle.emit("break_reminder", &reminder);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{due_reminders, BreakPolicy, BreakPolicyService, BreakReminder, BreakRule};
+    use crate::api::testing::fake_client;
+    use crate::timeline::DayTimeline;
+    use chrono::NaiveDate;
+
+    fn policy() -> BreakPolicy {
+        BreakPolicy {
+            id: "policy-1".to_string(),
+            name: "Arbeitszeitgesetz".to_string(),
+            max_daily_minutes: Some(600),
+            max_uninterrupted_minutes: Some(360),
+            alert_before_limit_minutes: 15,
+            break_rules: vec![
+                BreakRule {
+                    working_minutes_threshold: 360,
+                    required_break_minutes: 30,
+                },
+                BreakRule {
+                    working_minutes_threshold: 540,
+                    required_break_minutes: 45,
+                },
+            ],
+        }
+    }
+
+    fn day(worked_minutes: i64, break_minutes: i64, session_minutes: Option<i64>) -> DayTimeline {
+        DayTimeline {
+            date: NaiveDate::from_ymd_opt(2026, 10, 14).unwrap(),
+            entries: Vec::new(),
+            worked_minutes,
+            break_minutes,
+            is_clocked_in: session_minutes.is_some(),
+            current_session_minutes: session_minutes,
+            pending_count: 0,
+        }
+    }
+
+    #[test]
+    fn reminds_before_break_thresholds_and_limits() {
+        // 5h50 straight: the 6h break rule and the uninterrupted limit are both close
+        assert_eq!(
+            due_reminders(&policy(), &day(350, 0, Some(350))),
+            vec![
+                BreakReminder::BreakDue {
+                    required_break_minutes: 30,
+                    missing_break_minutes: 30,
+                    minutes_left: 10,
+                },
+                BreakReminder::MaxUninterrupted {
+                    limit_minutes: 360,
+                    minutes_left: 10,
+                },
+            ]
+        );
+
+        // The break was already taken, only the daily limit is left
+        assert_eq!(
+            due_reminders(&policy(), &day(590, 45, Some(120))),
+            vec![BreakReminder::MaxDaily {
+                limit_minutes: 600,
+                minutes_left: 10,
+            }]
+        );
+
+        assert!(due_reminders(&policy(), &day(350, 0, None)).is_empty());
+        assert!(due_reminders(&policy(), &day(200, 0, Some(200))).is_empty());
+    }
+
+    #[tokio::test]
+    async fn treats_missing_regulation_as_no_policy() {
+        let (api, transport) = fake_client();
+        transport.respond(
+            200,
+            serde_json::json!({
+                "policy": {
+                    "id": "policy-1",
+                    "name": "Arbeitszeitgesetz",
+                    "regulation": {
+                        "maxDailyMinutes": 600,
+                        "maxUninterruptedMinutes": null,
+                        "alertBeforeLimitMinutes": null,
+                        "breakRules": [
+                            { "workingMinutesThreshold": 540, "requiredBreakMinutes": 45 },
+                            { "workingMinutesThreshold": 360, "requiredBreakMinutes": 30 },
+                        ]
+                    }
+                }
+            }),
+        );
+        transport.respond(
+            200,
+            serde_json::json!({
+                "policy": { "id": "policy-2", "name": "Schedule only", "regulation": null }
+            }),
+        );
+
+        let service = BreakPolicyService::new(api);
+        let policy = service.fetch().await.unwrap().unwrap();
+        assert_eq!(policy.alert_before_limit_minutes, 15);
+        assert_eq!(policy.break_rules[0].working_minutes_threshold, 360);
+        assert_eq!(policy.max_uninterrupted_minutes, None);
+        assert_eq!(
+            transport.requests()[0].url,
+            "https://z8.example/api/desktop/work-policy"
+        );
+
+        assert!(service.fetch().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn reads_the_weekly_target_for_the_requested_week() {
+        let (api, transport) = fake_client();
+        transport.respond(
+            200,
+            serde_json::json!({ "policy": null, "weeklyTargetMinutes": 1950 }),
+        );
+        transport.respond(200, serde_json::json!({ "policy": null }));
+
+        let service = BreakPolicyService::new(api);
+        let week_start = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        assert_eq!(
+            service.weekly_target_minutes(week_start).await.unwrap(),
+            Some(1950)
+        );
+        assert!(transport.requests()[0]
+            .url
+            .starts_with("https://z8.example/api/desktop/work-policy?weekStart=2026-10-12"));
+
+        assert_eq!(
+            service.weekly_target_minutes(week_start).await.unwrap(),
+            None
+        );
+    }
+}
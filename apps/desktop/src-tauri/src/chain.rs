@@ -0,0 +1,126 @@
+//! Hash chain over the offline queue, like the webapp's chain of time entries.
+//!
+//! Every queued action, every rewrite of one and every removal before it was sent
+//! (a `remove` link naming the removed link's sequence) appends a link whose hash covers
+//! the action, its timestamp and payload and the previous link's hash, signed with
+//! the device key. Replayed punches carry the links up to their own, so the webapp
+//! can check that nothing was edited in `offline_queue.db` before upload.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::device::DeviceKey;
+
+/// `previousHash` of the first link a device records, as in the webapp's chain
+pub const GENESIS_HASH: &str = "genesis";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainLink {
+    /// Local order of the links; not covered by the hash
+    pub sequence: i64,
+    /// e.g. `clock_in`, see `ActionType::as_str`
+    pub action: String,
+    /// Unix seconds the action was queued for
+    pub timestamp: i64,
+    pub payload: Option<String>,
+    pub created_at: i64,
+    pub previous_hash: String,
+    /// Hex SHA-256 of the fields above, see `hash_link`
+    pub hash: String,
+    /// Base64 Ed25519 signature over the hash's UTF-8 bytes
+    pub signature: String,
+}
+
+impl ChainLink {
+    pub fn new(
+        previous_hash: &str,
+        action: &str,
+        timestamp: i64,
+        payload: Option<&str>,
+        created_at: i64,
+        key: &DeviceKey,
+    ) -> Self {
+        let hash = hash_link(previous_hash, action, timestamp, payload, created_at);
+        Self {
+            sequence: 0,
+            action: action.to_string(),
+            timestamp,
+            payload: payload.map(str::to_string),
+            created_at,
+            previous_hash: previous_hash.to_string(),
+            signature: key.sign(hash.as_bytes()),
+            hash,
+        }
+    }
+}
+
+/// Links sent with a replayed punch: all not yet accepted by the webapp, up to its own
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayChain {
+    pub public_key: String,
+    pub links: Vec<ChainLink>,
+}
+
+/// Hex SHA-256 of `action|timestamp|createdAt|previousHash|payload`, the payload last
+/// because it may itself contain `|`
+pub fn hash_link(
+    previous_hash: &str,
+    action: &str,
+    timestamp: i64,
+    payload: Option<&str>,
+    created_at: i64,
+) -> String {
+    let data = format!(
+        "{}|{}|{}|{}|{}",
+        action,
+        timestamp,
+        created_at,
+        previous_hash,
+        payload.unwrap_or_default()
+    );
+    Sha256::digest(data.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_link, ChainLink, GENESIS_HASH};
+    use crate::device::DeviceKey;
+    use std::fs;
+
+    #[test]
+    fn hashes_cover_the_fields_and_the_previous_link() {
+        let dir = std::env::temp_dir().join(format!("z8-chain-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let key = DeviceKey::load_or_create(&dir).unwrap();
+
+        let first = ChainLink::new(GENESIS_HASH, "clock_in", 100, Some("{}"), 100, &key);
+        let second = ChainLink::new(&first.hash, "clock_out", 200, None, 200, &key);
+        assert_eq!(first.hash.len(), 64);
+        assert_eq!(second.previous_hash, first.hash);
+        assert_eq!(
+            second.hash,
+            hash_link(&first.hash, "clock_out", 200, None, 200)
+        );
+
+        // Any edited field or a different predecessor changes the hash
+        assert_ne!(
+            first.hash,
+            hash_link(GENESIS_HASH, "clock_in", 101, Some("{}"), 100)
+        );
+        assert_ne!(
+            first.hash,
+            hash_link(GENESIS_HASH, "clock_in", 100, Some("{ }"), 100)
+        );
+        assert_ne!(
+            second.hash,
+            hash_link(GENESIS_HASH, "clock_out", 200, None, 200)
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,850 @@
+//! `z8-timer` subcommands for cron jobs, SSH sessions and scripts.
+//!
+//! Commands that change the clock go through the running desktop app when
+//! there is one, so its tray and timers stay in sync: through its control API
+//! when that is enabled, which reports the result back, else as a second
+//! instance in the same desktop session. Only without the app do they act
+//! directly on the persisted session token and `offline_queue.db`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Local, Utc};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use crate::api::{is_network_error, Z8ApiClient};
+use crate::auth;
+use crate::clock::{
+    ClockService, ClockStatus, PunchCapture, PunchDetails, WorkLocationType, CLOCK_STATUS_CACHE_KEY,
+};
+use crate::commands::{self, Punched, Puncher};
+use crate::control::{self, ControlEndpoint};
+use crate::device::DeviceKey;
+use crate::journal::{EventSource, Journal};
+use crate::location::{self, LocationDetection, LocationSuggestion};
+use crate::network::NetworkConfig;
+use crate::notify;
+use crate::offline::{self, OfflineQueue, QueueRun};
+use crate::settings::Settings;
+use crate::state::{AppState, ACTIVE_PUNCH_DETAILS_KEY, DEVICE_ID_FILE, TOKEN_FILE};
+use crate::wellness::WellnessService;
+
+/// Must match `identifier` in tauri.conf.json, which names the app data directory
+const APP_IDENTIFIER: &str = "com.z8.timer";
+/// Written by the desktop app on startup so the CLI can tell whether it is running
+pub const PID_FILE: &str = "instance.pid";
+
+const USAGE: &str = "\
+Usage: z8-timer [COMMAND]
+
+Without a command, starts the desktop app.
+
+Commands:
+  status [--json]                Show whether you are clocked in
+  clock-in [OPTIONS]             Clock in
+      --location <LOCATION>      office, home, remote or other
+                                 (default: from your work location rules, else office)
+      --project <ID>             Project to book the time on
+      --category <ID>            Work category
+      --notes <TEXT>             Notes for the time entry
+  clock-out [OPTIONS]            Clock out; --project, --category and --notes
+                                 default to the clock-in selection
+  queue list [--json]            Show actions waiting in the offline queue
+  queue flush                    Send the offline queue to the webapp now
+  login                          Sign in through the desktop app
+  help                           Show this help
+
+When the desktop app is running, clock-in and clock-out go through its control
+API if it is enabled in its settings, and their result is shown here. Otherwise
+they, queue flush and login are handed to the app in the same desktop session
+and their result is shown as a desktop notification. From another session,
+e.g. over SSH, the control API has to be enabled.";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    Status {
+        json: bool,
+    },
+    ClockIn {
+        location: Option<WorkLocationType>,
+        details: PunchDetails,
+    },
+    ClockOut {
+        details: PunchDetails,
+    },
+    QueueList {
+        json: bool,
+    },
+    QueueFlush,
+    Login,
+    Help,
+}
+
+impl CliCommand {
+    /// Whether a running desktop app should carry out the command
+    fn runs_in_app(&self) -> bool {
+        matches!(
+            self,
+            Self::ClockIn { .. } | Self::ClockOut { .. } | Self::QueueFlush | Self::Login
+        )
+    }
+}
+
+/// Parses the arguments after the program name.
+///
+/// Returns `None` when they don't start with a subcommand, e.g. no arguments
+/// or a `z8://` URL, in which case the desktop app starts as usual.
+pub fn parse(args: &[String]) -> Result<Option<CliCommand>> {
+    let Some((name, rest)) = args.split_first() else {
+        return Ok(None);
+    };
+
+    let command = match name.as_str() {
+        "status" => CliCommand::Status {
+            json: parse_options(rest, &["json"])?.contains_key("json"),
+        },
+        "clock-in" => {
+            let mut options = parse_options(rest, &["location", "project", "category", "notes"])?;
+            let location = options
+                .remove("location")
+                .map(|value| {
+                    WorkLocationType::from_str(&value).ok_or_else(|| {
+                        anyhow!(
+                            "Invalid location '{}', expected office, home, remote or other",
+                            value
+                        )
+                    })
+                })
+                .transpose()?;
+            CliCommand::ClockIn {
+                location,
+                details: punch_details(options),
+            }
+        }
+        "clock-out" => CliCommand::ClockOut {
+            details: punch_details(parse_options(rest, &["project", "category", "notes"])?),
+        },
+        "queue" => match rest.split_first() {
+            None => CliCommand::QueueList { json: false },
+            Some((action, rest)) => match action.as_str() {
+                "list" => CliCommand::QueueList {
+                    json: parse_options(rest, &["json"])?.contains_key("json"),
+                },
+                "flush" => {
+                    parse_options(rest, &[])?;
+                    CliCommand::QueueFlush
+                }
+                other => bail!("Unknown queue command '{}', expected list or flush", other),
+            },
+        },
+        "login" => {
+            parse_options(rest, &[])?;
+            CliCommand::Login
+        }
+        "help" | "--help" | "-h" => CliCommand::Help,
+        _ => return Ok(None),
+    };
+
+    Ok(Some(command))
+}
+
+/// Options that take no value
+const FLAGS: &[&str] = &["json"];
+
+/// Reads `--name value`, `--name=value` and flag options, rejecting any not in `allowed`
+fn parse_options(args: &[String], allowed: &[&str]) -> Result<HashMap<String, String>> {
+    let mut options = HashMap::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let Some(option) = arg.strip_prefix("--") else {
+            bail!("Unexpected argument '{}'", arg);
+        };
+        let (name, inline_value) = match option.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (option, None),
+        };
+        if !allowed.contains(&name) {
+            bail!("Unknown option '--{}'", name);
+        }
+
+        let value = if FLAGS.contains(&name) {
+            if inline_value.is_some() {
+                bail!("--{} takes no value", name);
+            }
+            String::new()
+        } else {
+            match inline_value {
+                Some(value) => value,
+                None => args
+                    .next()
+                    .cloned()
+                    .ok_or_else(|| anyhow!("--{} needs a value", name))?,
+            }
+        };
+        options.insert(name.to_string(), value);
+    }
+
+    Ok(options)
+}
+
+fn punch_details(mut options: HashMap<String, String>) -> PunchDetails {
+    PunchDetails::new(
+        options.remove("project"),
+        options.remove("category"),
+        options.remove("notes"),
+    )
+}
+
+/// Runs a subcommand given on the command line.
+///
+/// Returns the exit code, or `None` when the desktop app should start, either
+/// because there is no subcommand or to hand it to the running instance.
+pub fn run(args: Vec<String>) -> Option<i32> {
+    let command = match parse(args.get(1..).unwrap_or_default()) {
+        Ok(Some(command)) => command,
+        Ok(None) => return None,
+        Err(e) => {
+            attach_console();
+            eprintln!("z8-timer: {}\n\n{}", e, USAGE);
+            return Some(2);
+        }
+    };
+
+    attach_console();
+
+    if command == CliCommand::Help {
+        println!("{}", USAGE);
+        return Some(0);
+    }
+
+    let app_data_dir = app_data_dir();
+    let app_running = is_app_running(&app_data_dir);
+    if command == CliCommand::Login {
+        if has_desktop_session() {
+            // The single-instance plugin forwards our arguments and exits, or
+            // this becomes the app and signs in on startup
+            println!("Handing the command to the z8 Timer desktop app");
+            return None;
+        }
+        eprintln!("z8-timer: signing in needs the desktop app; run `z8-timer login` in your desktop session");
+        return Some(1);
+    }
+
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("z8-timer: {}", e);
+            return Some(1);
+        }
+    };
+
+    // The running app owns the queue and its in-memory clock state; writing
+    // behind its back would leave them out of step
+    let result = if command.runs_in_app() && app_running {
+        match runtime.block_on(forward_to_app(&app_data_dir, &command)) {
+            // The single-instance plugin forwards our arguments and exits. The app
+            // reports the result as a notification, so this can't show it or fail.
+            Err(e) if e.is::<ControlUnreachable>() && has_desktop_session() => {
+                println!("Handing the command to the z8 Timer desktop app; it shows the result as a notification");
+                return None;
+            }
+            result => result,
+        }
+    } else {
+        runtime.block_on(run_headless(&app_data_dir, command, app_running))
+    };
+    match result {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("z8-timer: {:#}", e);
+            Some(1)
+        }
+    }
+}
+
+/// Same directory as tauri's `app_data_dir()`, resolved without a running app
+fn app_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(APP_IDENTIFIER)
+}
+
+/// Whether a second instance can reach the running app; over SSH it can't
+fn has_desktop_session() -> bool {
+    if cfg!(target_os = "linux") {
+        ["DISPLAY", "WAYLAND_DISPLAY"]
+            .iter()
+            .any(|name| std::env::var_os(name).is_some_and(|value| !value.is_empty()))
+    } else {
+        std::env::var_os("SSH_CONNECTION").is_none()
+    }
+}
+
+fn is_app_running(app_data_dir: &Path) -> bool {
+    fs::read_to_string(app_data_dir.join(PID_FILE))
+        .ok()
+        .and_then(|pid| pid.trim().parse::<u32>().ok())
+        .is_some_and(|pid| pid != std::process::id() && is_process_alive(pid))
+}
+
+#[cfg(target_os = "linux")]
+fn is_process_alive(pid: u32) -> bool {
+    // A reused pid after a crash belongs to some other program
+    let exe = |path: PathBuf| fs::read_link(path).ok();
+    match (
+        exe(PathBuf::from(format!("/proc/{}/exe", pid))),
+        std::env::current_exe().ok(),
+    ) {
+        (Some(running), Some(current)) => running.file_name() == current.file_name(),
+        _ => false,
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn is_process_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(windows)]
+fn is_process_alive(pid: u32) -> bool {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+}
+
+/// Release builds use the GUI subsystem on Windows, which has no console for
+/// stdout; reuse the one of the shell that started us
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // SAFETY: no preconditions; fails harmlessly when there is no parent console
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+/// The persisted session and offline queue, without the desktop app
+struct Headless {
+    settings: Settings,
+    api: Z8ApiClient,
+    queue: Mutex<OfflineQueue>,
+    journal: Mutex<Journal>,
+    /// The desktop app runs in another session; only read what it stored
+    app_running: bool,
+}
+
+impl Headless {
+    fn open(app_data_dir: &Path, app_running: bool) -> Result<Self> {
+        fs::create_dir_all(app_data_dir)?;
+        let settings = Settings::load(app_data_dir)?;
+        let device_key = DeviceKey::load_or_create(app_data_dir)?;
+        let queue = OfflineQueue::new(app_data_dir, Arc::new(device_key))?;
+        let journal = Journal::new(app_data_dir)?;
+
+        let token = fs::read_to_string(app_data_dir.join(TOKEN_FILE))
+            .ok()
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty());
+        let network = NetworkConfig::resolve(&settings.network);
+        let api = Z8ApiClient::new(&settings.webapp_url, token, &network)?;
+        api.set_device_id(
+            fs::read_to_string(app_data_dir.join(DEVICE_ID_FILE))
+                .ok()
+                .map(|device_id| device_id.trim().to_string())
+                .filter(|device_id| !device_id.is_empty()),
+        );
+
+        Ok(Self {
+            settings,
+            api,
+            queue: Mutex::new(queue),
+            journal: Mutex::new(journal),
+            app_running,
+        })
+    }
+
+    fn clock_service(&self) -> Result<ClockService> {
+        if self.api.session_token().is_none() {
+            bail!("Not signed in; run `z8-timer login` in your desktop session");
+        }
+        if self.settings.webapp_url.is_empty() {
+            bail!("Webapp URL not configured");
+        }
+        Ok(ClockService::new(self.api.clone()))
+    }
+
+    fn puncher<'a>(&'a self, clock_service: &'a ClockService) -> Puncher<'a> {
+        Puncher::with_storage(clock_service, &self.queue, &self.journal, EventSource::Cli)
+    }
+
+    /// Sends queued punches before a new one, which otherwise waits behind them.
+    /// A running app sends its queue itself.
+    async fn send_queued_first(&self) {
+        if self.app_running {
+            return;
+        }
+        if let Err(e) = self.flush().await {
+            log::warn!("Failed to send the offline queue: {}", e);
+        }
+    }
+
+    fn active_punch_details(&self) -> PunchDetails {
+        self.queue
+            .lock()
+            .get_cached::<PunchDetails>(ACTIVE_PUNCH_DETAILS_KEY)
+            .ok()
+            .flatten()
+            .map(|cached| cached.value)
+            .unwrap_or_default()
+    }
+
+    fn set_active_punch_details(&self, details: &PunchDetails) -> Result<()> {
+        let mut queue = self.queue.lock();
+        if details.is_empty() {
+            queue.remove_cached(ACTIVE_PUNCH_DETAILS_KEY)
+        } else {
+            queue.put_cached(ACTIVE_PUNCH_DETAILS_KEY, details)
+        }
+    }
+
+    /// The webapp's status with punches still waiting in the queue applied. Offline,
+    /// the last status the webapp reported stands in for it.
+    async fn status(&self) -> Result<(ClockStatus, bool)> {
+        let server = match self.clock_service()?.get_status().await {
+            Ok(status) => Some(status),
+            Err(e) if is_network_error(&e) => None,
+            Err(e) => return Err(e),
+        };
+
+        let mut queue = self.queue.lock();
+        if let Some(status) = server.as_ref().filter(|_| !self.app_running) {
+            queue.put_cached(CLOCK_STATUS_CACHE_KEY, status)?;
+        }
+        let status = queue
+            .local_clock_status()?
+            .unwrap_or_else(ClockStatus::unknown);
+        let has_pending = !queue.pending_punches()?.is_empty()
+            && server.is_none_or(|server| server.is_clocked_in != status.is_clocked_in);
+        Ok((status, has_pending))
+    }
+
+    async fn clock_in(
+        &self,
+        location: Option<WorkLocationType>,
+        details: PunchDetails,
+    ) -> Result<String> {
+        let clock_service = self.clock_service()?;
+        let suggestion = suggest_location(&self.settings).await;
+        let work_location_type = location
+            .or(suggestion.as_ref().map(|suggestion| suggestion.location))
+            .unwrap_or(WorkLocationType::Office);
+        let detection = LocationDetection::for_choice(work_location_type, suggestion);
+
+        self.send_queued_first().await;
+        let punched = self
+            .puncher(&clock_service)
+            .clock_in(work_location_type, &details, PunchCapture::now(), detection)
+            .await?;
+        self.set_active_punch_details(&details)?;
+        Ok(match punched {
+            Punched::Sent => format!("Clocked in ({})", work_location_type.as_str()),
+            Punched::Queued => format!(
+                "Clock-in ({}) queued; it is sent with the offline queue",
+                work_location_type.as_str()
+            ),
+        })
+    }
+
+    async fn clock_out(&self, details: PunchDetails) -> Result<String> {
+        let clock_service = self.clock_service()?;
+        let details = details.or(&self.active_punch_details());
+
+        self.send_queued_first().await;
+        let punched = self
+            .puncher(&clock_service)
+            .clock_out("clock_out", &details, PunchCapture::now())
+            .await?;
+        self.set_active_punch_details(&PunchDetails::default())?;
+        Ok(match punched {
+            Punched::Sent => "Clocked out".to_string(),
+            Punched::Queued => "Clock-out queued; it is sent with the offline queue".to_string(),
+        })
+    }
+
+    async fn flush(&self) -> Result<QueueRun> {
+        let clock_service = self.clock_service()?;
+        let wellness_service = WellnessService::new(self.api.clone());
+        offline::process_queue(
+            &self.queue,
+            &self.journal,
+            &clock_service,
+            &wellness_service,
+        )
+        .await
+    }
+}
+
+async fn run_headless(app_data_dir: &Path, command: CliCommand, app_running: bool) -> Result<()> {
+    let headless = Headless::open(app_data_dir, app_running)
+        .with_context(|| format!("Failed to open {}", app_data_dir.display()))?;
+
+    match command {
+        CliCommand::Status { json } => {
+            let (status, has_pending) = headless.status().await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&status)?);
+            } else {
+                println!("{}", describe_status(&status, Utc::now()));
+                if has_pending {
+                    println!("(includes punches waiting in the offline queue)");
+                }
+            }
+        }
+        CliCommand::ClockIn { location, details } => {
+            println!("{}", headless.clock_in(location, details).await?);
+        }
+        CliCommand::ClockOut { details } => {
+            println!("{}", headless.clock_out(details).await?);
+        }
+        CliCommand::QueueList { json } => {
+            let pending = headless.queue.lock().get_pending()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&pending)?);
+            } else if pending.is_empty() {
+                println!("The offline queue is empty");
+            } else {
+                for action in pending {
+                    let queued_at = DateTime::from_timestamp(action.timestamp, 0)
+                        .map(|at| {
+                            at.with_timezone(&Local)
+                                .format("%Y-%m-%d %H:%M")
+                                .to_string()
+                        })
+                        .unwrap_or_default();
+                    println!(
+                        "{:>5}  {}  {:?}  (retries: {})",
+                        action.id, queued_at, action.action_type, action.retry_count
+                    );
+                }
+            }
+        }
+        CliCommand::QueueFlush => {
+            println!("{}", describe_run(&headless.flush().await?));
+        }
+        // Handled before opening the queue
+        CliCommand::Login | CliCommand::Help => {}
+    }
+
+    Ok(())
+}
+
+/// The running app's control API is disabled, not answering or has no endpoint
+/// for the command; nothing was carried out
+#[derive(Debug)]
+struct ControlUnreachable(String);
+
+impl std::fmt::Display for ControlUnreachable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ControlUnreachable {}
+
+/// Hands a command to the running desktop app through its control API and
+/// reports its result
+async fn forward_to_app(app_data_dir: &Path, command: &CliCommand) -> Result<()> {
+    let (path, body) = match command {
+        CliCommand::ClockIn { location, details } => (
+            "/v1/clock-in",
+            serde_json::json!({
+                "location": location,
+                "projectId": details.project_id,
+                "workCategoryId": details.work_category_id,
+                "notes": details.notes,
+            }),
+        ),
+        CliCommand::ClockOut { details } => (
+            "/v1/clock-out",
+            serde_json::json!({
+                "projectId": details.project_id,
+                "workCategoryId": details.work_category_id,
+                "notes": details.notes,
+            }),
+        ),
+        CliCommand::QueueFlush => {
+            return Err(ControlUnreachable(
+                "The desktop app is running and sends the offline queue itself".to_string(),
+            )
+            .into())
+        }
+        _ => bail!("The desktop app is running in another session"),
+    };
+
+    let endpoint = fs::read_to_string(control::discovery_path(app_data_dir))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<ControlEndpoint>(&contents).ok())
+        .ok_or_else(|| {
+            ControlUnreachable(
+                "The desktop app is running in another session; enable its control API in Settings to punch from here".to_string(),
+            )
+        })?;
+    let response = reqwest::Client::builder()
+        .no_proxy()
+        .build()?
+        .post(format!("{}{}", endpoint.url, path))
+        .bearer_auth(&endpoint.token)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| -> anyhow::Error {
+            // Only a refused connection is sure not to have reached the app
+            if e.is_connect() {
+                ControlUnreachable(format!(
+                    "The desktop app is running but its control API is not answering: {}",
+                    e
+                ))
+                .into()
+            } else {
+                anyhow!(e).context("The desktop app did not answer the command")
+            }
+        })?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error = response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|body| body["error"].as_str().map(str::to_string))
+            .unwrap_or_else(|| format!("The desktop app refused the command: {}", status));
+        bail!(error);
+    }
+
+    let status: ClockStatus = response.json().await?;
+    println!("{}", describe_status(&status, Utc::now()));
+    Ok(())
+}
+
+/// Suggests a work location from the user's and IT's rules for the current network
+async fn suggest_location(settings: &Settings) -> Option<LocationSuggestion> {
+    let mut rules = settings.location_rules.clone();
+    rules.extend(location::managed_rules());
+    if rules.is_empty() {
+        return None;
+    }
+
+    let signals = tokio::task::spawn_blocking(location::read_signals)
+        .await
+        .ok()?
+        .ok()?;
+    location::suggest(&rules, &signals)
+}
+
+fn describe_status(status: &ClockStatus, now: DateTime<Utc>) -> String {
+    if !status.has_employee {
+        return "No employee profile in this organization".to_string();
+    }
+    if !status.is_clocked_in {
+        return "Clocked out".to_string();
+    }
+
+    let Some(start) = status
+        .active_work_period
+        .as_ref()
+        .and_then(|period| DateTime::parse_from_rfc3339(&period.start_time).ok())
+    else {
+        return "Clocked in".to_string();
+    };
+
+    let minutes = (now - start.with_timezone(&Utc)).num_minutes().max(0);
+    format!(
+        "Clocked in since {} ({}h {:02}m)",
+        start.with_timezone(&Local).format("%H:%M"),
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+fn describe_run(run: &QueueRun) -> String {
+    if run.pending == 0 {
+        return "The offline queue is empty".to_string();
+    }
+    let mut message = format!("Sent {} of {} queued actions", run.sent, run.pending);
+    if run.failed > 0 {
+        message.push_str(&format!("; {} failed and will be retried", run.failed));
+    }
+    if run.blocked_by.is_some() {
+        message.push_str("; a punch failed too often and holds back the ones after it");
+    }
+    message
+}
+
+/// Carries out a command handed over by a second instance, or given to this
+/// instance on startup, and reports the result as a notification
+pub fn handle_in_app(app_handle: &AppHandle, command: CliCommand) {
+    if !command.runs_in_app() {
+        return;
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        log::info!("Running command line request: {:?}", command);
+        match run_in_app(&app_handle, command).await {
+            Ok(message) => notify::send(&app_handle, "z8 Timer", &message),
+            Err(e) => {
+                log::error!("Command line request failed: {}", e);
+                notify::send(&app_handle, "z8 Timer", &e);
+            }
+        }
+    });
+}
+
+async fn run_in_app(app_handle: &AppHandle, command: CliCommand) -> Result<String, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+
+    match command {
+        CliCommand::ClockIn { location, details } => {
+            let suggestion = commands::suggest_work_location(app_handle.clone()).await?;
+            let work_location_type = location
+                .or(suggestion.as_ref().map(|suggestion| suggestion.location))
+                .unwrap_or(WorkLocationType::Office);
+            let status = commands::clock_in(
+                app_handle.clone(),
+                work_location_type.as_str().to_string(),
+                details.project_id,
+                details.work_category_id,
+                details.notes,
+                suggestion,
+                Some(EventSource::Cli),
+            )
+            .await?;
+            let queued = state
+                .offline_queue
+                .lock()
+                .pending_punches()
+                .is_ok_and(|punches| !punches.is_empty());
+            Ok(if status.is_clocked_in && !queued {
+                format!("Clocked in ({})", work_location_type.as_str())
+            } else {
+                format!(
+                    "Clocked in ({}), waiting to sync",
+                    work_location_type.as_str()
+                )
+            })
+        }
+        CliCommand::ClockOut { details } => {
+            commands::clock_out(
+                app_handle.clone(),
+                details.project_id,
+                details.work_category_id,
+                details.notes,
+                Some(EventSource::Cli),
+            )
+            .await?;
+            Ok("Clocked out".to_string())
+        }
+        CliCommand::QueueFlush => {
+            let clock_service = ClockService::new(state.api.clone());
+            let wellness_service = WellnessService::new(state.api.clone());
+            let run = offline::process_queue(
+                &state.offline_queue,
+                &state.journal,
+                &clock_service,
+                &wellness_service,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+            let _ = commands::get_clock_status(app_handle.clone()).await;
+            Ok(describe_run(&run))
+        }
+        CliCommand::Login => {
+            auth::initiate_oauth(app_handle, &state.get_webapp_url())
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok("Continue signing in in your browser".to_string())
+        }
+        CliCommand::Status { .. } | CliCommand::QueueList { .. } | CliCommand::Help => {
+            Ok(String::new())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe_status, parse, CliCommand};
+    use crate::clock::{ClockStatus, PunchDetails, WorkLocationType};
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn parses_subcommands_and_leaves_other_arguments_to_the_app() {
+        assert_eq!(
+            parse(&args(
+                "clock-in --location home --project p1 --notes=review"
+            ))
+            .unwrap(),
+            Some(CliCommand::ClockIn {
+                location: Some(WorkLocationType::Home),
+                details: PunchDetails::new(Some("p1".into()), None, Some("review".into())),
+            })
+        );
+        assert_eq!(
+            parse(&args("queue flush")).unwrap(),
+            Some(CliCommand::QueueFlush)
+        );
+        assert_eq!(
+            parse(&args("status --json")).unwrap(),
+            Some(CliCommand::Status { json: true })
+        );
+        assert_eq!(parse(&args("")).unwrap(), None);
+        assert_eq!(parse(&args("z8://auth/callback?code=x")).unwrap(), None);
+
+        assert!(parse(&args("clock-in --location garden")).is_err());
+        assert!(parse(&args("clock-out --project")).is_err());
+        assert!(parse(&args("queue drop")).is_err());
+        assert!(parse(&args("status --verbose")).is_err());
+    }
+
+    #[test]
+    fn describes_the_running_work_period() {
+        let mut status = ClockStatus {
+            has_employee: true,
+            employee_id: None,
+            is_clocked_in: false,
+            active_work_period: None,
+        };
+        let now = "2026-05-11T11:05:00Z".parse().unwrap();
+        assert_eq!(describe_status(&status, now), "Clocked out");
+
+        status.is_clocked_in = true;
+        assert_eq!(describe_status(&status, now), "Clocked in");
+
+        status.active_work_period = Some(crate::clock::WorkPeriod {
+            id: "wp".into(),
+            start_time: "2026-05-11T08:32:00Z".into(),
+        });
+        assert!(describe_status(&status, now).ends_with("(2h 33m)"));
+    }
+}
@@ -1,8 +1,25 @@
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::api::{
+    is_unknown_outcome, ApiError, ApiRequest, ApiStream, Z8ApiClient, PUNCH_TIMEOUT,
+    STATUS_TIMEOUT, STREAM_TIMEOUT,
+};
+use crate::chain::ReplayChain;
+use crate::location::LocationDetection;
+use crate::timezone;
+
+/// The webapp accepts capture evidence up to 5 minutes old on live punches and
+/// 7 days old on replays. These leave a margin for clock skew.
+const LIVE_CAPTURE_WINDOW_MINUTES: i64 = 4;
+const REPLAY_CAPTURE_WINDOW_MINUTES: i64 = 7 * 24 * 60 - 60;
+
+/// The last status the webapp reported, so the app starts with it while offline
+pub const CLOCK_STATUS_CACHE_KEY: &str = "clock_status";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ClockStatus {
     pub has_employee: bool,
@@ -11,7 +28,60 @@ pub struct ClockStatus {
     pub active_work_period: Option<WorkPeriod>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ClockStatus {
+    /// Before the webapp has ever answered: not clocked in, employee unknown
+    pub fn unknown() -> Self {
+        Self {
+            has_employee: true,
+            employee_id: None,
+            is_clocked_in: false,
+            active_work_period: None,
+        }
+    }
+
+    /// Applies punches the webapp hasn't seen yet, oldest first. A queued clock-in
+    /// starts a period of its own, so the elapsed time counts from the punch.
+    pub fn with_punches(mut self, punches: &[(&str, DateTime<Utc>)]) -> Self {
+        for (entry_type, at) in punches {
+            match *entry_type {
+                "clock_in" if !self.is_clocked_in => {
+                    self.is_clocked_in = true;
+                    self.active_work_period = Some(WorkPeriod {
+                        id: format!("queued-{}", at.timestamp()),
+                        start_time: at.to_rfc3339(),
+                    });
+                }
+                "clock_out" => {
+                    self.is_clocked_in = false;
+                    self.active_work_period = None;
+                }
+                _ => {}
+            }
+        }
+        self
+    }
+
+    /// Whether the running period was started by a punch at `at`, to the second
+    pub fn started_at(&self, at: DateTime<Utc>) -> bool {
+        self.period_start()
+            .is_some_and(|start| start.timestamp() == at.timestamp())
+    }
+
+    /// Whether a period started before `at` is still running
+    pub fn started_before(&self, at: DateTime<Utc>) -> bool {
+        self.period_start()
+            .is_some_and(|start| start.timestamp() < at.timestamp())
+    }
+
+    fn period_start(&self) -> Option<DateTime<Utc>> {
+        let period = self.active_work_period.as_ref()?;
+        DateTime::parse_from_rfc3339(&period.start_time)
+            .ok()
+            .map(|start| start.with_timezone(&Utc))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkPeriod {
     pub id: String,
@@ -26,16 +96,16 @@ pub struct TimeEntry {
     #[serde(rename = "type")]
     pub entry_type: String,
     pub timestamp: String,
+    #[serde(default)]
+    pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ApiResponse<T> {
-    pub success: bool,
-    pub data: Option<T>,
-    pub error: Option<String>,
+#[derive(Debug, Deserialize)]
+struct TimeEntriesResponse {
+    entries: Vec<TimeEntry>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum WorkLocationType {
     Office,
@@ -65,174 +135,630 @@ impl WorkLocationType {
     }
 }
 
+/// Optional project, category and notes attached to a punch.
+///
+/// The webapp assigns project and category to the work period when it is
+/// closed, so these are remembered from clock-in and sent again on clock-out.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PunchDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_category_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl PunchDetails {
+    pub fn new(
+        project_id: Option<String>,
+        work_category_id: Option<String>,
+        notes: Option<String>,
+    ) -> Self {
+        let non_empty = |value: Option<String>| value.filter(|value| !value.trim().is_empty());
+
+        Self {
+            project_id: non_empty(project_id),
+            work_category_id: non_empty(work_category_id),
+            notes: non_empty(notes),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Fills fields the caller left empty from `fallback` (usually the clock-in selection)
+    pub fn or(self, fallback: &PunchDetails) -> Self {
+        Self {
+            project_id: self.project_id.or_else(|| fallback.project_id.clone()),
+            work_category_id: self
+                .work_category_id
+                .or_else(|| fallback.work_category_id.clone()),
+            notes: self.notes.or_else(|| fallback.notes.clone()),
+        }
+    }
+
+    fn apply_to(&self, body: &mut serde_json::Value) {
+        let fields = [
+            ("projectId", &self.project_id),
+            ("workCategoryId", &self.work_category_id),
+            ("notes", &self.notes),
+        ];
+
+        for (key, value) in fields {
+            if let Some(value) = value {
+                body[key] = serde_json::Value::String(value.clone());
+            }
+        }
+    }
+}
+
+/// When a punch happened and which timezone the device was in at that moment.
+///
+/// Captured when the user acts, stored with queued actions and sent unchanged on replay,
+/// so a punch made while travelling is attributed to the local day it happened on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PunchCapture {
+    /// Becomes the entry id on the webapp, which makes replays idempotent
+    pub action_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub timezone: Option<String>,
+    pub utc_offset_minutes: Option<i32>,
+}
+
+impl PunchCapture {
+    pub fn now() -> Self {
+        Self::at(Utc::now())
+    }
+
+    /// Captures a punch at `timestamp` in the device's current timezone
+    pub fn at(timestamp: DateTime<Utc>) -> Self {
+        Self::in_zone(timestamp, timezone::device_timezone())
+    }
+
+    /// For queued actions written before captures were stored
+    pub fn without_zone(timestamp: DateTime<Utc>) -> Self {
+        Self::in_zone(timestamp, None)
+    }
+
+    fn in_zone(timestamp: DateTime<Utc>, zone: Option<Tz>) -> Self {
+        Self {
+            action_id: uuid::Uuid::new_v4().to_string(),
+            timestamp,
+            timezone: zone.map(|zone| zone.name().to_string()),
+            utc_offset_minutes: zone.map(|zone| timezone::utc_offset_minutes(zone, timestamp)),
+        }
+    }
+
+    /// Whether the webapp will accept the action id and offset for this punch. Outside
+    /// the window only the timestamp and zone are sent and the webapp derives the offset.
+    fn carries_evidence(&self, replay: bool, now: DateTime<Utc>) -> bool {
+        let window = if replay {
+            REPLAY_CAPTURE_WINDOW_MINUTES
+        } else {
+            LIVE_CAPTURE_WINDOW_MINUTES
+        };
+        let age = now - self.timestamp;
+
+        self.timezone.is_some()
+            && self.utc_offset_minutes.is_some()
+            && age <= Duration::minutes(window)
+            && age >= -Duration::minutes(LIVE_CAPTURE_WINDOW_MINUTES)
+    }
+
+    fn apply_to(&self, body: &mut serde_json::Value, replay: bool) {
+        body["timestamp"] = serde_json::Value::String(self.timestamp.to_rfc3339());
+
+        if let Some(timezone) = &self.timezone {
+            body["browserTimezone"] = serde_json::Value::String(timezone.clone());
+        }
+
+        if self.carries_evidence(replay, Utc::now()) {
+            body["id"] = serde_json::Value::String(self.action_id.clone());
+            body["utcOffsetMinutes"] = serde_json::json!(self.utc_offset_minutes);
+            if replay {
+                body["replay"] = serde_json::Value::Bool(true);
+            }
+        }
+    }
+}
+
+/// A change to the user's recorded time, sent for approval and queued unchanged while offline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CorrectionRequest {
+    /// Moves an existing clock-in or clock-out to `capture.timestamp`
+    #[serde(rename_all = "camelCase")]
+    Entry {
+        replaces_entry_id: String,
+        work_location_type: WorkLocationType,
+        work_category_id: Option<String>,
+        reason: String,
+        /// The action id doubles as the idempotency key
+        capture: PunchCapture,
+    },
+    /// Adds a work period that was never punched
+    #[serde(rename_all = "camelCase")]
+    MissingPeriod {
+        submission_id: String,
+        date: NaiveDate,
+        clock_in_time: NaiveTime,
+        clock_out_time: NaiveTime,
+        timezone: Option<String>,
+        project_id: Option<String>,
+        work_category_id: Option<String>,
+        reason: String,
+    },
+}
+
+impl CorrectionRequest {
+    fn to_api_request(&self) -> ApiRequest {
+        match self {
+            Self::Entry {
+                replaces_entry_id,
+                work_location_type,
+                work_category_id,
+                reason,
+                capture,
+            } => {
+                // The webapp checks the offset against the zone at the corrected instant
+                let offset = capture
+                    .utc_offset_minutes
+                    .and_then(|minutes| FixedOffset::east_opt(minutes * 60));
+                let timestamp = match offset {
+                    Some(offset) => capture.timestamp.with_timezone(&offset).to_rfc3339(),
+                    None => capture.timestamp.to_rfc3339(),
+                };
+
+                let mut body = serde_json::json!({
+                    "replacesEntryId": replaces_entry_id,
+                    "timestamp": timestamp,
+                    "notes": reason,
+                    "workLocationType": work_location_type.as_str(),
+                    "workCategoryId": work_category_id,
+                });
+                if let (Some(timezone), Some(_)) = (&capture.timezone, offset) {
+                    body["timezone"] = serde_json::Value::String(timezone.clone());
+                }
+
+                ApiRequest::post("/api/time-entries/corrections", body)
+                    .header("Idempotency-Key", &capture.action_id)
+                    .timeout(PUNCH_TIMEOUT)
+            }
+            Self::MissingPeriod {
+                submission_id,
+                date,
+                clock_in_time,
+                clock_out_time,
+                timezone,
+                project_id,
+                work_category_id,
+                reason,
+            } => ApiRequest::post(
+                "/api/desktop/manual-entries",
+                serde_json::json!({
+                    "submissionId": submission_id,
+                    "date": date.format("%Y-%m-%d").to_string(),
+                    "clockInTime": clock_in_time.format("%H:%M").to_string(),
+                    "clockOutTime": clock_out_time.format("%H:%M").to_string(),
+                    "reason": reason,
+                    "timezone": timezone,
+                    "browserTimezone": timezone,
+                    "projectId": project_id,
+                    "workCategoryId": work_category_id,
+                }),
+            )
+            .timeout(PUNCH_TIMEOUT),
+        }
+    }
+}
+
+/// Outcome of submitting a correction
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrectionSubmission {
+    pub requires_approval: bool,
+    pub message: String,
+    /// True when the webapp was unreachable and the request waits in the offline queue
+    pub queued: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CorrectionResponse {
+    message: String,
+    approval_id: Option<String>,
+    requires_approval: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CorrectionStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+/// A correction or missing-period request and its approval state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Correction {
+    pub id: String,
+    pub work_period_id: String,
+    pub status: CorrectionStatus,
+    pub reason: Option<String>,
+    pub rejection_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CorrectionsResponse {
+    corrections: Vec<Correction>,
+}
+
+/// Where a project switch stopped, so the caller knows what is left to retry
+#[derive(Debug)]
+pub enum SwitchProjectError {
+    /// Nothing was recorded; the whole switch can be retried
+    ClockOut(anyhow::Error),
+    /// The previous period is closed; only the clock-in is still missing
+    ClockIn(anyhow::Error),
+}
+
 pub struct ClockService {
-    client: reqwest::Client,
+    api: Z8ApiClient,
 }
 
 fn clock_in_body(
     work_location_type: WorkLocationType,
-    timestamp: Option<&str>,
+    details: &PunchDetails,
+    capture: &PunchCapture,
+    replay: Option<&ReplayChain>,
 ) -> serde_json::Value {
     let mut body = serde_json::json!({
         "type": "clock_in",
         "workLocationType": work_location_type.as_str(),
     });
 
-    if let Some(timestamp) = timestamp {
-        body["timestamp"] = serde_json::Value::String(timestamp.to_string());
-    }
+    details.apply_to(&mut body);
+    capture.apply_to(&mut body, replay.is_some());
+    apply_chain(&mut body, replay);
 
     body
 }
 
+fn clock_out_body(
+    details: &PunchDetails,
+    capture: &PunchCapture,
+    replay: Option<&ReplayChain>,
+) -> serde_json::Value {
+    let mut body = serde_json::json!({
+        "type": "clock_out",
+    });
+
+    details.apply_to(&mut body);
+    capture.apply_to(&mut body, replay.is_some());
+    apply_chain(&mut body, replay);
+
+    body
+}
+
+/// Queued punches carry the hash chain links of the offline queue up to their own
+fn apply_chain(body: &mut serde_json::Value, replay: Option<&ReplayChain>) {
+    if let Some(chain) = replay {
+        body["chain"] = serde_json::json!(chain);
+    }
+}
+
 impl ClockService {
-    pub fn new() -> Self {
-        Self {
-            client: reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(30))
-                .build()
-                .unwrap_or_else(|_| reqwest::Client::new()),
-        }
+    pub fn new(api: Z8ApiClient) -> Self {
+        Self { api }
     }
 
     /// Fetches current clock status from the webapp
-    pub async fn get_status(&self, webapp_url: &str, token: &str) -> Result<ClockStatus> {
-        let url = format!("{}/api/time-entries/status", webapp_url.trim_end_matches('/'));
-
+    pub async fn get_status(&self) -> Result<ClockStatus> {
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
+            .api
+            .send(ApiRequest::get("/api/time-entries/status").timeout(STATUS_TIMEOUT))
             .await?;
 
-        if !response.status().is_success() {
+        if !response.is_success() {
             return Err(anyhow::anyhow!(
                 "Failed to fetch clock status: {}",
-                response.status()
+                response.status
             ));
         }
 
-        let status: ClockStatus = response.json().await?;
-        Ok(status)
+        response.json()
     }
 
-    /// Clocks in the user
-    pub async fn clock_in(
+    /// A punch sent without its action id (its capture was too old or had no zone)
+    /// can't be deduplicated by the webapp, so when its answer was lost this asks the
+    /// webapp whether it was recorded: `recorded` counts as sent, `untouched` turns the
+    /// error into a network error so the punch can be queued, and anything else keeps
+    /// the unknown outcome for the user to check. Punches with an id were already
+    /// resent under it and reach here as sent or offline.
+    pub async fn settle<T>(
         &self,
-        webapp_url: &str,
-        token: &str,
-        work_location_type: WorkLocationType,
-        timestamp: Option<&str>,
-    ) -> Result<TimeEntry> {
-        let url = format!("{}/api/time-entries", webapp_url.trim_end_matches('/'));
-
-        let body = clock_in_body(work_location_type, timestamp);
+        result: Result<T>,
+        recorded: impl Fn(&ClockStatus) -> bool,
+        untouched: impl Fn(&ClockStatus) -> bool,
+    ) -> Result<()> {
+        let error = match result {
+            Ok(_) => return Ok(()),
+            Err(e) if is_unknown_outcome(&e) => e,
+            Err(e) => return Err(e),
+        };
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
+        match self.get_status().await {
+            Ok(status) if recorded(&status) => Ok(()),
+            Ok(status) if untouched(&status) => Err(ApiError::Network(error.to_string()).into()),
+            Ok(_) => Err(error),
+            Err(e) => {
+                log::warn!("Failed to check a punch without an answer: {}", e);
+                Err(error)
+            }
+        }
+    }
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!("Clock in failed: {}", error_text));
+    /// Works out which half of a switch an unanswered punch left to send. The
+    /// clock-in is only sent once the clock-out was answered.
+    pub async fn settle_switch(
+        &self,
+        result: std::result::Result<(), SwitchProjectError>,
+        switched_at: DateTime<Utc>,
+    ) -> std::result::Result<(), SwitchProjectError> {
+        match result {
+            Err(SwitchProjectError::ClockOut(e)) if is_unknown_outcome(&e) => {
+                let settled = self
+                    .settle(
+                        Err::<(), _>(e),
+                        |status| !status.is_clocked_in,
+                        |status| status.started_before(switched_at),
+                    )
+                    .await;
+                match settled {
+                    Ok(()) => Err(SwitchProjectError::ClockIn(
+                        ApiError::Network("clock-in for the project switch not sent".to_string())
+                            .into(),
+                    )),
+                    Err(e) => Err(SwitchProjectError::ClockOut(e)),
+                }
+            }
+            Err(SwitchProjectError::ClockIn(e)) => self
+                .settle(
+                    Err::<(), _>(e),
+                    |status| status.started_at(switched_at),
+                    |status| !status.is_clocked_in,
+                )
+                .await
+                .map_err(SwitchProjectError::ClockIn),
+            result => result,
         }
+    }
 
-        let result: serde_json::Value = response.json().await?;
-        let entry = serde_json::from_value(result["entry"].clone())?;
-        Ok(entry)
+    /// Opens the webapp's server-sent events channel for status changes; `None` when
+    /// the webapp doesn't offer one
+    pub async fn status_stream(&self) -> Result<Option<ApiStream>> {
+        let request = ApiRequest::get("/api/desktop/clock-status/stream")
+            .header("Accept", "text/event-stream")
+            .timeout(STREAM_TIMEOUT);
+        let stream = self.api.open_stream(request).await?;
+
+        match stream.status {
+            404 | 405 | 501 => Ok(None),
+            _ if stream.is_success() => Ok(Some(stream)),
+            status => Err(anyhow::anyhow!("Failed to open status stream: {}", status)),
+        }
     }
 
-    /// Clocks out the user
-    pub async fn clock_out(&self, webapp_url: &str, token: &str) -> Result<TimeEntry> {
-        let url = format!("{}/api/time-entries", webapp_url.trim_end_matches('/'));
+    /// Lists the user's current (non-superseded) entries between `from` and `to`
+    pub async fn list_entries(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<TimeEntry>> {
+        let request = ApiRequest::get("/api/time-entries")
+            .query("from", &from.to_rfc3339())
+            .query("to", &to.to_rfc3339());
+        let response = self.api.send(request).await?;
 
-        let body = serde_json::json!({
-            "type": "clock_out",
-        });
+        if !response.is_success() {
+            return Err(anyhow::anyhow!(
+                "Failed to fetch time entries: {}",
+                response.status
+            ));
+        }
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
+        let payload: TimeEntriesResponse = response.json()?;
+        Ok(payload.entries)
+    }
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!("Clock out failed: {}", error_text));
+    /// Posts a single punch and returns the created entry
+    /// Sends a punch. One that carries its action id is resent once when its answer
+    /// was lost: the webapp returns the entry already recorded for that id or records
+    /// it now. If the resend goes unanswered too, the punch is reported offline so the
+    /// queue replays it under the same id.
+    async fn post_entry(&self, body: serde_json::Value, failure: &str) -> Result<TimeEntry> {
+        let deduplicated = body.get("id").is_some();
+        let request = ApiRequest::post("/api/time-entries", body).timeout(PUNCH_TIMEOUT);
+        let response = match self.api.send(request.clone()).await {
+            Err(e) if deduplicated && is_unknown_outcome(&e) => {
+                log::info!("Resending a punch without an answer: {}", e);
+                self.api.send(request).await.map_err(|e| {
+                    if is_unknown_outcome(&e) {
+                        ApiError::Network(e.to_string()).into()
+                    } else {
+                        e
+                    }
+                })?
+            }
+            response => response?,
+        };
+
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("{}: {}", failure, response.body));
         }
 
-        let result: serde_json::Value = response.json().await?;
+        let result: serde_json::Value = response.json()?;
         let entry = serde_json::from_value(result["entry"].clone())?;
         Ok(entry)
     }
 
-    /// Clocks out at a specific time (for break handling) then clocks back in
+    /// Clocks in the user. `replay` is set for punches sent from the offline queue.
+    pub async fn clock_in(
+        &self,
+        work_location_type: WorkLocationType,
+        details: &PunchDetails,
+        capture: &PunchCapture,
+        detection: Option<&LocationDetection>,
+        replay: Option<&ReplayChain>,
+    ) -> Result<TimeEntry> {
+        let mut body = clock_in_body(work_location_type, details, capture, replay);
+        if let Some(detection) = detection {
+            body["location"] = serde_json::Value::String(detection.describe(work_location_type));
+        }
+        self.post_entry(body, "Clock in failed").await
+    }
+
+    /// Clocks out the user
+    pub async fn clock_out(
+        &self,
+        details: &PunchDetails,
+        capture: &PunchCapture,
+        replay: Option<&ReplayChain>,
+    ) -> Result<TimeEntry> {
+        let body = clock_out_body(details, capture, replay);
+        self.post_entry(body, "Clock out failed").await
+    }
+
+    /// Clocks out at the break start (for break handling) then clocks back in
     pub async fn clock_out_with_break(
         &self,
-        webapp_url: &str,
-        token: &str,
-        break_start_time: DateTime<Utc>,
+        break_capture: &PunchCapture,
         work_location_type: WorkLocationType,
-        resume_timestamp: Option<&str>,
+        details: &PunchDetails,
+        resume_capture: &PunchCapture,
+        replay: Option<&ReplayChain>,
     ) -> Result<()> {
-        let url = format!("{}/api/time-entries", webapp_url.trim_end_matches('/'));
+        self.post_entry(
+            clock_out_body(details, break_capture, replay),
+            "Clock out for break failed",
+        )
+        .await?;
 
-        // First, clock out at the break start time
-        let clock_out_body = serde_json::json!({
-            "type": "clock_out",
-            "timestamp": break_start_time.to_rfc3339(),
-        });
+        self.post_entry(
+            clock_in_body(work_location_type, details, resume_capture, replay),
+            "Clock in after break failed",
+        )
+        .await?;
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&clock_out_body)
-            .send()
-            .await?;
+        Ok(())
+    }
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!("Clock out for break failed: {}", error_text));
+    /// Closes the current period and opens a new one for another project at the same instant
+    pub async fn switch_project(
+        &self,
+        work_location_type: WorkLocationType,
+        previous: &PunchDetails,
+        next: &PunchDetails,
+        clock_out_capture: &PunchCapture,
+        clock_in_capture: &PunchCapture,
+        replay: Option<&ReplayChain>,
+    ) -> std::result::Result<(), SwitchProjectError> {
+        self.post_entry(
+            clock_out_body(previous, clock_out_capture, replay),
+            "Clock out for project switch failed",
+        )
+        .await
+        .map_err(SwitchProjectError::ClockOut)?;
+
+        self.post_entry(
+            clock_in_body(work_location_type, next, clock_in_capture, replay),
+            "Clock in for project switch failed",
+        )
+        .await
+        .map_err(SwitchProjectError::ClockIn)?;
+
+        Ok(())
+    }
+
+    /// Submits a correction; it is applied directly when the user may approve their own time
+    pub async fn submit_correction(
+        &self,
+        request: &CorrectionRequest,
+    ) -> Result<CorrectionSubmission> {
+        let response = self.api.send(request.to_api_request()).await?;
+
+        if !response.is_success() {
+            return Err(anyhow::anyhow!("Correction failed: {}", response.body));
         }
 
-        // Then, clock back in at current time unless replaying a queued resume.
-        let clock_in_body = clock_in_body(work_location_type, resume_timestamp);
+        let result: CorrectionResponse = response.json()?;
+        Ok(CorrectionSubmission {
+            requires_approval: result
+                .requires_approval
+                .unwrap_or(result.approval_id.is_some()),
+            message: result.message,
+            queued: false,
+        })
+    }
 
+    /// Lists the user's recent correction requests, newest first
+    pub async fn list_corrections(&self) -> Result<Vec<Correction>> {
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("Content-Type", "application/json")
-            .json(&clock_in_body)
-            .send()
+            .api
+            .send(ApiRequest::get("/api/desktop/corrections"))
             .await?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
+        if !response.is_success() {
             return Err(anyhow::anyhow!(
-                "Clock in after break failed: {}",
-                error_text
+                "Failed to fetch corrections: {}",
+                response.status
             ));
         }
 
-        Ok(())
+        let payload: CorrectionsResponse = response.json()?;
+        Ok(payload.corrections)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{clock_in_body, WorkLocationType};
+    use super::{
+        clock_in_body, clock_out_body, ClockService, ClockStatus, CorrectionRequest, PunchCapture,
+        PunchDetails, SwitchProjectError, WorkLocationType,
+    };
+    use crate::api::testing::fake_client;
+    use crate::api::{is_network_error, is_unknown_outcome};
+    use crate::chain::{ChainLink, ReplayChain, GENESIS_HASH};
+    use chrono::{DateTime, Duration, Utc};
+
+    fn replay_chain() -> ReplayChain {
+        ReplayChain {
+            public_key: "public-key".to_string(),
+            links: vec![ChainLink {
+                sequence: 1,
+                action: "clock_out".to_string(),
+                timestamp: 1_777_593_600,
+                payload: None,
+                created_at: 1_777_593_600,
+                previous_hash: GENESIS_HASH.to_string(),
+                hash: "hash-1".to_string(),
+                signature: "signature-1".to_string(),
+            }],
+        }
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
 
     #[test]
     fn work_location_type_accepts_only_supported_values() {
@@ -256,22 +782,372 @@ mod tests {
     }
 
     #[test]
-    fn clock_in_body_includes_timestamp_only_when_provided() {
+    fn queued_punches_replace_the_webapp_period() {
+        let server = ClockStatus {
+            has_employee: true,
+            employee_id: Some("employee-1".to_string()),
+            is_clocked_in: true,
+            active_work_period: Some(super::WorkPeriod {
+                id: "period-1".to_string(),
+                start_time: "2026-05-11T08:00:00+00:00".to_string(),
+            }),
+        };
+
+        // A queued clock-in while already clocked in keeps the running period
+        let unchanged = server
+            .clone()
+            .with_punches(&[("clock_in", at("2026-05-11T08:30:00Z"))]);
+        assert_eq!(unchanged.active_work_period.unwrap().id, "period-1");
+
+        // A project switch closes the period and starts one at the switch
+        let switched = server.clone().with_punches(&[
+            ("clock_out", at("2026-05-11T12:00:00Z")),
+            ("clock_in", at("2026-05-11T12:00:00Z")),
+        ]);
+        assert!(switched.is_clocked_in);
+        assert_eq!(switched.employee_id.as_deref(), Some("employee-1"));
+        assert_eq!(
+            switched.active_work_period.unwrap().start_time,
+            "2026-05-11T12:00:00+00:00"
+        );
+
+        let clocked_out = server.with_punches(&[("clock_out", at("2026-05-11T17:00:00Z"))]);
+        assert!(!clocked_out.is_clocked_in);
+        assert!(clocked_out.active_work_period.is_none());
+    }
+
+    #[test]
+    fn punch_bodies_send_capture_evidence_only_inside_the_window() {
+        let details = PunchDetails::default();
+        let live = PunchCapture::in_zone(Utc::now(), Some(chrono_tz::Asia::Kolkata));
+
         assert_eq!(
-            clock_in_body(WorkLocationType::Remote, None),
+            clock_in_body(WorkLocationType::Remote, &details, &live, None),
             serde_json::json!({
                 "type": "clock_in",
                 "workLocationType": "remote",
+                "timestamp": live.timestamp.to_rfc3339(),
+                "browserTimezone": "Asia/Kolkata",
+                "id": live.action_id,
+                "utcOffsetMinutes": 330,
             })
         );
 
+        let queued = PunchCapture::in_zone(
+            Utc::now() - Duration::hours(2),
+            Some(chrono_tz::Asia::Kolkata),
+        );
+        let chain = replay_chain();
+        let replayed = clock_out_body(&details, &queued, Some(&chain));
+        assert_eq!(replayed["id"], queued.action_id.as_str());
+        assert_eq!(replayed["replay"], true);
+        assert_eq!(replayed["chain"]["links"][0]["hash"], "hash-1");
+
+        // Too old for a live punch; the webapp derives the offset from the zone instead
+        let stale = clock_out_body(&details, &queued, None);
         assert_eq!(
-            clock_in_body(WorkLocationType::Remote, Some("2026-05-01T00:00:00+00:00")),
+            stale,
+            serde_json::json!({
+                "type": "clock_out",
+                "timestamp": queued.timestamp.to_rfc3339(),
+                "browserTimezone": "Asia/Kolkata",
+            })
+        );
+    }
+
+    #[test]
+    fn punch_bodies_carry_project_category_and_notes() {
+        let details = PunchDetails::new(
+            Some("project-1".to_string()),
+            Some("category-1".to_string()),
+            Some("  ".to_string()),
+        );
+        let capture = PunchCapture::without_zone(at("2026-05-01T17:00:00Z"));
+
+        assert_eq!(
+            clock_in_body(WorkLocationType::Office, &details, &capture, None),
             serde_json::json!({
                 "type": "clock_in",
-                "workLocationType": "remote",
-                "timestamp": "2026-05-01T00:00:00+00:00",
+                "workLocationType": "office",
+                "projectId": "project-1",
+                "workCategoryId": "category-1",
+                "timestamp": "2026-05-01T17:00:00+00:00",
+            })
+        );
+        assert_eq!(
+            clock_out_body(&details, &capture, None),
+            serde_json::json!({
+                "type": "clock_out",
+                "projectId": "project-1",
+                "workCategoryId": "category-1",
+                "timestamp": "2026-05-01T17:00:00+00:00",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn clock_out_with_break_posts_clock_out_then_clock_in() {
+        let (api, transport) = fake_client();
+        let entry = serde_json::json!({
+            "entry": {
+                "id": "entry-1",
+                "employeeId": "employee-1",
+                "type": "clock_out",
+                "timestamp": "2026-05-09T10:15:30Z",
+            }
+        });
+        transport.respond(201, entry.clone());
+        transport.respond(201, entry);
+
+        let break_capture = PunchCapture::without_zone(at("2026-05-09T10:15:30Z"));
+        let resume_capture = PunchCapture::without_zone(at("2026-05-09T10:45:00Z"));
+        ClockService::new(api)
+            .clock_out_with_break(
+                &break_capture,
+                WorkLocationType::Home,
+                &PunchDetails::default(),
+                &resume_capture,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].url, "https://z8.example/api/time-entries");
+        assert_eq!(
+            requests[0].body,
+            Some(serde_json::json!({
+                "type": "clock_out",
+                "timestamp": "2026-05-09T10:15:30+00:00",
+            }))
+        );
+        assert_eq!(
+            requests[1].body,
+            Some(clock_in_body(
+                WorkLocationType::Home,
+                &PunchDetails::default(),
+                &resume_capture,
+                None
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn clock_in_surfaces_network_failures_for_offline_queueing() {
+        let (api, transport) = fake_client();
+        transport.fail_network();
+
+        let error = ClockService::new(api)
+            .clock_in(
+                WorkLocationType::Office,
+                &PunchDetails::default(),
+                &PunchCapture::now(),
+                None,
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert!(is_network_error(&error));
+    }
+
+    #[tokio::test]
+    async fn settles_unanswered_punches_by_checking_the_status() {
+        let (api, transport) = fake_client();
+        let service = ClockService::new(api);
+        let details = PunchDetails::default();
+        let capture = PunchCapture::without_zone(at("2026-05-09T08:00:00Z"));
+        let clock_in =
+            || service.clock_in(WorkLocationType::Office, &details, &capture, None, None);
+        let status = |start: Option<&str>| {
+            serde_json::json!({
+                "hasEmployee": true,
+                "employeeId": "employee-1",
+                "isClockedIn": start.is_some(),
+                "activeWorkPeriod": start.map(|start| serde_json::json!({
+                    "id": "period-1",
+                    "startTime": start,
+                })),
             })
+        };
+        let recorded = |status: &ClockStatus| status.started_at(capture.timestamp);
+        let untouched = |status: &ClockStatus| !status.is_clocked_in;
+
+        // The clock-in arrived: nothing to queue
+        transport.fail_unanswered();
+        transport.respond(200, status(Some("2026-05-09T08:00:00.000Z")));
+        assert!(service
+            .settle(clock_in().await, recorded, untouched)
+            .await
+            .is_ok());
+
+        // It never arrived: safe to queue
+        transport.fail_unanswered();
+        transport.respond(200, status(None));
+        let error = service
+            .settle(clock_in().await, recorded, untouched)
+            .await
+            .unwrap_err();
+        assert!(is_network_error(&error));
+
+        // Another period is running, or the status can't be read: don't guess
+        transport.fail_unanswered();
+        transport.respond(200, status(Some("2026-05-09T07:00:00Z")));
+        transport.fail_unanswered();
+        transport.fail_network();
+        for _ in 0..2 {
+            let error = service
+                .settle(clock_in().await, recorded, untouched)
+                .await
+                .unwrap_err();
+            assert!(is_unknown_outcome(&error));
+        }
+    }
+
+    #[tokio::test]
+    async fn resends_unanswered_punches_under_their_action_id() {
+        let (api, transport) = fake_client();
+        let service = ClockService::new(api);
+        let details = PunchDetails::default();
+        let capture = PunchCapture::in_zone(Utc::now(), Some(chrono_tz::Europe::Berlin));
+
+        // The resend is answered with the entry, whether or not the first one landed
+        transport.fail_unanswered();
+        transport.respond(
+            201,
+            serde_json::json!({
+                "entry": {
+                    "id": capture.action_id,
+                    "employeeId": "employee-1",
+                    "type": "clock_out",
+                    "timestamp": capture.timestamp.to_rfc3339(),
+                }
+            }),
+        );
+        let entry = service.clock_out(&details, &capture, None).await.unwrap();
+        assert_eq!(entry.id, capture.action_id);
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].body, requests[1].body);
+        assert_eq!(
+            requests[1].body.as_ref().unwrap()["id"],
+            capture.action_id.as_str()
+        );
+
+        // Unanswered twice: offline, so the queue replays it under the same id
+        transport.fail_unanswered();
+        transport.fail_unanswered();
+        let error = service
+            .clock_out(&details, &capture, None)
+            .await
+            .unwrap_err();
+        assert!(is_network_error(&error));
+        assert_eq!(transport.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn switch_project_reports_when_only_the_clock_in_is_missing() {
+        let (api, transport) = fake_client();
+        transport.respond(
+            201,
+            serde_json::json!({
+                "entry": {
+                    "id": "entry-1",
+                    "employeeId": "employee-1",
+                    "type": "clock_out",
+                    "timestamp": "2026-05-09T12:00:00Z",
+                }
+            }),
+        );
+        transport.fail_network();
+
+        let previous = PunchDetails::new(Some("project-a".to_string()), None, None);
+        let next = PunchDetails::new(Some("project-b".to_string()), None, None);
+        let clock_out_capture = PunchCapture::without_zone(at("2026-05-09T12:00:00Z"));
+        let clock_in_capture = PunchCapture::without_zone(at("2026-05-09T12:00:00Z"));
+        let chain = replay_chain();
+        let result = ClockService::new(api)
+            .switch_project(
+                WorkLocationType::Office,
+                &previous,
+                &next,
+                &clock_out_capture,
+                &clock_in_capture,
+                Some(&chain),
+            )
+            .await;
+
+        assert!(matches!(result, Err(SwitchProjectError::ClockIn(_))));
+
+        let requests = transport.requests();
+        assert_eq!(
+            requests[0].body,
+            Some(clock_out_body(&previous, &clock_out_capture, Some(&chain)))
+        );
+        assert_eq!(
+            requests[1].body,
+            Some(clock_in_body(
+                WorkLocationType::Office,
+                &next,
+                &clock_in_capture,
+                Some(&chain)
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn submits_corrections_in_the_local_offset_with_an_idempotency_key() {
+        let (api, transport) = fake_client();
+        transport.respond(
+            201,
+            serde_json::json!({
+                "entry": { "id": "correction-1" },
+                "approvalId": "approval-1",
+                "message": "Correction submitted. Awaiting manager approval.",
+            }),
+        );
+
+        let capture =
+            PunchCapture::in_zone(at("2026-03-02T16:30:00Z"), Some(chrono_tz::Europe::Berlin));
+        let request = CorrectionRequest::Entry {
+            replaces_entry_id: "entry-1".to_string(),
+            work_location_type: WorkLocationType::Office,
+            work_category_id: None,
+            reason: "Forgot to clock out".to_string(),
+            capture: capture.clone(),
+        };
+
+        let submission = ClockService::new(api)
+            .submit_correction(&request)
+            .await
+            .unwrap();
+        assert!(submission.requires_approval);
+
+        let sent = &transport.requests()[0];
+        assert_eq!(sent.url, "https://z8.example/api/time-entries/corrections");
+        assert_eq!(
+            sent.header("idempotency-key"),
+            Some(capture.action_id.as_str())
+        );
+        assert_eq!(
+            sent.body,
+            Some(serde_json::json!({
+                "replacesEntryId": "entry-1",
+                "timestamp": "2026-03-02T17:30:00+01:00",
+                "notes": "Forgot to clock out",
+                "workLocationType": "office",
+                "workCategoryId": null,
+                "timezone": "Europe/Berlin",
+            }))
+        );
+
+        // Queued requests survive a round trip through the offline store unchanged
+        let stored = serde_json::to_string(&request).unwrap();
+        let restored: CorrectionRequest = serde_json::from_str(&stored).unwrap();
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap(),
+            serde_json::to_value(&request).unwrap()
         );
     }
 }
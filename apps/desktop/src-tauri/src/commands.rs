@@ -1,15 +1,50 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
 
-use crate::auth;
-use crate::clock::{ClockService, ClockStatus, WorkLocationType};
-use crate::offline::ActionType;
-use crate::settings::Settings;
+use crate::absences::{
+    AbsenceOverview, AbsenceRequest, AbsenceService, MyRequests, ABSENCES_CACHE_KEY,
+};
+use crate::api::{is_network_error, is_unknown_outcome, ApiError};
+use crate::approvals::{self, ApprovalPage, ApprovalService, BulkDecisionResult};
+use crate::auth::{self, Profile};
+use crate::breaks::{BreakPolicy, BreakPolicyService, BREAK_POLICY_CACHE_KEY};
+use crate::clock::{
+    ClockService, ClockStatus, Correction, CorrectionRequest, CorrectionSubmission, PunchCapture,
+    PunchDetails, SwitchProjectError, WorkLocationType,
+};
+use crate::control;
+use crate::deep_link;
+use crate::device::{Device, DeviceService};
+use crate::end_of_day;
+use crate::journal::{
+    self, EventSource, Journal, JournalEntry, JournalEvent, JournalKind, JournalOutcome,
+    JournalQuery,
+};
+use crate::location::{self, LocationDetection, LocationRule, LocationSuggestion, NetworkSignals};
+use crate::network::{self, ConnectionTestResult, NetworkConfig};
+use crate::offline::{
+    ActionType, Cached, ClockInPayload, ClockOutPayload, ClockOutWithBreakPayload, OfflineQueue,
+    QueuedAction, SwitchProjectPayload, UnsentAction,
+};
+use crate::organizations::{OrganizationList, OrganizationService};
+use crate::projects::{Project, ProjectList, ProjectService, PROJECTS_CACHE_KEY};
+use crate::schedule::{self, PunchWarning, ScheduleService, ScheduleView, SCHEDULE_CACHE_KEY};
+use crate::server_notifications::{self, NotificationPage, NotificationService};
+use crate::settings::NetworkSettings;
+use crate::shortcuts;
 use crate::startup;
 use crate::state::AppState;
+use crate::timeline::{self, DayTimeline, TimelineService, WeekSummary};
+use crate::timezone;
 use crate::tray;
+use crate::triggers::{self, FiredTrigger, TriggerRule};
+use crate::wellness::{
+    self, WaterAction, WaterActionResult, WaterReminderStatus, WellnessService,
+    WATER_STATUS_CACHE_KEY,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -17,240 +52,1218 @@ pub struct SettingsResponse {
     pub webapp_url: String,
     pub always_on_top: bool,
     pub auto_startup: bool,
+    pub network: NetworkSettings,
+    pub switch_project_shortcut: Option<String>,
+    pub weekly_target_hours: f64,
+    pub break_reminders_enabled: bool,
+    pub shift_reminders_enabled: bool,
+    pub end_of_day_time: Option<String>,
+    pub end_of_day_idle_minutes: u32,
+    pub location_rules: Vec<LocationRule>,
+    /// Rules deployed by IT; read-only in the app
+    pub managed_location_rules: Vec<LocationRule>,
+    pub trigger_rules: Vec<TriggerRule>,
+    pub control_api_enabled: bool,
+    /// Where clients find the control API's address and token
+    pub control_api_file: String,
     pub version: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionResponse {
-    pub token: Option<String>,
     pub is_authenticated: bool,
+    /// Who is signed in; `None` until the webapp first answered
+    pub profile: Option<Profile>,
+}
+
+/// Builds a clock service on the shared API client, failing early when the session isn't usable
+fn clock_service(state: &AppState) -> Result<ClockService, String> {
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
+    }
+
+    if state.get_webapp_url().is_empty() {
+        return Err("Webapp URL not configured".to_string());
+    }
+
+    Ok(ClockService::new(state.api.clone()))
+}
+
+/// Reads the current Wi-Fi, gateway and interfaces, e.g. to create a rule for this network
+#[tauri::command]
+pub async fn get_network_signals() -> Result<NetworkSignals, String> {
+    tauri::async_runtime::spawn_blocking(location::read_signals)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Suggests a work location from the user's and IT's rules for the current network
+#[tauri::command]
+pub async fn suggest_work_location(
+    app_handle: AppHandle,
+) -> Result<Option<LocationSuggestion>, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let mut rules = state.settings.read().location_rules.clone();
+    rules.extend(location::managed_rules());
+    if rules.is_empty() {
+        return Ok(None);
+    }
+
+    let signals = get_network_signals().await?;
+    Ok(location::suggest(&rules, &signals))
 }
 
 /// Fetches the current clock status from the webapp
 #[tauri::command]
 pub async fn get_clock_status(app_handle: AppHandle) -> Result<ClockStatus, String> {
     let state = app_handle.state::<Arc<AppState>>();
+    let clock_service = clock_service(&state)?;
 
-    let token = state
-        .get_session_token()
-        .ok_or("Not authenticated".to_string())?;
+    // Punches still waiting in the offline queue are newer than the webapp's view.
+    // Offline, the last status the webapp reported stands in for it.
+    let status = match clock_service.get_status().await {
+        Ok(status) => state.remember_clock_status(&status),
+        Err(e) if is_network_error(&e) => state.local_clock_status(),
+        Err(e) => return Err(e.to_string()),
+    };
 
-    let webapp_url = state.get_webapp_url();
-    if webapp_url.is_empty() {
-        return Err("Webapp URL not configured".to_string());
+    // Update local state
+    state.set_clocked_in(status.is_clocked_in);
+
+    // Update tray icon
+    let _ = tray::update_tray_icon(&app_handle, status.is_clocked_in);
+
+    Ok(status)
+}
+
+/// Whether a punch reached the webapp or waits in the offline queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Punched {
+    Sent,
+    Queued,
+}
+
+/// Sends clock-ins and clock-outs, queueing them while the webapp is unreachable
+/// and journaling the outcome. Shared by the commands and the headless command line.
+pub struct Puncher<'a> {
+    clock_service: &'a ClockService,
+    queue: &'a Mutex<OfflineQueue>,
+    journal: &'a Mutex<Journal>,
+    source: EventSource,
+}
+
+impl<'a> Puncher<'a> {
+    fn new(state: &'a AppState, clock_service: &'a ClockService, source: EventSource) -> Self {
+        Self::with_storage(clock_service, &state.offline_queue, &state.journal, source)
     }
 
-    let clock_service = ClockService::new();
-    let status = clock_service
-        .get_status(&webapp_url, &token)
+    pub fn with_storage(
+        clock_service: &'a ClockService,
+        queue: &'a Mutex<OfflineQueue>,
+        journal: &'a Mutex<Journal>,
+        source: EventSource,
+    ) -> Self {
+        Self {
+            clock_service,
+            queue,
+            journal,
+            source,
+        }
+    }
+
+    pub async fn clock_in(
+        &self,
+        work_location_type: WorkLocationType,
+        details: &PunchDetails,
+        capture: PunchCapture,
+        detection: Option<LocationDetection>,
+    ) -> anyhow::Result<Punched> {
+        let result = match self.queued_ahead() {
+            Some(waiting) => Err(waiting),
+            None => {
+                let result = self
+                    .clock_service
+                    .clock_in(
+                        work_location_type,
+                        details,
+                        &capture,
+                        detection.as_ref(),
+                        None,
+                    )
+                    .await;
+                self.clock_service
+                    .settle(
+                        result,
+                        |status| status.started_at(capture.timestamp),
+                        |status| !status.is_clocked_in,
+                    )
+                    .await
+            }
+        };
+        self.record(
+            JournalEvent::punch(self.source, "clock_in", result.as_ref().err()).details(
+                serde_json::json!({
+                    "at": capture.timestamp,
+                    "workLocationType": work_location_type.as_str(),
+                    "details": details,
+                }),
+            ),
+        );
+
+        match result {
+            Ok(()) => Ok(Punched::Sent),
+            Err(e) if is_network_error(&e) => {
+                let queued_at = capture.timestamp.timestamp();
+                let payload = ClockInPayload {
+                    work_location_type: work_location_type.as_str().to_string(),
+                    details: details.clone(),
+                    capture: Some(capture),
+                    detection,
+                };
+                self.queue.lock().enqueue(
+                    ActionType::ClockIn,
+                    queued_at,
+                    Some(serde_json::to_string(&payload)?),
+                )?;
+                Ok(Punched::Queued)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// `action` names the punch in the journal, e.g. `start_break`
+    pub async fn clock_out(
+        &self,
+        action: &str,
+        details: &PunchDetails,
+        capture: PunchCapture,
+    ) -> anyhow::Result<Punched> {
+        let result = match self.queued_ahead() {
+            Some(waiting) => Err(waiting),
+            None => {
+                let result = self.clock_service.clock_out(details, &capture, None).await;
+                self.clock_service
+                    .settle(
+                        result,
+                        |status| !status.is_clocked_in,
+                        |status| status.started_before(capture.timestamp),
+                    )
+                    .await
+            }
+        };
+        self.record(
+            JournalEvent::punch(self.source, action, result.as_ref().err()).details(
+                serde_json::json!({
+                    "at": capture.timestamp,
+                    "details": details,
+                }),
+            ),
+        );
+
+        match result {
+            Ok(()) => Ok(Punched::Sent),
+            Err(e) if is_network_error(&e) => {
+                let queued_at = capture.timestamp.timestamp();
+                let payload = ClockOutPayload {
+                    details: details.clone(),
+                    capture: Some(capture),
+                };
+                self.queue.lock().enqueue(
+                    ActionType::ClockOut,
+                    queued_at,
+                    Some(serde_json::to_string(&payload)?),
+                )?;
+                Ok(Punched::Queued)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Punches still in the queue go first: sending a new one live would record it
+    /// ahead of them. Reported like a network error, so it is queued behind them.
+    fn queued_ahead(&self) -> Option<anyhow::Error> {
+        match self.queue.lock().pending_punches() {
+            Ok(punches) if punches.is_empty() => None,
+            Ok(_) => Some(ApiError::Network("Earlier punches are still queued".to_string()).into()),
+            Err(e) => {
+                log::warn!("Failed to read offline queue: {}", e);
+                None
+            }
+        }
+    }
+
+    fn record(&self, event: JournalEvent) {
+        if let Err(e) = self.journal.lock().append(event, Utc::now()) {
+            log::warn!("Failed to write journal entry: {}", e);
+        }
+    }
+}
+
+/// Clocks in the user, optionally against a project and work category
+#[tauri::command]
+pub async fn clock_in(
+    app_handle: AppHandle,
+    work_location_type: String,
+    project_id: Option<String>,
+    work_category_id: Option<String>,
+    notes: Option<String>,
+    location_suggestion: Option<LocationSuggestion>,
+    source: Option<EventSource>,
+) -> Result<ClockStatus, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let clock_service = clock_service(&state)?;
+
+    let work_location_type = WorkLocationType::from_str(&work_location_type)
+        .ok_or("Invalid work location type".to_string())?;
+    let details = PunchDetails::new(project_id, work_category_id, notes);
+    let capture = PunchCapture::now();
+    let detection = LocationDetection::for_choice(work_location_type, location_suggestion);
+
+    let punched = Puncher::new(&state, &clock_service, source.unwrap_or_default())
+        .clock_in(work_location_type, &details, capture, detection)
         .await
         .map_err(|e| e.to_string())?;
+    state.set_active_punch_details(details);
+
+    let status = match punched {
+        Punched::Sent => {
+            let status = clock_service
+                .get_status()
+                .await
+                .map_err(|e| e.to_string())?;
+            state.remember_clock_status(&status)
+        }
+        // Optimistically update local state
+        Punched::Queued => {
+            state.process_queue_soon();
+            state.local_clock_status()
+        }
+    };
 
-    // Update local state
     state.set_clocked_in(status.is_clocked_in);
+    let _ = tray::update_tray_icon(&app_handle, status.is_clocked_in);
 
-    // Update tray icon
+    Ok(status)
+}
+
+/// Clocks out the user. Project, category and notes default to the clock-in selection.
+#[tauri::command]
+pub async fn clock_out(
+    app_handle: AppHandle,
+    project_id: Option<String>,
+    work_category_id: Option<String>,
+    notes: Option<String>,
+    source: Option<EventSource>,
+) -> Result<ClockStatus, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let details =
+        PunchDetails::new(project_id, work_category_id, notes).or(&state.active_punch_details());
+
+    finish_clock_out(
+        &app_handle,
+        details,
+        PunchCapture::now(),
+        source.unwrap_or_default(),
+        "clock_out",
+    )
+    .await
+}
+
+/// Clocks out but keeps the project, so clocking in after the break continues it
+pub async fn start_break(
+    app_handle: AppHandle,
+    source: EventSource,
+) -> Result<ClockStatus, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let details = state.active_punch_details();
+    let status = finish_clock_out(
+        &app_handle,
+        details.clone(),
+        PunchCapture::now(),
+        source,
+        "start_break",
+    )
+    .await?;
+    state.set_active_punch_details(details);
+    Ok(status)
+}
+
+/// Clocks out retroactively, e.g. at the last activity time after forgetting to clock out
+#[tauri::command]
+pub async fn clock_out_at(
+    app_handle: AppHandle,
+    clock_out_time: String,
+    source: Option<EventSource>,
+) -> Result<ClockStatus, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+
+    let clock_out_time: DateTime<Utc> = DateTime::parse_from_rfc3339(&clock_out_time)
+        .map_err(|e| format!("Invalid clock-out time: {}", e))?
+        .with_timezone(&Utc);
+    if clock_out_time > Utc::now() {
+        return Err("Clock-out time is in the future".to_string());
+    }
+
+    let details = state.active_punch_details();
+    finish_clock_out(
+        &app_handle,
+        details,
+        PunchCapture::at(clock_out_time),
+        source.unwrap_or_default(),
+        "clock_out_at",
+    )
+    .await
+}
+
+async fn finish_clock_out(
+    app_handle: &AppHandle,
+    details: PunchDetails,
+    capture: PunchCapture,
+    source: EventSource,
+    action: &str,
+) -> Result<ClockStatus, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let clock_service = clock_service(&state)?;
+
+    let punched = Puncher::new(&state, &clock_service, source)
+        .clock_out(action, &details, capture)
+        .await
+        .map_err(|e| e.to_string())?;
+    state.set_active_punch_details(PunchDetails::default());
+
+    let status = match punched {
+        Punched::Sent => {
+            let status = clock_service
+                .get_status()
+                .await
+                .map_err(|e| e.to_string())?;
+            state.remember_clock_status(&status)
+        }
+        Punched::Queued => {
+            state.process_queue_soon();
+            state.local_clock_status()
+        }
+    };
+
+    state.set_clocked_in(status.is_clocked_in);
+    let _ = tray::update_tray_icon(app_handle, status.is_clocked_in);
+
+    Ok(status)
+}
+
+/// Clocks out at a specific time (for break handling) then immediately clocks back in
+#[tauri::command]
+pub async fn clock_out_with_break(
+    app_handle: AppHandle,
+    break_start_time: String,
+    work_location_type: String,
+    source: Option<EventSource>,
+) -> Result<ClockStatus, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let clock_service = clock_service(&state)?;
+
+    let break_time: DateTime<Utc> = DateTime::parse_from_rfc3339(&break_start_time)
+        .map_err(|e| format!("Invalid break time: {}", e))?
+        .with_timezone(&Utc);
+
+    let work_location_type = WorkLocationType::from_str(&work_location_type)
+        .ok_or("Invalid work location type".to_string())?;
+
+    // The same project continues after the break
+    let details = state.active_punch_details();
+    let break_capture = PunchCapture::at(break_time);
+    let resume_capture = PunchCapture::now();
+
+    let result = clock_service
+        .clock_out_with_break(
+            &break_capture,
+            work_location_type,
+            &details,
+            &resume_capture,
+            None,
+        )
+        .await;
+    // Only both punches arriving restarts the period at the resume time
+    let result = clock_service
+        .settle(
+            result,
+            |status| status.started_at(resume_capture.timestamp),
+            |status| status.started_before(break_capture.timestamp),
+        )
+        .await;
+    journal::record(
+        &state,
+        JournalEvent::sent(
+            JournalKind::Break,
+            source.unwrap_or_default(),
+            "clock_out_with_break",
+            result.as_ref().err(),
+        )
+        .details(serde_json::json!({
+            "breakStart": break_capture.timestamp,
+            "resumedAt": resume_capture.timestamp,
+            "workLocationType": work_location_type.as_str(),
+            "details": &details,
+        })),
+    );
+
+    match result {
+        Ok(()) => {
+            let status = clock_service
+                .get_status()
+                .await
+                .map_err(|e| e.to_string())?;
+            let status = state.remember_clock_status(&status);
+
+            state.set_clocked_in(status.is_clocked_in);
+            let _ = tray::update_tray_icon(&app_handle, status.is_clocked_in);
+
+            Ok(status)
+        }
+        Err(e) => {
+            if is_network_error(&e) {
+                let queued_at = resume_capture.timestamp.timestamp();
+                let payload = ClockOutWithBreakPayload {
+                    break_start_time,
+                    work_location_type: work_location_type.as_str().to_string(),
+                    details,
+                    break_capture: Some(break_capture),
+                    resume_capture: Some(resume_capture),
+                };
+                let mut queue = state.offline_queue.lock();
+                let _ = queue.enqueue(
+                    ActionType::ClockOutWithBreak,
+                    queued_at,
+                    serde_json::to_string(&payload).ok(),
+                );
+                drop(queue);
+
+                // Remain clocked in since we'll clock back in after break
+                Ok(state.local_clock_status())
+            } else {
+                Err(e.to_string())
+            }
+        }
+    }
+}
+
+/// Closes the running period and starts a new one for another project at the same instant
+#[tauri::command]
+pub async fn switch_project(
+    app_handle: AppHandle,
+    work_location_type: String,
+    project_id: Option<String>,
+    work_category_id: Option<String>,
+    notes: Option<String>,
+    source: Option<EventSource>,
+) -> Result<ClockStatus, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let clock_service = clock_service(&state)?;
+
+    if !state.is_clocked_in() {
+        return Err("Not clocked in".to_string());
+    }
+
+    let work_location_type = WorkLocationType::from_str(&work_location_type)
+        .ok_or("Invalid work location type".to_string())?;
+
+    let previous = state.active_punch_details();
+    let next = PunchDetails::new(project_id, work_category_id, notes);
+    let switched_at = Utc::now();
+    let clock_out_capture = PunchCapture::at(switched_at);
+    let clock_in_capture = PunchCapture::at(switched_at);
+
+    let result = clock_service
+        .switch_project(
+            work_location_type,
+            &previous,
+            &next,
+            &clock_out_capture,
+            &clock_in_capture,
+            None,
+        )
+        .await;
+    let result = clock_service.settle_switch(result, switched_at).await;
+    let error = result.as_ref().err().map(|e| match e {
+        SwitchProjectError::ClockOut(e) | SwitchProjectError::ClockIn(e) => e,
+    });
+    journal::record(
+        &state,
+        JournalEvent::punch(source.unwrap_or_default(), "switch_project", error).details(
+            serde_json::json!({
+                "at": switched_at,
+                "workLocationType": work_location_type.as_str(),
+                "previous": &previous,
+                "next": &next,
+            }),
+        ),
+    );
+
+    let queued = match result {
+        Ok(()) => None,
+        Err(SwitchProjectError::ClockOut(e)) if is_network_error(&e) => {
+            let payload = SwitchProjectPayload {
+                work_location_type: work_location_type.as_str().to_string(),
+                previous,
+                next: next.clone(),
+                clock_out_capture,
+                clock_in_capture,
+            };
+            Some((
+                ActionType::SwitchProject,
+                serde_json::to_string(&payload).ok(),
+            ))
+        }
+        Err(SwitchProjectError::ClockIn(e)) if is_network_error(&e) => {
+            // The previous period is closed; only the clock-in still has to reach the server
+            let payload = ClockInPayload {
+                work_location_type: work_location_type.as_str().to_string(),
+                details: next.clone(),
+                capture: Some(clock_in_capture),
+                detection: None,
+            };
+            Some((ActionType::ClockIn, serde_json::to_string(&payload).ok()))
+        }
+        Err(SwitchProjectError::ClockOut(e)) => return Err(e.to_string()),
+        Err(SwitchProjectError::ClockIn(e)) => {
+            state.set_active_punch_details(PunchDetails::default());
+            state.set_clocked_in(false);
+            let _ = tray::update_tray_icon(&app_handle, false);
+            return Err(e.to_string());
+        }
+    };
+
+    state.set_active_punch_details(next);
+
+    if let Some((action_type, payload)) = queued {
+        let mut queue = state.offline_queue.lock();
+        let _ = queue.enqueue(action_type, switched_at.timestamp(), payload);
+        drop(queue);
+
+        // Optimistically stay clocked in on the new project
+        return Ok(state.local_clock_status());
+    }
+
+    let status = clock_service
+        .get_status()
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = state.remember_clock_status(&status);
+
+    state.set_clocked_in(status.is_clocked_in);
     let _ = tray::update_tray_icon(&app_handle, status.is_clocked_in);
 
     Ok(status)
 }
 
-/// Clocks in the user
+/// Snapshot of the offline queue so unsynced punches show up in totals
+fn pending_actions(state: &AppState) -> Vec<QueuedAction> {
+    match state.offline_queue.lock().get_pending() {
+        Ok(actions) => actions,
+        Err(e) => {
+            log::warn!("Failed to read offline queue: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Returns today's punches with worked and break time
+#[tauri::command]
+pub async fn get_today_timeline(app_handle: AppHandle) -> Result<DayTimeline, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
+    }
+
+    let queued = pending_actions(&state);
+    TimelineService::new(state.api.clone())
+        .today(&queued)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Returns worked time per day this week against the employee's weekly target. The
+/// target comes from their contract or work schedule; the setting only applies when
+/// the webapp has none.
+#[tauri::command]
+pub async fn get_week_summary(app_handle: AppHandle) -> Result<WeekSummary, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
+    }
+
+    let queued = pending_actions(&state);
+    let target_minutes = BreakPolicyService::new(state.api.clone())
+        .weekly_target_minutes(timeline::week_start(Utc::now()))
+        .await
+        .map_err(|e| e.to_string())?
+        .unwrap_or_else(|| (state.settings.read().weekly_target_hours * 60.0).round() as i64);
+    TimelineService::new(state.api.clone())
+        .week(&queued, target_minutes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Asks to move an existing clock-in or clock-out, e.g. after punching late
+#[tauri::command]
+pub async fn submit_correction(
+    app_handle: AppHandle,
+    replaces_entry_id: String,
+    timestamp: String,
+    reason: String,
+    work_location_type: String,
+    work_category_id: Option<String>,
+) -> Result<CorrectionSubmission, String> {
+    let timestamp: DateTime<Utc> = DateTime::parse_from_rfc3339(&timestamp)
+        .map_err(|e| format!("Invalid time: {}", e))?
+        .with_timezone(&Utc);
+    if timestamp > Utc::now() {
+        return Err("Corrected time is in the future".to_string());
+    }
+
+    let work_location_type = WorkLocationType::from_str(&work_location_type)
+        .ok_or("Invalid work location type".to_string())?;
+
+    let request = CorrectionRequest::Entry {
+        replaces_entry_id,
+        work_location_type,
+        work_category_id: work_category_id.filter(|id| !id.is_empty()),
+        reason: required_reason(reason)?,
+        capture: PunchCapture::at(timestamp),
+    };
+    send_correction(&app_handle, request).await
+}
+
+/// Asks to add a work period the user forgot to punch entirely
+#[tauri::command]
+pub async fn submit_missing_period(
+    app_handle: AppHandle,
+    date: String,
+    clock_in_time: String,
+    clock_out_time: String,
+    reason: String,
+    project_id: Option<String>,
+    work_category_id: Option<String>,
+) -> Result<CorrectionSubmission, String> {
+    let date =
+        NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| format!("Invalid date: {}", e))?;
+    let clock_in_time = NaiveTime::parse_from_str(&clock_in_time, "%H:%M")
+        .map_err(|e| format!("Invalid clock-in time: {}", e))?;
+    let clock_out_time = NaiveTime::parse_from_str(&clock_out_time, "%H:%M")
+        .map_err(|e| format!("Invalid clock-out time: {}", e))?;
+    if clock_out_time <= clock_in_time {
+        return Err("Clock-out must be after clock-in".to_string());
+    }
+
+    let request = CorrectionRequest::MissingPeriod {
+        submission_id: uuid::Uuid::new_v4().to_string(),
+        date,
+        clock_in_time,
+        clock_out_time,
+        timezone: timezone::device_timezone().map(|zone| zone.name().to_string()),
+        project_id: project_id.filter(|id| !id.is_empty()),
+        work_category_id: work_category_id.filter(|id| !id.is_empty()),
+        reason: required_reason(reason)?,
+    };
+    send_correction(&app_handle, request).await
+}
+
+fn required_reason(reason: String) -> Result<String, String> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err("A reason is required".to_string());
+    }
+    Ok(reason.to_string())
+}
+
+/// Sends a correction, queueing it while the webapp is unreachable
+async fn send_correction(
+    app_handle: &AppHandle,
+    request: CorrectionRequest,
+) -> Result<CorrectionSubmission, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let clock_service = clock_service(&state)?;
+
+    // The webapp deduplicates corrections by their idempotency key, so one that
+    // went unanswered can be queued and sent again
+    match clock_service.submit_correction(&request).await {
+        Ok(submission) => Ok(submission),
+        Err(e) if is_network_error(&e) || is_unknown_outcome(&e) => {
+            let payload = serde_json::to_string(&request).ok();
+            let mut queue = state.offline_queue.lock();
+            queue
+                .enqueue(
+                    ActionType::SubmitCorrection,
+                    Utc::now().timestamp(),
+                    payload,
+                )
+                .map_err(|e| e.to_string())?;
+
+            Ok(CorrectionSubmission {
+                requires_approval: true,
+                message: "Saved offline. It will be submitted once you are back online."
+                    .to_string(),
+                queued: true,
+            })
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Lists the user's correction requests with their approval state
+#[tauri::command]
+pub async fn list_corrections(app_handle: AppHandle) -> Result<Vec<Correction>, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    clock_service(&state)?
+        .list_corrections()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Builds an absence service, failing early when the session isn't usable
+fn absence_service(state: &AppState) -> Result<AbsenceService, String> {
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
+    }
+
+    Ok(AbsenceService::new(state.api.clone()))
+}
+
+/// Returns absence categories, this year's absences and the vacation balance,
+/// falling back to the cached overview when offline
+#[tauri::command]
+pub async fn list_absences(app_handle: AppHandle) -> Result<AbsenceOverview, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+
+    match absence_service(&state)?.overview().await {
+        Ok(overview) => {
+            let mut queue = state.offline_queue.lock();
+            if let Err(e) = queue.put_cached(ABSENCES_CACHE_KEY, &overview) {
+                log::warn!("Failed to cache absences: {}", e);
+            }
+            Ok(overview)
+        }
+        Err(e) if is_network_error(&e) => {
+            let queue = state.offline_queue.lock();
+            let cached: Option<Cached<AbsenceOverview>> = queue
+                .get_cached(ABSENCES_CACHE_KEY)
+                .map_err(|e| e.to_string())?;
+            cached
+                .map(|cached| cached.value)
+                .ok_or_else(|| e.to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Requests an absence and returns the new request's id
+#[tauri::command]
+pub async fn request_absence(
+    app_handle: AppHandle,
+    request: AbsenceRequest,
+) -> Result<String, String> {
+    if request.end_date < request.start_date {
+        return Err("The end date must not be before the start date".to_string());
+    }
+
+    let state = app_handle.state::<Arc<AppState>>();
+    absence_service(&state)?
+        .request(&request)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Withdraws a pending absence request
+#[tauri::command]
+pub async fn cancel_absence(app_handle: AppHandle, absence_id: String) -> Result<(), String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    absence_service(&state)?
+        .cancel(&absence_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Lists the user's absence, correction and expense requests with their status
+#[tauri::command]
+pub async fn list_my_requests(app_handle: AppHandle) -> Result<MyRequests, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    absence_service(&state)?
+        .my_requests()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Builds a notification service, failing early when the session isn't usable
+fn notification_service(state: &AppState) -> Result<NotificationService, String> {
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
+    }
+
+    Ok(NotificationService::new(state.api.clone()))
+}
+
+/// Lists the user's server notifications, newest first
+#[tauri::command]
+pub async fn list_notifications(
+    app_handle: AppHandle,
+    unread_only: Option<bool>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<NotificationPage, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    notification_service(&state)?
+        .list(
+            unread_only.unwrap_or(false),
+            limit.unwrap_or(20).clamp(1, 100),
+            offset.unwrap_or(0),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Marks a notification read and opens its page in the webapp, if it links to one
+#[tauri::command]
+pub async fn open_notification(
+    app_handle: AppHandle,
+    notification_id: String,
+    action_url: Option<String>,
+) -> Result<(), String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    notification_service(&state)?
+        .mark_read(&notification_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(action) = action_url.filter(|action| !action.is_empty()) {
+        let url = server_notifications::action_url(&state.get_webapp_url(), &action)
+            .map_err(|e| e.to_string())?;
+        server_notifications::open_in_browser(&app_handle, &url).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Marks every server notification read
+#[tauri::command]
+pub async fn mark_all_notifications_read(app_handle: AppHandle) -> Result<(), String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    notification_service(&state)?
+        .mark_all_read()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Builds an approval service, failing early when the session isn't usable
+fn approval_service(state: &AppState) -> Result<ApprovalService, String> {
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
+    }
+
+    Ok(ApprovalService::new(state.api.clone()))
+}
+
+/// Brings the tray's approvals submenu up to date after a decision
+async fn refresh_approvals(app_handle: &AppHandle) {
+    if let Err(e) = approvals::refresh_tray(app_handle).await {
+        log::warn!("Failed to refresh approvals: {}", e);
+    }
+}
+
+/// Lists pending requests in the manager's approval inbox
+#[tauri::command]
+pub async fn list_approvals(
+    app_handle: AppHandle,
+    cursor: Option<String>,
+    limit: Option<usize>,
+) -> Result<ApprovalPage, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    approval_service(&state)?
+        .list(cursor.as_deref(), limit.unwrap_or(20).clamp(1, 100))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Approves a single request
+#[tauri::command]
+pub async fn approve_request(app_handle: AppHandle, approval_id: String) -> Result<(), String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    approval_service(&state)?
+        .approve(&approval_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    refresh_approvals(&app_handle).await;
+    Ok(())
+}
+
+/// Rejects a single request; a reason is required
+#[tauri::command]
+pub async fn reject_request(
+    app_handle: AppHandle,
+    approval_id: String,
+    reason: String,
+) -> Result<(), String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    approval_service(&state)?
+        .reject(&approval_id, &reason)
+        .await
+        .map_err(|e| e.to_string())?;
+    refresh_approvals(&app_handle).await;
+    Ok(())
+}
+
+/// Approves several requests; failures are reported per request
+#[tauri::command]
+pub async fn bulk_approve_requests(
+    app_handle: AppHandle,
+    approval_ids: Vec<String>,
+) -> Result<BulkDecisionResult, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let result = approval_service(&state)?
+        .bulk_approve(&approval_ids)
+        .await
+        .map_err(|e| e.to_string())?;
+    refresh_approvals(&app_handle).await;
+    Ok(result)
+}
+
+/// Rejects several requests with one shared reason; failures are reported per request
 #[tauri::command]
-pub async fn clock_in(
+pub async fn bulk_reject_requests(
     app_handle: AppHandle,
-    work_location_type: String,
-) -> Result<ClockStatus, String> {
+    approval_ids: Vec<String>,
+    reason: String,
+) -> Result<BulkDecisionResult, String> {
     let state = app_handle.state::<Arc<AppState>>();
+    let result = approval_service(&state)?
+        .bulk_reject(&approval_ids, &reason)
+        .await
+        .map_err(|e| e.to_string())?;
+    refresh_approvals(&app_handle).await;
+    Ok(result)
+}
 
-    let token = match state.get_session_token() {
-        Some(t) => t,
-        None => return Err("Not authenticated".to_string()),
-    };
-
-    let webapp_url = state.get_webapp_url();
-    if webapp_url.is_empty() {
-        return Err("Webapp URL not configured".to_string());
+/// Returns the break policy that applies today, falling back to the cached one when offline
+#[tauri::command]
+pub async fn get_break_policy(app_handle: AppHandle) -> Result<Option<BreakPolicy>, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
     }
 
-    let work_location_type = WorkLocationType::from_str(&work_location_type)
-        .ok_or("Invalid work location type".to_string())?;
-
-    let clock_service = ClockService::new();
-
-    // Try to clock in
-    match clock_service
-        .clock_in(&webapp_url, &token, work_location_type, None)
-        .await
-    {
-        Ok(_entry) => {
-            // Fetch updated status
-            let status = clock_service
-                .get_status(&webapp_url, &token)
-                .await
+    match BreakPolicyService::new(state.api.clone()).fetch().await {
+        Ok(policy) => {
+            let mut queue = state.offline_queue.lock();
+            if let Err(e) = queue.put_cached(BREAK_POLICY_CACHE_KEY, &policy) {
+                log::warn!("Failed to cache break policy: {}", e);
+            }
+            Ok(policy)
+        }
+        Err(e) if is_network_error(&e) => {
+            let queue = state.offline_queue.lock();
+            let cached: Option<Cached<Option<BreakPolicy>>> = queue
+                .get_cached(BREAK_POLICY_CACHE_KEY)
                 .map_err(|e| e.to_string())?;
-
-            state.set_clocked_in(status.is_clocked_in);
-            let _ = tray::update_tray_icon(&app_handle, status.is_clocked_in);
-
-            Ok(status)
+            Ok(cached.and_then(|cached| cached.value))
         }
-        Err(e) => {
-            // Check if it's a network error - queue for later
-            if e.to_string().contains("connection")
-                || e.to_string().contains("timeout")
-                || e.to_string().contains("network")
-            {
-                let mut queue = state.offline_queue.lock();
-                let _ = queue.enqueue(
-                    ActionType::ClockIn,
-                    Utc::now().timestamp(),
-                    Some(work_location_type.as_str().to_string()),
-                );
+        Err(e) => Err(e.to_string()),
+    }
+}
 
-                // Optimistically update local state
-                state.set_clocked_in(true);
-                let _ = tray::update_tray_icon(&app_handle, true);
+/// Returns the published shifts and the next one, falling back to the cached one when offline
+#[tauri::command]
+pub async fn get_schedule(app_handle: AppHandle) -> Result<ScheduleView, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
+    }
 
-                Ok(ClockStatus {
-                    has_employee: true,
-                    employee_id: None,
-                    is_clocked_in: true,
-                    active_work_period: None,
-                })
-            } else {
-                Err(e.to_string())
+    match ScheduleService::new(state.api.clone()).fetch().await {
+        Ok(schedule) => {
+            let mut queue = state.offline_queue.lock();
+            if let Err(e) = queue.put_cached(SCHEDULE_CACHE_KEY, &schedule) {
+                log::warn!("Failed to cache schedule: {}", e);
             }
+            Ok(schedule.view(Utc::now()))
         }
+        Err(e) if is_network_error(&e) => Ok(schedule::cached_schedule(&state).view(Utc::now())),
+        Err(e) => Err(e.to_string()),
     }
 }
 
-/// Clocks out the user
+/// Returns the water reminder settings, falling back to the cached ones when offline
 #[tauri::command]
-pub async fn clock_out(app_handle: AppHandle) -> Result<ClockStatus, String> {
+pub async fn get_water_reminder_status(
+    app_handle: AppHandle,
+) -> Result<Option<WaterReminderStatus>, String> {
     let state = app_handle.state::<Arc<AppState>>();
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
+    }
 
-    let token = match state.get_session_token() {
-        Some(t) => t,
-        None => return Err("Not authenticated".to_string()),
-    };
+    match WellnessService::new(state.api.clone()).status().await {
+        Ok(status) => {
+            let mut queue = state.offline_queue.lock();
+            if let Err(e) = queue.put_cached(WATER_STATUS_CACHE_KEY, &status) {
+                log::warn!("Failed to cache water reminder status: {}", e);
+            }
+            Ok(Some(status))
+        }
+        Err(e) if is_network_error(&e) => Ok(wellness::cached_status(&state)),
+        Err(e) => Err(e.to_string()),
+    }
+}
 
-    let webapp_url = state.get_webapp_url();
-    if webapp_url.is_empty() {
-        return Err("Webapp URL not configured".to_string());
+/// Logs glasses of water, queueing the entry while offline
+#[tauri::command]
+pub async fn log_water(
+    app_handle: AppHandle,
+    amount: Option<u32>,
+) -> Result<WaterActionResult, String> {
+    let amount = amount.unwrap_or(1);
+    if !(1..=10).contains(&amount) {
+        return Err("Amount must be between 1 and 10 glasses".to_string());
     }
 
-    let clock_service = ClockService::new();
+    wellness::perform(&app_handle, WaterAction::Log { amount })
+        .await
+        .map_err(|e| e.to_string())
+}
 
-    match clock_service.clock_out(&webapp_url, &token).await {
-        Ok(_entry) => {
-            let status = clock_service
-                .get_status(&webapp_url, &token)
-                .await
-                .map_err(|e| e.to_string())?;
+/// Pauses water reminders for the rest of the day
+#[tauri::command]
+pub async fn snooze_water_reminders(app_handle: AppHandle) -> Result<WaterActionResult, String> {
+    wellness::perform(&app_handle, WaterAction::Snooze)
+        .await
+        .map_err(|e| e.to_string())
+}
 
-            state.set_clocked_in(status.is_clocked_in);
-            let _ = tray::update_tray_icon(&app_handle, status.is_clocked_in);
+/// Punches a proposed automatic clock-in/out through the offline queue
+#[tauri::command]
+pub fn confirm_trigger(app_handle: AppHandle, id: u64) -> Result<FiredTrigger, String> {
+    triggers::perform(&app_handle, id).map_err(|e| e.to_string())
+}
 
-            Ok(status)
-        }
-        Err(e) => {
-            if e.to_string().contains("connection")
-                || e.to_string().contains("timeout")
-                || e.to_string().contains("network")
-            {
-                let mut queue = state.offline_queue.lock();
-                let _ = queue.enqueue(ActionType::ClockOut, Utc::now().timestamp(), None);
+/// Reverts an automatic punch
+#[tauri::command]
+pub fn undo_trigger(app_handle: AppHandle, id: u64) -> Result<(), String> {
+    triggers::undo(&app_handle, id).map_err(|e| e.to_string())
+}
 
-                state.set_clocked_in(false);
-                let _ = tray::update_tray_icon(&app_handle, false);
+/// Declines a proposed automatic punch
+#[tauri::command]
+pub fn dismiss_trigger(app_handle: AppHandle, id: u64) {
+    triggers::dismiss(&app_handle, id);
+}
 
-                Ok(ClockStatus {
-                    has_employee: true,
-                    employee_id: None,
-                    is_clocked_in: false,
-                    active_work_period: None,
-                })
-            } else {
-                Err(e.to_string())
-            }
-        }
-    }
+/// Runs a `z8://` link action the user confirmed
+#[tauri::command]
+pub async fn confirm_deep_link(app_handle: AppHandle, id: u64) -> Result<(), String> {
+    deep_link::confirm(&app_handle, id)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Clocks out at a specific time (for break handling) then immediately clocks back in
+/// Declines a `z8://` link action
 #[tauri::command]
-pub async fn clock_out_with_break(
+pub fn dismiss_deep_link(app_handle: AppHandle, id: u64) {
+    deep_link::dismiss(&app_handle, id);
+}
+
+/// Lists journal entries, newest first
+#[tauri::command]
+pub fn get_journal(
     app_handle: AppHandle,
-    break_start_time: String,
-    work_location_type: String,
-) -> Result<ClockStatus, String> {
+    query: Option<JournalQuery>,
+) -> Result<Vec<JournalEntry>, String> {
     let state = app_handle.state::<Arc<AppState>>();
+    let entries = state.journal.lock().query(&query.unwrap_or_default());
+    entries.map_err(|e| e.to_string())
+}
 
-    let token = match state.get_session_token() {
-        Some(t) => t,
-        None => return Err("Not authenticated".to_string()),
-    };
+/// Saves the journal to the downloads folder for a support ticket and returns the file path
+#[tauri::command]
+pub fn export_journal(
+    app_handle: AppHandle,
+    query: Option<JournalQuery>,
+) -> Result<String, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let dir = dirs::download_dir()
+        .or_else(|| app_handle.path().app_data_dir().ok())
+        .ok_or("No folder to export to")?;
 
-    let webapp_url = state.get_webapp_url();
-    if webapp_url.is_empty() {
-        return Err("Webapp URL not configured".to_string());
-    }
+    let path = journal::export(&state.journal.lock(), &query.unwrap_or_default(), &dir)
+        .map_err(|e| e.to_string())?;
+    Ok(path.display().to_string())
+}
 
-    let break_time: DateTime<Utc> = DateTime::parse_from_rfc3339(&break_start_time)
-        .map_err(|e| format!("Invalid break time: {}", e))?
-        .with_timezone(&Utc);
+/// Records that the user kept working after an idle or clock-out prompt; nothing is sent
+#[tauri::command]
+pub fn record_keep_working(app_handle: AppHandle, prompt: String, since: Option<String>) {
+    let state = app_handle.state::<Arc<AppState>>();
+    journal::record(
+        &state,
+        JournalEvent::new(
+            JournalKind::Break,
+            EventSource::Ui,
+            "keep_working",
+            JournalOutcome::Noted,
+        )
+        .details(serde_json::json!({ "prompt": prompt, "since": since })),
+    );
+}
 
-    let work_location_type = WorkLocationType::from_str(&work_location_type)
-        .ok_or("Invalid work location type".to_string())?;
+/// Checks a clock-in right now against the cached schedule; `Some` asks the user to confirm
+#[tauri::command]
+pub fn check_clock_in(app_handle: AppHandle) -> Option<PunchWarning> {
+    let state = app_handle.state::<Arc<AppState>>();
+    schedule::cached_schedule(&state).clock_in_warning(Utc::now())
+}
 
-    let clock_service = ClockService::new();
+/// Lists projects the user can clock against, falling back to the cached list when offline
+#[tauri::command]
+pub async fn list_projects(app_handle: AppHandle) -> Result<ProjectList, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
+    }
 
-    match clock_service
-        .clock_out_with_break(&webapp_url, &token, break_time, work_location_type, None)
-        .await
-    {
-        Ok(_) => {
-            let status = clock_service
-                .get_status(&webapp_url, &token)
-                .await
-                .map_err(|e| e.to_string())?;
+    let project_service = ProjectService::new(state.api.clone());
 
-            state.set_clocked_in(status.is_clocked_in);
-            let _ = tray::update_tray_icon(&app_handle, status.is_clocked_in);
+    match project_service.list_projects().await {
+        Ok(projects) => {
+            let mut queue = state.offline_queue.lock();
+            if let Err(e) = queue.put_cached(PROJECTS_CACHE_KEY, &projects) {
+                log::warn!("Failed to cache projects: {}", e);
+            }
 
-            Ok(status)
+            Ok(ProjectList {
+                projects,
+                from_cache: false,
+                updated_at: Some(Utc::now().timestamp()),
+            })
         }
-        Err(e) => {
-            if e.to_string().contains("connection")
-                || e.to_string().contains("timeout")
-                || e.to_string().contains("network")
-            {
-                let mut queue = state.offline_queue.lock();
-                let _ = queue.enqueue(
-                    ActionType::ClockOutWithBreak,
-                    Utc::now().timestamp(),
-                    Some(
-                        serde_json::json!({
-                            "breakStartTime": break_start_time,
-                            "workLocationType": work_location_type.as_str(),
-                        })
-                        .to_string(),
-                    ),
-                );
+        Err(e) if is_network_error(&e) => {
+            let queue = state.offline_queue.lock();
+            let cached: Option<Cached<Vec<Project>>> = queue
+                .get_cached(PROJECTS_CACHE_KEY)
+                .map_err(|e| e.to_string())?;
 
-                // Remain clocked in since we'll clock back in after break
-                Ok(ClockStatus {
-                    has_employee: true,
-                    employee_id: None,
-                    is_clocked_in: true,
-                    active_work_period: None,
-                })
-            } else {
-                Err(e.to_string())
-            }
+            Ok(match cached {
+                Some(cached) => ProjectList {
+                    projects: cached.value,
+                    from_cache: true,
+                    updated_at: Some(cached.updated_at),
+                },
+                None => ProjectList {
+                    projects: Vec::new(),
+                    from_cache: true,
+                    updated_at: None,
+                },
+            })
         }
+        Err(e) => Err(e.to_string()),
     }
 }
 
@@ -275,16 +1288,102 @@ pub fn logout(app_handle: AppHandle) -> Result<(), String> {
     auth::logout(&app_handle).map_err(|e| e.to_string())
 }
 
-/// Gets the current session state
+/// Gets the current session state. The token itself never leaves Rust.
 #[tauri::command]
-pub fn get_session(app_handle: AppHandle) -> SessionResponse {
+pub async fn get_session(app_handle: AppHandle) -> SessionResponse {
     let state = app_handle.state::<Arc<AppState>>();
-    let token = state.get_session_token();
+    if state.get_session_token().is_none() {
+        return SessionResponse {
+            is_authenticated: false,
+            profile: None,
+        };
+    }
+
+    // Signed in before profiles were kept, or the fetch at sign-in failed
+    let mut profile = state.profile();
+    if profile.is_none() {
+        match auth::fetch_profile(&state.api).await {
+            Ok(fetched) => {
+                state.set_profile(Some(fetched.clone()));
+                profile = Some(fetched);
+            }
+            Err(e) => log::warn!("Failed to fetch profile: {}", e),
+        }
+    }
 
     SessionResponse {
-        is_authenticated: token.is_some(),
-        token,
+        is_authenticated: true,
+        profile,
+    }
+}
+
+/// Builds an organization service, failing early when the session isn't usable
+fn organization_service(state: &AppState) -> Result<OrganizationService, String> {
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
+    }
+
+    Ok(OrganizationService::new(state.api.clone()))
+}
+
+/// Lists the user's organizations and the one the session is in
+#[tauri::command]
+pub async fn list_organizations(app_handle: AppHandle) -> Result<OrganizationList, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    organization_service(&state)?
+        .list()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Moves the session to another organization and refreshes the clock status for it
+#[tauri::command]
+pub async fn switch_organization(
+    app_handle: AppHandle,
+    organization_id: String,
+) -> Result<(), String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    organization_service(&state)?
+        .switch(&organization_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let _ = get_clock_status(app_handle.clone()).await;
+    Ok(())
+}
+
+/// Builds a device service, failing early when the session isn't usable
+fn device_service(state: &AppState) -> Result<DeviceService, String> {
+    if state.get_session_token().is_none() {
+        return Err("Not authenticated".to_string());
+    }
+
+    Ok(DeviceService::new(state.api.clone()))
+}
+
+/// Lists the devices signed in to the user's account, this one first
+#[tauri::command]
+pub async fn list_devices(app_handle: AppHandle) -> Result<Vec<Device>, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    device_service(&state)?
+        .list()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Revokes a device, e.g. a lost laptop; revoking this one signs out here as well
+#[tauri::command]
+pub async fn revoke_device(app_handle: AppHandle, device_id: String) -> Result<(), String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    device_service(&state)?
+        .revoke(&device_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if state.api.device_id().as_deref() == Some(device_id.as_str()) {
+        auth::handle_device_revoked(&app_handle);
     }
+    Ok(())
 }
 
 /// Gets the current settings
@@ -297,26 +1396,146 @@ pub fn get_settings(app_handle: AppHandle) -> SettingsResponse {
         webapp_url: settings.webapp_url.clone(),
         always_on_top: settings.always_on_top,
         auto_startup: settings.auto_startup,
+        network: settings.network.clone(),
+        switch_project_shortcut: settings.switch_project_shortcut.clone(),
+        weekly_target_hours: settings.weekly_target_hours,
+        break_reminders_enabled: settings.break_reminders_enabled,
+        shift_reminders_enabled: settings.shift_reminders_enabled,
+        end_of_day_time: settings.end_of_day_time.clone(),
+        end_of_day_idle_minutes: settings.end_of_day_idle_minutes,
+        location_rules: settings.location_rules.clone(),
+        managed_location_rules: location::managed_rules(),
+        trigger_rules: settings.trigger_rules.clone(),
+        control_api_enabled: settings.control_api_enabled,
+        control_api_file: app_handle
+            .path()
+            .app_data_dir()
+            .map(|dir| control::discovery_path(&dir).display().to_string())
+            .unwrap_or_default(),
         version: env!("CARGO_PKG_VERSION").to_string(),
     }
 }
 
 /// Saves settings
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn save_settings(
     app_handle: AppHandle,
     webapp_url: String,
     always_on_top: bool,
     auto_startup: bool,
+    network: Option<NetworkSettings>,
+    switch_project_shortcut: Option<String>,
+    weekly_target_hours: Option<f64>,
+    break_reminders_enabled: Option<bool>,
+    shift_reminders_enabled: Option<bool>,
+    end_of_day_time: Option<String>,
+    end_of_day_idle_minutes: Option<u32>,
+    location_rules: Option<Vec<LocationRule>>,
+    trigger_rules: Option<Vec<TriggerRule>>,
+    control_api_enabled: Option<bool>,
 ) -> Result<(), String> {
     let state = app_handle.state::<Arc<AppState>>();
 
+    if let Some(rules) = &trigger_rules {
+        for rule in rules {
+            rule.validate().map_err(|e| e.to_string())?;
+        }
+    }
+
+    fn normalized(url: &str) -> &str {
+        url.trim().trim_end_matches('/')
+    }
+    // The session token belongs to the webapp that issued it; never send it elsewhere
+    if normalized(&webapp_url) != normalized(&state.get_webapp_url())
+        && state.get_session_token().is_some()
+    {
+        return Err("Sign out before changing the webapp URL".to_string());
+    }
+
+    // An empty end-of-day time disables the prompt; omitting it keeps the current one
+    let end_of_day_time = end_of_day_time
+        .map(|time| {
+            let time = time.trim().to_string();
+            if time.is_empty() {
+                return Ok(None);
+            }
+            end_of_day::parse_end_of_day(&time)
+                .map(|_| Some(time))
+                .ok_or_else(|| "End of day must be a time like 18:00".to_string())
+        })
+        .transpose()?;
+
+    // An empty shortcut disables it; omitting it keeps the current one
+    let switch_project_shortcut = switch_project_shortcut.map(|shortcut| {
+        let shortcut = shortcut.trim().to_string();
+        (!shortcut.is_empty()).then_some(shortcut)
+    });
+    if let Some(Some(shortcut)) = &switch_project_shortcut {
+        shortcuts::validate_shortcut(shortcut).map_err(|e| format!("Invalid shortcut: {}", e))?;
+    }
+
+    // Rebuild the HTTP client first so an invalid proxy or CA file is rejected before saving
+    if let Some(network) = &network {
+        state
+            .api
+            .reconfigure(&NetworkConfig::resolve(network))
+            .map_err(|e| e.to_string())?;
+    }
+
     // Update settings
     {
         let mut settings = state.settings.write();
         settings.webapp_url = webapp_url;
         settings.always_on_top = always_on_top;
         settings.auto_startup = auto_startup;
+        if let Some(network) = network {
+            settings.network = network;
+        }
+        if let Some(shortcut) = &switch_project_shortcut {
+            settings.switch_project_shortcut = shortcut.clone();
+        }
+        if let Some(hours) = weekly_target_hours.filter(|hours| *hours >= 0.0) {
+            settings.weekly_target_hours = hours;
+        }
+        if let Some(enabled) = break_reminders_enabled {
+            settings.break_reminders_enabled = enabled;
+        }
+        if let Some(enabled) = shift_reminders_enabled {
+            settings.shift_reminders_enabled = enabled;
+        }
+        if let Some(time) = end_of_day_time {
+            settings.end_of_day_time = time;
+        }
+        if let Some(minutes) = end_of_day_idle_minutes.filter(|minutes| *minutes > 0) {
+            settings.end_of_day_idle_minutes = minutes;
+        }
+        if let Some(rules) = location_rules {
+            settings.location_rules = rules
+                .into_iter()
+                .filter(|rule| !rule.value.trim().is_empty())
+                .collect();
+        }
+        if let Some(rules) = trigger_rules {
+            // A blank network means the rule applies on any network
+            settings.trigger_rules = rules
+                .into_iter()
+                .map(|mut rule| {
+                    if rule
+                        .network
+                        .as_ref()
+                        .is_some_and(|condition| condition.value.trim().is_empty())
+                    {
+                        rule.network = None;
+                    }
+                    rule
+                })
+                .collect();
+        }
+        if let Some(enabled) = control_api_enabled {
+            settings.control_api_enabled = enabled;
+        }
+        state.api.set_base_url(&settings.webapp_url);
 
         // Save to file
         let app_data_dir = app_handle
@@ -326,6 +1545,18 @@ pub fn save_settings(
         settings.save(&app_data_dir).map_err(|e| e.to_string())?;
     }
 
+    // Registered only once saved, so a failed save leaves the old shortcut in place
+    if let Some(shortcut) = &switch_project_shortcut {
+        shortcuts::register_shortcuts(&app_handle, shortcut.as_deref())
+            .map_err(|e| format!("Invalid shortcut: {}", e))?;
+    }
+
+    if state.settings.read().control_api_enabled {
+        control::start(&app_handle);
+    } else {
+        control::stop(&state);
+    }
+
     // Apply always-on-top setting
     if let Some(window) = app_handle.get_webview_window("main") {
         let _ = window.set_always_on_top(always_on_top);
@@ -344,6 +1575,24 @@ pub fn save_settings(
     Ok(())
 }
 
+/// Checks that the webapp is reachable with the given (or saved) URL and network settings
+#[tauri::command]
+pub async fn test_connection(
+    app_handle: AppHandle,
+    webapp_url: Option<String>,
+    network: Option<NetworkSettings>,
+) -> Result<ConnectionTestResult, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let webapp_url = webapp_url.unwrap_or_else(|| state.get_webapp_url());
+    let network = network.unwrap_or_else(|| state.settings.read().network.clone());
+
+    if webapp_url.is_empty() {
+        return Err("Webapp URL not configured".to_string());
+    }
+
+    Ok(network::test_connection(&webapp_url, &NetworkConfig::resolve(&network)).await)
+}
+
 /// Sets the always-on-top window state
 #[tauri::command]
 pub fn set_always_on_top(app_handle: AppHandle, enabled: bool) -> Result<(), String> {
@@ -374,3 +1623,117 @@ pub fn get_pending_queue_count(app_handle: AppHandle) -> Result<i64, String> {
     let queue = state.offline_queue.lock();
     queue.count().map_err(|e| e.to_string())
 }
+
+/// Lists queued actions the webapp refused too often to be retried automatically
+#[tauri::command]
+pub fn list_unsent_actions(app_handle: AppHandle) -> Result<Vec<UnsentAction>, String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let unsent = state.offline_queue.lock().unsent();
+    unsent.map_err(|e| e.to_string())
+}
+
+/// Sends a refused action again with the next queue pass, which starts right away
+#[tauri::command]
+pub fn retry_unsent_action(app_handle: AppHandle, id: i64) -> Result<(), String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let queued = state
+        .offline_queue
+        .lock()
+        .reset_retries(id)
+        .map_err(|e| e.to_string())?;
+    if !queued {
+        return Err("This action is no longer queued".to_string());
+    }
+
+    state.process_queue_soon();
+    Ok(())
+}
+
+/// Drops a refused action, letting the actions queued after it go out
+#[tauri::command]
+pub fn discard_unsent_action(app_handle: AppHandle, id: i64) -> Result<(), String> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let action = state
+        .offline_queue
+        .lock()
+        .unsent()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|action| action.id == id)
+        .ok_or("This action is no longer queued")?;
+
+    state
+        .offline_queue
+        .lock()
+        .remove(id)
+        .map_err(|e| e.to_string())?;
+    journal::record(
+        &state,
+        JournalEvent::new(
+            JournalKind::Sync,
+            EventSource::Ui,
+            &format!("discard_{}", action.action),
+            JournalOutcome::Noted,
+        )
+        .message("Discarded an action the webapp refused")
+        .details(serde_json::json!({
+            "queuedId": action.id,
+            "queuedAt": action.queued_at,
+            "retryCount": action.retry_count,
+        })),
+    );
+
+    let status = state.local_clock_status();
+    state.set_clocked_in(status.is_clocked_in);
+    let _ = tray::update_tray_icon(&app_handle, status.is_clocked_in);
+    state.process_queue_soon();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Punched, Puncher};
+    use crate::api::testing::fake_client;
+    use crate::clock::{ClockService, PunchCapture, PunchDetails, WorkLocationType};
+    use crate::device::DeviceKey;
+    use crate::journal::{EventSource, Journal};
+    use crate::offline::{ActionType, OfflineQueue};
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn queues_punches_the_webapp_did_not_receive_and_those_after_them() {
+        let dir = std::env::temp_dir().join(format!("z8-puncher-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key = DeviceKey::load_or_create(&dir).unwrap();
+        let queue = Mutex::new(OfflineQueue::new(&dir, Arc::new(key)).unwrap());
+        let journal = Mutex::new(Journal::new(&dir).unwrap());
+        let (api, transport) = fake_client();
+        let clock_service = ClockService::new(api);
+        let puncher = Puncher::with_storage(&clock_service, &queue, &journal, EventSource::Cli);
+        let details = PunchDetails::new(Some("project-1".to_string()), None, None);
+
+        transport.fail_network();
+        let punched = puncher
+            .clock_in(WorkLocationType::Home, &details, PunchCapture::now(), None)
+            .await
+            .unwrap();
+        assert_eq!(punched, Punched::Queued);
+
+        // Back online, the clock-out still waits behind the queued clock-in
+        let punched = puncher
+            .clock_out("clock_out", &details, PunchCapture::now())
+            .await
+            .unwrap();
+        assert_eq!(punched, Punched::Queued);
+        assert_eq!(transport.requests().len(), 1);
+
+        let pending = queue.lock().get_pending().unwrap();
+        assert_eq!(pending.len(), 2);
+        assert!(matches!(pending[0].action_type, ActionType::ClockIn));
+        assert!(pending[0].payload.as_deref().unwrap().contains("project-1"));
+        assert!(matches!(pending[1].action_type, ActionType::ClockOut));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,481 @@
+//! Localhost API for scripts, git hooks, launchers and editor plugins.
+//!
+//! Listens on `127.0.0.1` only and requires the per-install token from
+//! `control.json` in the app data directory as `Authorization: Bearer <token>`.
+//! Every endpoint calls the same functions as the Tauri commands, so punches
+//! use the app's session and fall back to the offline queue.
+//!
+//! | Method | Path                 | Body                                              |
+//! |--------|----------------------|---------------------------------------------------|
+//! | GET    | `/v1/status`         |                                                   |
+//! | GET    | `/v1/today`          |                                                   |
+//! | POST   | `/v1/clock-in`       | `location`, `projectId`, `workCategoryId`, `notes` |
+//! | POST   | `/v1/clock-out`      | `projectId`, `workCategoryId`, `notes`            |
+//! | POST   | `/v1/break/start`    |                                                   |
+//! | POST   | `/v1/break/end`      | like clock-in; defaults to the project before the break |
+//! | POST   | `/v1/switch-project` | like clock-in                                     |
+
+use anyhow::Result;
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use crate::api;
+use crate::clock::{PunchDetails, WorkLocationType};
+use crate::commands;
+use crate::journal::EventSource;
+use crate::state::AppState;
+
+/// Preferred port, so plugins can be configured once; taken ports fall back to a random one
+const DEFAULT_PORT: u16 = 7723;
+const PORT_ENV: &str = "Z8_CONTROL_PORT";
+const DISCOVERY_FILE: &str = "control.json";
+
+/// Where to reach the API, written to the app data directory for clients to read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlEndpoint {
+    pub url: String,
+    pub token: String,
+}
+
+pub fn discovery_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(DISCOVERY_FILE)
+}
+
+/// Keeps the token of an earlier run so configured clients keep working
+fn load_or_create_token(app_data_dir: &Path) -> String {
+    let existing = fs::read_to_string(discovery_path(app_data_dir))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<ControlEndpoint>(&contents).ok())
+        .map(|endpoint| endpoint.token)
+        .filter(|token| !token.is_empty());
+
+    existing.unwrap_or_else(|| {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        URL_SAFE_NO_PAD.encode(bytes)
+    })
+}
+
+fn write_discovery_file(app_data_dir: &Path, endpoint: &ControlEndpoint) -> Result<()> {
+    let contents = serde_json::to_string_pretty(endpoint)?;
+    let path = discovery_path(app_data_dir);
+
+    // Only the signed-in OS user may read the token
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&path)?;
+        // The mode only applies to new files
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(contents.as_bytes())?;
+    }
+    #[cfg(not(unix))]
+    fs::write(&path, contents)?;
+
+    Ok(())
+}
+
+/// Compares without exiting early, so the token can't be guessed byte by byte
+fn tokens_match(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[derive(Clone)]
+struct ControlContext {
+    app_handle: AppHandle,
+    token: Arc<String>,
+}
+
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// Checks the commands make before anything is sent; failing one is the caller's fault
+const VALIDATION_ERRORS: &[&str] = &["Invalid work location type", "Not clocked in"];
+
+/// Commands report errors as text: session problems are 401, the caller's mistakes
+/// 400 and anything the webapp refused or didn't answer 502
+fn status_for(message: &str) -> StatusCode {
+    let is = |error: api::ApiError| message == error.to_string();
+    if is(api::ApiError::NotAuthenticated) || is(api::ApiError::DeviceRevoked) {
+        StatusCode::UNAUTHORIZED
+    } else if is(api::ApiError::NotConfigured) {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else if VALIDATION_ERRORS.contains(&message) {
+        StatusCode::BAD_REQUEST
+    } else {
+        StatusCode::BAD_GATEWAY
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        Self(status_for(&message), message)
+    }
+}
+
+type ApiResult<T> = std::result::Result<Json<T>, ApiError>;
+
+async fn authorize(
+    State(token): State<Arc<String>>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    // Browsers always send an Origin; web pages have no business here
+    if headers.contains_key(header::ORIGIN) {
+        return ApiError(
+            StatusCode::FORBIDDEN,
+            "Browser requests are not allowed".into(),
+        )
+        .into_response();
+    }
+
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if !tokens_match(&token, provided.trim()) {
+        return ApiError(StatusCode::UNAUTHORIZED, "Invalid control API token".into())
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct PunchBody {
+    location: Option<WorkLocationType>,
+    project_id: Option<String>,
+    work_category_id: Option<String>,
+    notes: Option<String>,
+}
+
+impl PunchBody {
+    /// The requested location, else the network rules' suggestion, else the office
+    async fn location(&self, app_handle: &AppHandle) -> WorkLocationType {
+        if let Some(location) = self.location {
+            return location;
+        }
+        commands::suggest_work_location(app_handle.clone())
+            .await
+            .ok()
+            .flatten()
+            .map_or(WorkLocationType::Office, |suggestion| suggestion.location)
+    }
+}
+
+async fn status(State(context): State<ControlContext>) -> ApiResult<impl Serialize> {
+    Ok(Json(commands::get_clock_status(context.app_handle).await?))
+}
+
+async fn today(State(context): State<ControlContext>) -> ApiResult<impl Serialize> {
+    Ok(Json(
+        commands::get_today_timeline(context.app_handle).await?,
+    ))
+}
+
+async fn clock_in(
+    State(context): State<ControlContext>,
+    body: Option<Json<PunchBody>>,
+) -> ApiResult<impl Serialize> {
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    let location = body.location(&context.app_handle).await;
+    let status = commands::clock_in(
+        context.app_handle,
+        location.as_str().to_string(),
+        body.project_id,
+        body.work_category_id,
+        body.notes,
+        None,
+        Some(EventSource::ControlApi),
+    )
+    .await?;
+    Ok(Json(status))
+}
+
+async fn clock_out(
+    State(context): State<ControlContext>,
+    body: Option<Json<PunchBody>>,
+) -> ApiResult<impl Serialize> {
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    let status = commands::clock_out(
+        context.app_handle,
+        body.project_id,
+        body.work_category_id,
+        body.notes,
+        Some(EventSource::ControlApi),
+    )
+    .await?;
+    Ok(Json(status))
+}
+
+/// Clocks out but keeps the project, so ending the break continues it
+async fn start_break(State(context): State<ControlContext>) -> ApiResult<impl Serialize> {
+    let status = commands::start_break(context.app_handle, EventSource::ControlApi).await?;
+    Ok(Json(status))
+}
+
+async fn end_break(
+    State(context): State<ControlContext>,
+    body: Option<Json<PunchBody>>,
+) -> ApiResult<impl Serialize> {
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    let state = context.app_handle.state::<Arc<AppState>>();
+    let details = PunchDetails::new(body.project_id.clone(), body.work_category_id.clone(), None)
+        .or(&state.active_punch_details());
+    let location = body.location(&context.app_handle).await;
+    let status = commands::clock_in(
+        context.app_handle.clone(),
+        location.as_str().to_string(),
+        details.project_id,
+        details.work_category_id,
+        body.notes,
+        None,
+        Some(EventSource::ControlApi),
+    )
+    .await?;
+    Ok(Json(status))
+}
+
+async fn switch_project(
+    State(context): State<ControlContext>,
+    Json(body): Json<PunchBody>,
+) -> ApiResult<impl Serialize> {
+    let location = body.location(&context.app_handle).await;
+    let status = commands::switch_project(
+        context.app_handle,
+        location.as_str().to_string(),
+        body.project_id,
+        body.work_category_id,
+        body.notes,
+        Some(EventSource::ControlApi),
+    )
+    .await?;
+    Ok(Json(status))
+}
+
+fn router(context: ControlContext) -> Router {
+    Router::new()
+        .route("/v1/status", get(status))
+        .route("/v1/today", get(today))
+        .route("/v1/clock-in", post(clock_in))
+        .route("/v1/clock-out", post(clock_out))
+        .route("/v1/break/start", post(start_break))
+        .route("/v1/break/end", post(end_break))
+        .route("/v1/switch-project", post(switch_project))
+        .layer(middleware::from_fn_with_state(
+            context.token.clone(),
+            authorize,
+        ))
+        .with_state(context)
+}
+
+async fn bind() -> Result<tokio::net::TcpListener> {
+    let port = std::env::var(PORT_ENV)
+        .ok()
+        .and_then(|port| port.trim().parse().ok())
+        .unwrap_or(DEFAULT_PORT);
+
+    match tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port))).await {
+        Ok(listener) => Ok(listener),
+        Err(e) => {
+            log::warn!(
+                "Control API port {} unavailable ({}), using a random one",
+                port,
+                e
+            );
+            Ok(tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0))).await?)
+        }
+    }
+}
+
+async fn serve(app_handle: AppHandle) -> Result<()> {
+    let app_data_dir = app_handle.path().app_data_dir()?;
+    let token = load_or_create_token(&app_data_dir);
+    let listener = bind().await?;
+
+    let endpoint = ControlEndpoint {
+        url: format!("http://{}", listener.local_addr()?),
+        token: token.clone(),
+    };
+    write_discovery_file(&app_data_dir, &endpoint)?;
+    log::info!("Control API listening on {}", endpoint.url);
+
+    let context = ControlContext {
+        app_handle,
+        token: Arc::new(token),
+    };
+    axum::serve(listener, router(context)).await?;
+    Ok(())
+}
+
+/// Starts the control API if it is enabled and not running yet
+pub fn start(app_handle: &AppHandle) {
+    let state = app_handle.state::<Arc<AppState>>();
+    if !state.settings.read().control_api_enabled {
+        return;
+    }
+
+    // A server that failed to bind or stopped leaves its finished task behind;
+    // that slot is free again
+    let mut server = state.control_server.lock();
+    if server
+        .as_ref()
+        .is_some_and(|server| !server.inner().is_finished())
+    {
+        return;
+    }
+
+    let app_handle = app_handle.clone();
+    *server = Some(tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve(app_handle).await {
+            log::error!("Control API stopped: {}", e);
+        }
+    }));
+}
+
+/// Stops the control API; clients get connection refused until it is enabled again
+pub fn stop(state: &AppState) {
+    if let Some(server) = state.control_server.lock().take() {
+        server.abort();
+        log::info!("Control API stopped");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        authorize, load_or_create_token, status_for, tokens_match, write_discovery_file,
+        ControlEndpoint,
+    };
+    use axum::http::StatusCode;
+    use axum::middleware;
+    use axum::routing::get;
+    use axum::Router;
+    use std::sync::Arc;
+
+    #[test]
+    fn tells_session_errors_from_rejections_and_upstream_failures() {
+        assert_eq!(status_for("Not authenticated"), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status_for("This device was signed out remotely"),
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_for("Invalid work location type"),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status_for("Failed to fetch clock status: 500"),
+            StatusCode::BAD_GATEWAY
+        );
+    }
+
+    #[test]
+    fn compares_tokens_exactly() {
+        assert!(tokens_match("s3cret-token", "s3cret-token"));
+        assert!(!tokens_match("s3cret-token", "s3cret-tokem"));
+        assert!(!tokens_match("s3cret-token", "s3cret"));
+        assert!(!tokens_match("s3cret-token", ""));
+    }
+
+    #[tokio::test]
+    async fn refuses_requests_without_the_token_or_from_a_browser() {
+        let routes = Router::new()
+            .route("/v1/status", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                Arc::new("s3cret-token".to_string()),
+                authorize,
+            ));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1/status", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, routes).await });
+
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let status = |request: reqwest::RequestBuilder| async move {
+            request.send().await.unwrap().status().as_u16()
+        };
+
+        assert_eq!(status(client.get(&url)).await, 401);
+        assert_eq!(
+            status(client.get(&url).bearer_auth("s3cret-tokem")).await,
+            401
+        );
+        assert_eq!(
+            status(
+                client
+                    .get(&url)
+                    .bearer_auth("s3cret-token")
+                    .header("Origin", "https://example.com")
+            )
+            .await,
+            403
+        );
+        assert_eq!(
+            status(client.get(&url).bearer_auth("s3cret-token")).await,
+            200
+        );
+    }
+
+    #[test]
+    fn keeps_the_token_across_restarts() {
+        let dir = std::env::temp_dir().join(format!("z8-control-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let token = load_or_create_token(&dir);
+        assert_eq!(token.len(), 43);
+        write_discovery_file(
+            &dir,
+            &ControlEndpoint {
+                url: "http://127.0.0.1:7723".to_string(),
+                token: token.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(load_or_create_token(&dir), token);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(super::discovery_path(&dir))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::clock::{ClockService, Correction, CorrectionStatus};
+use crate::notify;
+use crate::state::AppState;
+
+/// Cache key for the correction states seen on the last check
+const CORRECTIONS_CACHE_KEY: &str = "correction_statuses";
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CorrectionSnapshot {
+    checked_at: Option<DateTime<Utc>>,
+    statuses: HashMap<String, CorrectionStatus>,
+}
+
+impl CorrectionSnapshot {
+    /// Corrections a manager decided since the snapshot was taken
+    fn decided<'a>(&self, corrections: &'a [Correction]) -> Vec<&'a Correction> {
+        corrections
+            .iter()
+            .filter(|correction| correction.status != CorrectionStatus::Pending)
+            .filter(|correction| match self.statuses.get(&correction.id) {
+                Some(previous) => *previous == CorrectionStatus::Pending,
+                // Submitted and decided between two checks
+                None => self
+                    .checked_at
+                    .zip(correction.decided_at)
+                    .is_some_and(|(checked_at, decided_at)| decided_at > checked_at),
+            })
+            .collect()
+    }
+
+    fn of(corrections: &[Correction], checked_at: DateTime<Utc>) -> Self {
+        Self {
+            checked_at: Some(checked_at),
+            statuses: corrections
+                .iter()
+                .map(|correction| (correction.id.clone(), correction.status))
+                .collect(),
+        }
+    }
+}
+
+fn notification(correction: &Correction) -> (&'static str, String) {
+    match correction.status {
+        CorrectionStatus::Approved => (
+            "Correction approved",
+            match &correction.reason {
+                Some(reason) => format!("Your correction \"{}\" was approved.", reason),
+                None => "Your time correction was approved.".to_string(),
+            },
+        ),
+        _ => (
+            "Correction rejected",
+            match &correction.rejection_reason {
+                Some(reason) => format!("Your time correction was rejected: {}", reason),
+                None => "Your time correction was rejected.".to_string(),
+            },
+        ),
+    }
+}
+
+/// Polls correction requests and notifies when a manager approves or rejects one
+pub async fn start_correction_watcher(app_handle: AppHandle) {
+    log::info!("Starting correction watcher");
+
+    let state = app_handle.state::<Arc<AppState>>();
+    let clock_service = ClockService::new(state.api.clone());
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        if state.get_session_token().is_none() || state.get_webapp_url().is_empty() {
+            continue;
+        }
+
+        let checked_at = Utc::now();
+        let corrections = match clock_service.list_corrections().await {
+            Ok(corrections) => corrections,
+            Err(e) => {
+                log::warn!("Failed to check corrections: {}", e);
+                continue;
+            }
+        };
+
+        let previous = {
+            let queue = state.offline_queue.lock();
+            queue
+                .get_cached::<CorrectionSnapshot>(CORRECTIONS_CACHE_KEY)
+                .ok()
+                .flatten()
+                .map(|cached| cached.value)
+                .unwrap_or_default()
+        };
+
+        for correction in previous.decided(&corrections) {
+            log::info!("Correction {} was {:?}", correction.id, correction.status);
+            let (title, body) = notification(correction);
+            notify::send(&app_handle, title, &body);
+            let _ = app_handle.emit("correction_decided", correction);
+        }
+
+        let mut queue = state.offline_queue.lock();
+        let snapshot = CorrectionSnapshot::of(&corrections, checked_at);
+        if let Err(e) = queue.put_cached(CORRECTIONS_CACHE_KEY, &snapshot) {
+            log::warn!("Failed to cache correction states: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CorrectionSnapshot;
+    use crate::clock::{Correction, CorrectionStatus};
+    use chrono::{DateTime, Utc};
+
+    fn correction(id: &str, status: CorrectionStatus, decided_at: Option<&str>) -> Correction {
+        Correction {
+            id: id.to_string(),
+            work_period_id: format!("period-{}", id),
+            status,
+            reason: None,
+            rejection_reason: None,
+            created_at: "2026-10-12T08:00:00Z".parse().unwrap(),
+            decided_at: decided_at.map(|at| at.parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn reports_only_newly_decided_corrections() {
+        let checked_at: DateTime<Utc> = "2026-10-12T09:00:00Z".parse().unwrap();
+        let previous = CorrectionSnapshot::of(
+            &[
+                correction("a", CorrectionStatus::Pending, None),
+                correction(
+                    "b",
+                    CorrectionStatus::Approved,
+                    Some("2026-10-12T08:30:00Z"),
+                ),
+            ],
+            checked_at,
+        );
+
+        let current = [
+            correction(
+                "a",
+                CorrectionStatus::Rejected,
+                Some("2026-10-12T09:10:00Z"),
+            ),
+            correction(
+                "b",
+                CorrectionStatus::Approved,
+                Some("2026-10-12T08:30:00Z"),
+            ),
+            correction(
+                "c",
+                CorrectionStatus::Approved,
+                Some("2026-10-12T09:05:00Z"),
+            ),
+            correction("d", CorrectionStatus::Pending, None),
+        ];
+        let decided: Vec<&str> = previous
+            .decided(&current)
+            .into_iter()
+            .map(|correction| correction.id.as_str())
+            .collect();
+        assert_eq!(decided, vec!["a", "c"]);
+
+        // Nothing is announced on the very first check
+        assert!(CorrectionSnapshot::default().decided(&current).is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+//! Routes `z8://` links from the webapp, chat messages and calendar events.
+//!
+//! Any web page can open such a link, so each action has a confirmation
+//! policy: punches and organization switches wait until the user confirms
+//! them in the app, harmless ones run right away.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use url::Url;
+
+use crate::auth;
+use crate::clock::WorkLocationType;
+use crate::commands;
+use crate::journal::EventSource;
+use crate::notify;
+use crate::state::AppState;
+
+/// Links not confirmed within this time are dropped, e.g. a dialog left open overnight
+const CONFIRMATION_WINDOW_MINUTES: i64 = 10;
+/// Bounds what a page opening links in a loop can pile up
+const MAX_PENDING: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DeepLinkAction {
+    /// `z8://auth/callback`, the end of the browser sign-in
+    AuthCallback,
+    /// `z8://clock/in?location=home`; without a location, the network rules decide
+    ClockIn { location: Option<WorkLocationType> },
+    /// `z8://clock/out`
+    ClockOut,
+    /// `z8://break/start`, a clock-out that keeps the project for afterwards
+    StartBreak,
+    /// `z8://org/switch?id=...`
+    SwitchOrganization { id: String },
+    /// `z8://open/settings`
+    OpenSettings,
+}
+
+/// Whether an action may run without the user's say-so
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confirmation {
+    Never,
+    Always,
+}
+
+impl DeepLinkAction {
+    pub fn parse(url: &Url) -> Result<Self> {
+        if url.scheme() != "z8" {
+            bail!("Not a z8 link: {}", url);
+        }
+
+        let route = format!("{}{}", url.host_str().unwrap_or_default(), url.path());
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        Ok(match route.trim_end_matches('/') {
+            "auth/callback" => Self::AuthCallback,
+            "clock/in" => Self::ClockIn {
+                location: query("location")
+                    .map(|value| {
+                        WorkLocationType::from_str(&value)
+                            .ok_or_else(|| anyhow!("Invalid work location '{}'", value))
+                    })
+                    .transpose()?,
+            },
+            "clock/out" => Self::ClockOut,
+            "break/start" => Self::StartBreak,
+            "org/switch" => Self::SwitchOrganization {
+                id: query("id").ok_or_else(|| anyhow!("The link has no organization id"))?,
+            },
+            "open/settings" => Self::OpenSettings,
+            other => bail!("Unsupported link action '{}'", other),
+        })
+    }
+
+    pub fn confirmation(&self) -> Confirmation {
+        match self {
+            // The callback only completes a sign-in this app started
+            Self::AuthCallback | Self::OpenSettings => Confirmation::Never,
+            Self::ClockIn { .. }
+            | Self::ClockOut
+            | Self::StartBreak
+            | Self::SwitchOrganization { .. } => Confirmation::Always,
+        }
+    }
+
+    /// What the confirmation dialog asks, e.g. `Clock in (home)`
+    fn describe(&self) -> String {
+        match self {
+            Self::AuthCallback => "Finish signing in".to_string(),
+            Self::ClockIn {
+                location: Some(location),
+            } => format!("Clock in ({})", location.as_str()),
+            Self::ClockIn { location: None } => "Clock in".to_string(),
+            Self::ClockOut => "Clock out".to_string(),
+            Self::StartBreak => "Start a break".to_string(),
+            Self::SwitchOrganization { .. } => "Switch organization".to_string(),
+            Self::OpenSettings => "Open settings".to_string(),
+        }
+    }
+}
+
+/// A link action waiting for the user to confirm it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingDeepLink {
+    pub id: u64,
+    pub action: DeepLinkAction,
+    pub description: String,
+    pub received_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+pub struct DeepLinkState {
+    pending: Vec<PendingDeepLink>,
+    next_id: u64,
+}
+
+impl DeepLinkState {
+    fn add(&mut self, action: DeepLinkAction, at: DateTime<Utc>) -> PendingDeepLink {
+        self.next_id += 1;
+        let pending = PendingDeepLink {
+            id: self.next_id,
+            description: action.describe(),
+            action,
+            received_at: at,
+        };
+
+        self.pending.push(pending.clone());
+        if self.pending.len() > MAX_PENDING {
+            self.pending.remove(0);
+        }
+        pending
+    }
+
+    /// Removes the action; `None` when it is unknown or was left too long
+    fn take(&mut self, id: u64, now: DateTime<Utc>) -> Option<PendingDeepLink> {
+        let index = self.pending.iter().position(|pending| pending.id == id)?;
+        let pending = self.pending.remove(index);
+        (now - pending.received_at <= Duration::minutes(CONFIRMATION_WINDOW_MINUTES))
+            .then_some(pending)
+    }
+}
+
+/// Handles a `z8://` link opened while the app runs or passed to a second instance
+pub fn handle(app_handle: &AppHandle, url: &Url) {
+    let action = match DeepLinkAction::parse(url) {
+        Ok(action) => action,
+        Err(e) => {
+            log::warn!("Ignoring deep link {}: {}", url, e);
+            notify::send(
+                app_handle,
+                "z8 Timer",
+                &format!("Can't open this link: {}", e),
+            );
+            return;
+        }
+    };
+
+    if action == DeepLinkAction::AuthCallback {
+        let handle = app_handle.clone();
+        let url = url.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = auth::handle_deep_link_callback(&handle, &url).await {
+                log::error!("OAuth callback error: {}", e);
+            }
+        });
+        return;
+    }
+
+    log::info!("Deep link action: {:?}", action);
+    show_window(app_handle);
+
+    match action.confirmation() {
+        Confirmation::Never => {
+            let handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = perform(&handle, action).await {
+                    log::error!("Deep link action failed: {}", e);
+                }
+            });
+        }
+        Confirmation::Always => {
+            let state = app_handle.state::<Arc<AppState>>();
+            let pending = state.deep_links.lock().add(action, Utc::now());
+            if let Err(e) = app_handle.emit("deep_link_requested", &pending) {
+                log::error!("Failed to emit deep link request: {}", e);
+            }
+        }
+    }
+}
+
+/// Runs a link action the user confirmed
+pub async fn confirm(app_handle: &AppHandle, id: u64) -> Result<()> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let pending = state
+        .deep_links
+        .lock()
+        .take(id, Utc::now())
+        .ok_or_else(|| anyhow!("This link has expired; open it again"))?;
+
+    perform(app_handle, pending.action).await
+}
+
+pub fn dismiss(app_handle: &AppHandle, id: u64) {
+    let state = app_handle.state::<Arc<AppState>>();
+    state.deep_links.lock().take(id, Utc::now());
+}
+
+async fn perform(app_handle: &AppHandle, action: DeepLinkAction) -> Result<()> {
+    match action {
+        DeepLinkAction::ClockIn { location } => {
+            let suggestion = commands::suggest_work_location(app_handle.clone())
+                .await
+                .map_err(|e| anyhow!(e))?;
+            let work_location_type = location
+                .or(suggestion.as_ref().map(|suggestion| suggestion.location))
+                .unwrap_or(WorkLocationType::Office);
+            commands::clock_in(
+                app_handle.clone(),
+                work_location_type.as_str().to_string(),
+                None,
+                None,
+                None,
+                suggestion,
+                Some(EventSource::Link),
+            )
+            .await
+            .map_err(|e| anyhow!(e))?;
+        }
+        DeepLinkAction::ClockOut => {
+            commands::clock_out(
+                app_handle.clone(),
+                None,
+                None,
+                None,
+                Some(EventSource::Link),
+            )
+            .await
+            .map_err(|e| anyhow!(e))?;
+        }
+        DeepLinkAction::StartBreak => {
+            commands::start_break(app_handle.clone(), EventSource::Link)
+                .await
+                .map_err(|e| anyhow!(e))?;
+        }
+        DeepLinkAction::SwitchOrganization { id } => {
+            commands::switch_organization(app_handle.clone(), id.clone())
+                .await
+                .map_err(|e| anyhow!(e))?;
+            app_handle.emit("organization_switched", &id)?;
+        }
+        DeepLinkAction::OpenSettings => {
+            app_handle.emit("open_settings", ())?;
+        }
+        DeepLinkAction::AuthCallback => {}
+    }
+
+    Ok(())
+}
+
+fn show_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Confirmation, DeepLinkAction, DeepLinkState};
+    use crate::clock::WorkLocationType;
+    use chrono::{DateTime, Utc};
+    use url::Url;
+
+    fn parse(link: &str) -> anyhow::Result<DeepLinkAction> {
+        DeepLinkAction::parse(&Url::parse(link).unwrap())
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_actions_and_asks_before_punching() {
+        let clock_in = parse("z8://clock/in?location=home").unwrap();
+        assert_eq!(
+            clock_in,
+            DeepLinkAction::ClockIn {
+                location: Some(WorkLocationType::Home)
+            }
+        );
+        assert_eq!(clock_in.confirmation(), Confirmation::Always);
+        assert_eq!(
+            parse("z8://org/switch?id=org-2").unwrap(),
+            DeepLinkAction::SwitchOrganization {
+                id: "org-2".to_string()
+            }
+        );
+        assert_eq!(
+            parse("z8://open/settings/").unwrap().confirmation(),
+            Confirmation::Never
+        );
+        assert_eq!(
+            parse("z8://auth/callback?code=x").unwrap(),
+            DeepLinkAction::AuthCallback
+        );
+
+        assert!(parse("z8://clock/in?location=beach").is_err());
+        assert!(parse("z8://org/switch").is_err());
+        assert!(parse("z8://admin/delete").is_err());
+    }
+
+    #[test]
+    fn pending_actions_expire_and_are_bounded() {
+        let mut state = DeepLinkState::default();
+        let first = state.add(DeepLinkAction::ClockOut, at("2026-05-11T08:00:00Z"));
+        assert!(state.take(first.id, at("2026-05-11T08:11:00Z")).is_none());
+
+        let second = state.add(DeepLinkAction::ClockOut, at("2026-05-11T09:00:00Z"));
+        assert!(state.take(second.id, at("2026-05-11T09:05:00Z")).is_some());
+        assert!(state.take(second.id, at("2026-05-11T09:05:00Z")).is_none());
+
+        let ids: Vec<u64> = (0..7)
+            .map(|_| {
+                state
+                    .add(DeepLinkAction::StartBreak, at("2026-05-11T10:00:00Z"))
+                    .id
+            })
+            .collect();
+        assert!(state.take(ids[0], at("2026-05-11T10:01:00Z")).is_none());
+        assert!(state.take(ids[6], at("2026-05-11T10:01:00Z")).is_some());
+    }
+}
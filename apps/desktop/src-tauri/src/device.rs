@@ -0,0 +1,289 @@
+//! The key that identifies this installation. It is registered with the webapp at
+//! sign-in and signs what the app records while offline, so the webapp can tell
+//! which device a punch came from and that it arrived unchanged.
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signer, SigningKey};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::api::{ApiRequest, Z8ApiClient};
+
+const KEY_FILE: &str = "device_key";
+const CORRUPT_KEY_FILE: &str = "device_key.corrupt";
+
+pub struct DeviceKey {
+    signing_key: SigningKey,
+}
+
+impl DeviceKey {
+    /// Loads the device key, creating one on first start
+    pub fn load_or_create(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join(KEY_FILE);
+
+        match fs::read(&path) {
+            Ok(contents) => match decode_key(&contents) {
+                Some(bytes) => {
+                    return Ok(Self {
+                        signing_key: SigningKey::from_bytes(&bytes),
+                    })
+                }
+                None => {
+                    // A damaged key must not stop the app from starting. The new key
+                    // is registered at the next sign-in; the old file is kept aside.
+                    let corrupt = app_data_dir.join(CORRUPT_KEY_FILE);
+                    log::error!(
+                        "Invalid device key in {}, moving it to {} and creating a new one",
+                        path.display(),
+                        corrupt.display()
+                    );
+                    fs::rename(&path, &corrupt)?;
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        write_private(&path, &STANDARD.encode(bytes))?;
+        log::info!("Created a new device key");
+
+        Ok(Self {
+            signing_key: SigningKey::from_bytes(&bytes),
+        })
+    }
+
+    /// Base64 of the Ed25519 public key
+    pub fn public_key(&self) -> String {
+        STANDARD.encode(self.signing_key.verifying_key().as_bytes())
+    }
+
+    /// Base64 of the Ed25519 signature over `message`
+    pub fn sign(&self, message: &[u8]) -> String {
+        STANDARD.encode(self.signing_key.sign(message).to_bytes())
+    }
+
+    /// Sent with the login code exchange. Signing the PKCE verifier proves the app
+    /// that started the login holds the key.
+    pub fn registration(&self, verifier: &str) -> DeviceRegistration {
+        DeviceRegistration {
+            public_key: self.public_key(),
+            signature: self.sign(verifier.as_bytes()),
+            name: device_name(),
+            platform: std::env::consts::OS.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceRegistration {
+    pub public_key: String,
+    pub signature: String,
+    pub name: String,
+    pub platform: String,
+}
+
+/// A device registered for the signed-in user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Device {
+    pub id: String,
+    pub name: Option<String>,
+    pub platform: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Set locally; true for the device this app runs on
+    #[serde(default)]
+    pub is_current: bool,
+}
+
+#[derive(Deserialize)]
+struct DevicesResponse {
+    devices: Vec<Device>,
+}
+
+pub struct DeviceService {
+    api: Z8ApiClient,
+}
+
+impl DeviceService {
+    pub fn new(api: Z8ApiClient) -> Self {
+        Self { api }
+    }
+
+    /// Lists the user's active devices, this one first
+    pub async fn list(&self) -> Result<Vec<Device>> {
+        let response = self
+            .api
+            .send(ApiRequest::get("/api/desktop/devices"))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Failed to fetch devices: {}", response.status));
+        }
+
+        let current = self.api.device_id();
+        let mut devices = response.json::<DevicesResponse>()?.devices;
+        for device in &mut devices {
+            device.is_current = current.as_deref() == Some(device.id.as_str());
+        }
+        devices.sort_by_key(|device| !device.is_current);
+        Ok(devices)
+    }
+
+    /// Signs the device out; its session stops working on its next request
+    pub async fn revoke(&self, device_id: &str) -> Result<()> {
+        let response = self
+            .api
+            .send(ApiRequest::post(
+                &format!("/api/desktop/devices/{}/revoke", device_id),
+                serde_json::json!({}),
+            ))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Failed to revoke device: {}", response.status));
+        }
+
+        Ok(())
+    }
+}
+
+/// The computer's name as shown in the device list, e.g. `DESKTOP-4F2KQ7`
+fn device_name() -> String {
+    let from_env = std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok();
+    let from_command = || {
+        #[cfg(target_os = "macos")]
+        let output = Command::new("scutil")
+            .args(["--get", "ComputerName"])
+            .output();
+        #[cfg(not(target_os = "macos"))]
+        let output = Command::new("hostname").output();
+
+        output
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    from_env
+        .or_else(from_command)
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| format!("z8 Timer on {}", std::env::consts::OS))
+}
+
+fn decode_key(contents: &[u8]) -> Option<[u8; 32]> {
+    STANDARD
+        .decode(String::from_utf8_lossy(contents).trim())
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+/// Writes a file only the signed-in OS user may read
+fn write_private(path: &Path, contents: &str) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(path)?;
+        file.write_all(contents.as_bytes())?;
+    }
+    #[cfg(not(unix))]
+    fs::write(path, contents)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeviceKey, DeviceService};
+    use crate::api::testing::fake_client;
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+    use std::fs;
+
+    #[test]
+    fn keeps_the_key_across_starts_and_signs_verifiably() {
+        let dir = std::env::temp_dir().join(format!("z8-device-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let key = DeviceKey::load_or_create(&dir).unwrap();
+        let reloaded = DeviceKey::load_or_create(&dir).unwrap();
+        assert_eq!(key.public_key(), reloaded.public_key());
+
+        let public_key: [u8; 32] = STANDARD
+            .decode(key.public_key())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let signature: [u8; 64] = STANDARD
+            .decode(key.sign(b"punch"))
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let verifying_key = VerifyingKey::from_bytes(&public_key).unwrap();
+        assert!(verifying_key
+            .verify(b"punch", &Signature::from_bytes(&signature))
+            .is_ok());
+        assert!(verifying_key
+            .verify(b"edited", &Signature::from_bytes(&signature))
+            .is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn replaces_a_damaged_key_instead_of_failing() {
+        let dir = std::env::temp_dir().join(format!("z8-device-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("device_key"), b"\xff not a key").unwrap();
+
+        let key = DeviceKey::load_or_create(&dir).unwrap();
+        assert_eq!(
+            DeviceKey::load_or_create(&dir).unwrap().public_key(),
+            key.public_key()
+        );
+        assert_eq!(
+            fs::read(dir.join("device_key.corrupt")).unwrap(),
+            b"\xff not a key"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn marks_the_current_device_and_lists_it_first() {
+        let (client, transport) = fake_client();
+        client.set_device_id(Some("device-2".to_string()));
+        transport.respond(
+            200,
+            serde_json::json!({
+                "devices": [
+                    { "id": "device-1", "name": "Old laptop", "platform": "windows", "createdAt": "2026-01-05T08:00:00Z", "lastUsedAt": null },
+                    { "id": "device-2", "name": "Desk", "platform": "linux", "createdAt": "2026-05-11T08:00:00Z", "lastUsedAt": "2026-05-11T09:00:00Z" },
+                ]
+            }),
+        );
+
+        let devices = DeviceService::new(client).list().await.unwrap();
+        assert_eq!(devices[0].id, "device-2");
+        assert!(devices[0].is_current);
+        assert!(!devices[1].is_current);
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::notify;
+use crate::state::AppState;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Idle time below this is treated as still being at the desk
+const AWAY_THRESHOLD_MINUTES: i64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PromptReason {
+    /// The configured end of day has passed
+    EndOfDay,
+    /// No input for a long time after the end of day
+    LongIdle,
+}
+
+/// Asks whether a clock that is still running was forgotten
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockOutPrompt {
+    pub reason: PromptReason,
+    /// Suggested clock-out time; `None` when the user is still active
+    pub last_activity: Option<DateTime<Utc>>,
+    pub idle_minutes: i64,
+}
+
+/// Parses the `HH:MM` end-of-day setting
+pub fn parse_end_of_day(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// Remembers what was already asked so each prompt fires once
+#[derive(Debug, Default)]
+struct PromptHistory {
+    end_of_day: Option<NaiveDate>,
+    idle_since: Option<DateTime<Utc>>,
+}
+
+impl PromptHistory {
+    fn next_prompt<Tz: TimeZone>(
+        &mut self,
+        zone: &Tz,
+        now: DateTime<Utc>,
+        last_activity: Option<DateTime<Utc>>,
+        end_of_day: NaiveTime,
+        idle_limit_minutes: i64,
+    ) -> Option<ClockOutPrompt> {
+        let now_local = now.with_timezone(zone);
+        let today = now_local.date_naive();
+
+        // A clock left running overnight is past the end of the day it was last used on
+        let past_end_of_day = now_local.time() >= end_of_day
+            || last_activity.is_some_and(|at| at.with_timezone(zone).date_naive() < today);
+        if !past_end_of_day {
+            return None;
+        }
+
+        let idle_minutes = last_activity.map_or(0, |at| (now - at).num_minutes().max(0));
+        let last_activity = last_activity.filter(|_| idle_minutes >= AWAY_THRESHOLD_MINUTES);
+
+        let reason = if idle_minutes >= idle_limit_minutes && self.idle_since != last_activity {
+            self.idle_since = last_activity;
+            PromptReason::LongIdle
+        } else if self.end_of_day != Some(today) {
+            self.end_of_day = Some(today);
+            PromptReason::EndOfDay
+        } else {
+            return None;
+        };
+
+        Some(ClockOutPrompt {
+            reason,
+            last_activity,
+            idle_minutes,
+        })
+    }
+}
+
+impl ClockOutPrompt {
+    fn message(&self) -> String {
+        match self.last_activity {
+            Some(at) => format!(
+                "No activity since {}. Clock out at that time?",
+                at.with_timezone(&Local).format("%H:%M")
+            ),
+            None => "Your work day is over. Don't forget to clock out.".to_string(),
+        }
+    }
+}
+
+/// Prompts to clock out when the clock keeps running after the end of day
+pub async fn start_end_of_day_monitor(app_handle: AppHandle) {
+    log::info!("Starting end-of-day monitor");
+
+    let state = app_handle.state::<Arc<AppState>>();
+    let mut history = PromptHistory::default();
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        if !state.is_clocked_in() {
+            continue;
+        }
+
+        let (end_of_day, idle_limit_minutes) = {
+            let settings = state.settings.read();
+            (
+                settings
+                    .end_of_day_time
+                    .as_deref()
+                    .and_then(parse_end_of_day),
+                i64::from(settings.end_of_day_idle_minutes),
+            )
+        };
+        let Some(end_of_day) = end_of_day else {
+            continue;
+        };
+
+        let Some(prompt) = history.next_prompt(
+            &Local,
+            Utc::now(),
+            state.last_activity(),
+            end_of_day,
+            idle_limit_minutes,
+        ) else {
+            continue;
+        };
+
+        log::info!("Prompting for forgotten clock-out: {:?}", prompt);
+        notify::send(&app_handle, "Still clocked in?", &prompt.message());
+
+        if let Err(e) = app_handle.emit("forgotten_clock_out", &prompt) {
+            log::error!("Failed to emit forgotten clock-out event: {}", e);
+        }
+
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_end_of_day, PromptHistory, PromptReason};
+    use chrono::{DateTime, NaiveTime, Utc};
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn prompts_once_at_end_of_day_and_again_when_idle() {
+        let end_of_day = NaiveTime::from_hms_opt(18, 0, 0).unwrap();
+        let mut history = PromptHistory::default();
+        let mut next = |now: &str, last_activity: &str| {
+            history
+                .next_prompt(&Utc, at(now), Some(at(last_activity)), end_of_day, 60)
+                .map(|prompt| (prompt.reason, prompt.last_activity))
+        };
+
+        assert_eq!(next("2026-10-14T17:30:00Z", "2026-10-14T17:29:00Z"), None);
+        assert_eq!(
+            next("2026-10-14T18:01:00Z", "2026-10-14T18:00:00Z"),
+            Some((PromptReason::EndOfDay, None))
+        );
+        assert_eq!(next("2026-10-14T18:30:00Z", "2026-10-14T18:02:00Z"), None);
+        assert_eq!(
+            next("2026-10-14T19:05:00Z", "2026-10-14T18:02:00Z"),
+            Some((PromptReason::LongIdle, Some(at("2026-10-14T18:02:00Z"))))
+        );
+        assert_eq!(next("2026-10-14T19:30:00Z", "2026-10-14T18:02:00Z"), None);
+
+        // Still running the next morning
+        assert_eq!(
+            next("2026-10-15T07:00:00Z", "2026-10-14T18:02:00Z"),
+            Some((PromptReason::EndOfDay, Some(at("2026-10-14T18:02:00Z"))))
+        );
+    }
+
+    #[test]
+    fn parses_end_of_day_setting() {
+        assert_eq!(
+            parse_end_of_day(" 17:30 "),
+            NaiveTime::from_hms_opt(17, 30, 0)
+        );
+        assert_eq!(parse_end_of_day("5pm"), None);
+    }
+}
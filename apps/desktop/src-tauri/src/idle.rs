@@ -4,7 +4,9 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 
+use crate::journal::{self, EventSource, JournalEvent, JournalKind, JournalOutcome};
 use crate::state::AppState;
+use crate::triggers::{self, Observation};
 
 const IDLE_THRESHOLD_SECS: u64 = 5 * 60; // 5 minutes
 const CHECK_INTERVAL_SECS: u64 = 10; // Check every 10 seconds
@@ -48,6 +50,8 @@ pub fn start_idle_monitor(app_handle: AppHandle) {
     std::thread::spawn(move || {
         let mut was_idle = false;
         let mut idle_start: Option<Instant> = None;
+        // Tracked regardless of the clock, for the automatic clock-in/out rules
+        let mut was_away = false;
 
         loop {
             std::thread::sleep(Duration::from_secs(CHECK_INTERVAL_SECS));
@@ -59,6 +63,17 @@ pub fn start_idle_monitor(app_handle: AppHandle) {
             // Check if user is clocked in
             let state = app_handle.state::<Arc<AppState>>();
             let is_clocked_in = state.is_clocked_in();
+            let last_activity_at = chrono::Utc::now()
+                - chrono::Duration::milliseconds(idle_duration.as_millis() as i64);
+            state.set_last_activity(last_activity_at);
+
+            // Idle rules punch at the last input; activity rules see every tick with input
+            if is_idle && !was_away {
+                triggers::observe(&app_handle, Observation::Idle, last_activity_at);
+            } else if idle_duration < Duration::from_secs(CHECK_INTERVAL_SECS) {
+                triggers::observe(&app_handle, Observation::Activity, chrono::Utc::now());
+            }
+            was_away = is_idle;
 
             if is_idle && !was_idle && is_clocked_in {
                 // User just became idle while clocked in
@@ -82,6 +97,20 @@ pub fn start_idle_monitor(app_handle: AppHandle) {
                         idle_ms
                     );
 
+                    journal::record(
+                        &state,
+                        JournalEvent::new(
+                            JournalKind::Idle,
+                            EventSource::System,
+                            "idle",
+                            JournalOutcome::Noted,
+                        )
+                        .details(serde_json::json!({
+                            "idleStart": idle_start_time,
+                            "idleDurationMs": idle_ms,
+                        })),
+                    );
+
                     // Emit event to frontend
                     if let Err(e) = app_handle.emit("idle_detected", event) {
                         log::error!("Failed to emit idle event: {}", e);
@@ -0,0 +1,391 @@
+//! Append-only record of what the app did: punch attempts with the webapp's
+//! answer, idle episodes, break decisions and offline queue replays.
+//!
+//! Kept apart from the offline queue, whose rows disappear once sent, so a
+//! disputed timesheet can be traced back to what happened on the device.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::api::is_network_error;
+use crate::state::AppState;
+
+/// Entries returned when the query sets no limit
+const DEFAULT_QUERY_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JournalKind {
+    /// A clock-in, clock-out or project switch and the webapp's answer
+    Punch,
+    /// The user was away while clocked in
+    Idle,
+    /// What the user decided about a break, e.g. after being idle
+    Break,
+    /// An offline queue action replayed to the webapp
+    Sync,
+}
+
+/// What started the action
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EventSource {
+    /// The main window
+    #[default]
+    Ui,
+    Tray,
+    Hotkey,
+    /// An automatic clock-in/out rule
+    Rule,
+    /// A `z8://` link
+    Link,
+    /// `z8-timer` on the command line
+    Cli,
+    /// The localhost control API
+    ControlApi,
+    /// Background work such as the idle monitor and queue processor
+    System,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JournalOutcome {
+    /// The webapp accepted it
+    Accepted,
+    /// The webapp was unreachable; saved to the offline queue
+    Queued,
+    /// Refused by the webapp or failed locally
+    Failed,
+    /// Nothing was sent, e.g. an idle episode
+    Noted,
+}
+
+/// An entry to append; the id and time are assigned when it is stored
+#[derive(Debug, Clone)]
+pub struct JournalEvent {
+    kind: JournalKind,
+    source: EventSource,
+    action: String,
+    outcome: JournalOutcome,
+    message: Option<String>,
+    details: Option<serde_json::Value>,
+}
+
+impl JournalEvent {
+    pub fn new(
+        kind: JournalKind,
+        source: EventSource,
+        action: &str,
+        outcome: JournalOutcome,
+    ) -> Self {
+        Self {
+            kind,
+            source,
+            action: action.to_string(),
+            outcome,
+            message: None,
+            details: None,
+        }
+    }
+
+    /// A punch with the outcome of sending it to the webapp
+    pub fn punch(source: EventSource, action: &str, error: Option<&anyhow::Error>) -> Self {
+        Self::sent(JournalKind::Punch, source, action, error)
+    }
+
+    /// Something sent to the webapp; network errors mean it waits in the offline queue
+    pub fn sent(
+        kind: JournalKind,
+        source: EventSource,
+        action: &str,
+        error: Option<&anyhow::Error>,
+    ) -> Self {
+        match error {
+            None => Self::new(kind, source, action, JournalOutcome::Accepted),
+            Some(e) => {
+                let outcome = if is_network_error(e) {
+                    JournalOutcome::Queued
+                } else {
+                    JournalOutcome::Failed
+                };
+                Self::new(kind, source, action, outcome).message(e.to_string())
+            }
+        }
+    }
+
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    pub fn details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+    pub id: i64,
+    pub at: DateTime<Utc>,
+    pub kind: JournalKind,
+    pub source: EventSource,
+    /// e.g. `clock_in`, `clock_out_at`, `keep_working`
+    pub action: String,
+    pub outcome: JournalOutcome,
+    /// The webapp's error or a short explanation
+    pub message: Option<String>,
+    /// Punch time, work location, project and the like
+    pub details: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub kinds: Vec<JournalKind>,
+    pub limit: Option<usize>,
+}
+
+pub struct Journal {
+    conn: Connection,
+}
+
+impl Journal {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let conn = Connection::open(app_data_dir.join("journal.db"))?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS journal (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                at INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                source TEXT NOT NULL,
+                action TEXT NOT NULL,
+                outcome TEXT NOT NULL,
+                message TEXT,
+                details TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_journal_at ON journal(at);
+            CREATE TRIGGER IF NOT EXISTS journal_no_update BEFORE UPDATE ON journal
+            BEGIN SELECT RAISE(ABORT, 'the journal is append-only'); END;
+            CREATE TRIGGER IF NOT EXISTS journal_no_delete BEFORE DELETE ON journal
+            BEGIN SELECT RAISE(ABORT, 'the journal is append-only'); END;",
+        )?;
+
+        Ok(Self { conn })
+    }
+
+    pub fn append(&mut self, event: JournalEvent, at: DateTime<Utc>) -> Result<i64> {
+        self.conn.execute(
+            "INSERT INTO journal (at, kind, source, action, outcome, message, details)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![
+                at.timestamp_millis(),
+                serde_json::to_string(&event.kind)?,
+                serde_json::to_string(&event.source)?,
+                event.action,
+                serde_json::to_string(&event.outcome)?,
+                event.message,
+                event
+                    .details
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+            ],
+        )?;
+
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Entries matching the query, newest first
+    pub fn query(&self, query: &JournalQuery) -> Result<Vec<JournalEntry>> {
+        let from = query.from.map_or(i64::MIN, |from| from.timestamp_millis());
+        let to = query.to.map_or(i64::MAX, |to| to.timestamp_millis());
+        let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+
+        let mut stmt = self.conn.prepare(
+            "SELECT id, at, kind, source, action, outcome, message, details
+             FROM journal
+             WHERE at >= ? AND at <= ?
+             ORDER BY at DESC, id DESC",
+        )?;
+        let mut rows = stmt.query(params![from, to])?;
+        let mut entries = Vec::new();
+
+        while entries.len() < limit {
+            let Some(row) = rows.next()? else {
+                break;
+            };
+            let id: i64 = row.get(0)?;
+            let entry = (|| -> Result<JournalEntry> {
+                let details: Option<String> = row.get(7)?;
+                Ok(JournalEntry {
+                    id,
+                    at: DateTime::from_timestamp_millis(row.get(1)?).unwrap_or_default(),
+                    kind: serde_json::from_str(&row.get::<_, String>(2)?)?,
+                    source: serde_json::from_str(&row.get::<_, String>(3)?)?,
+                    action: row.get(4)?,
+                    outcome: serde_json::from_str(&row.get::<_, String>(5)?)?,
+                    message: row.get(6)?,
+                    details: details.as_deref().map(serde_json::from_str).transpose()?,
+                })
+            })();
+
+            match entry {
+                Ok(entry) if query.kinds.is_empty() || query.kinds.contains(&entry.kind) => {
+                    entries.push(entry)
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Skipping malformed journal entry {}: {}", id, e),
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Appends to the journal; failures are logged since the action itself already happened
+pub fn record(state: &AppState, event: JournalEvent) {
+    if let Err(e) = state.journal.lock().append(event, Utc::now()) {
+        log::warn!("Failed to write journal entry: {}", e);
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JournalExport<'a> {
+    app_version: &'a str,
+    os: &'a str,
+    exported_at: DateTime<Utc>,
+    entries: &'a [JournalEntry],
+}
+
+/// Writes the matching entries to a JSON file in `dir` for a support ticket
+pub fn export(journal: &Journal, query: &JournalQuery, dir: &Path) -> Result<PathBuf> {
+    let query = JournalQuery {
+        limit: Some(query.limit.unwrap_or(usize::MAX)),
+        ..query.clone()
+    };
+    let entries = journal.query(&query)?;
+    let exported_at = Utc::now();
+
+    let export = JournalExport {
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        exported_at,
+        entries: &entries,
+    };
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!(
+        "z8-activity-{}.json",
+        exported_at.format("%Y%m%d-%H%M%S")
+    ));
+    fs::write(&path, serde_json::to_string_pretty(&export)?)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        export, EventSource, Journal, JournalEvent, JournalKind, JournalOutcome, JournalQuery,
+    };
+    use crate::api::ApiError;
+    use chrono::{DateTime, Utc};
+    use std::fs;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("z8-journal-{}-{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn records_outcomes_and_refuses_changes() {
+        let dir = temp_dir("append");
+        let mut journal = Journal::new(&dir).unwrap();
+
+        let offline = anyhow::Error::from(ApiError::Network("timed out".into()));
+        journal
+            .append(
+                JournalEvent::punch(EventSource::Rule, "clock_in", Some(&offline))
+                    .details(serde_json::json!({ "workLocationType": "office" })),
+                at("2026-05-11T07:58:00Z"),
+            )
+            .unwrap();
+        journal
+            .append(
+                JournalEvent::new(
+                    JournalKind::Idle,
+                    EventSource::System,
+                    "idle",
+                    JournalOutcome::Noted,
+                ),
+                at("2026-05-11T10:30:00Z"),
+            )
+            .unwrap();
+
+        let entries = journal.query(&JournalQuery::default()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind, JournalKind::Idle);
+        assert_eq!(entries[1].outcome, JournalOutcome::Queued);
+        assert_eq!(entries[1].source, EventSource::Rule);
+        assert_eq!(
+            entries[1].details,
+            Some(serde_json::json!({ "workLocationType": "office" }))
+        );
+
+        assert!(journal
+            .conn
+            .execute("UPDATE journal SET outcome = '\"accepted\"'", [])
+            .is_err());
+        assert!(journal.conn.execute("DELETE FROM journal", []).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn filters_by_time_and_kind_and_exports() {
+        let dir = temp_dir("query");
+        let mut journal = Journal::new(&dir).unwrap();
+        for (time, kind) in [
+            ("2026-05-10T09:00:00Z", JournalKind::Punch),
+            ("2026-05-11T09:00:00Z", JournalKind::Punch),
+            ("2026-05-11T12:00:00Z", JournalKind::Sync),
+        ] {
+            let event =
+                JournalEvent::new(kind, EventSource::Ui, "clock_in", JournalOutcome::Accepted);
+            journal.append(event, at(time)).unwrap();
+        }
+
+        let query = JournalQuery {
+            from: Some(at("2026-05-11T00:00:00Z")),
+            kinds: vec![JournalKind::Punch],
+            ..JournalQuery::default()
+        };
+        let entries = journal.query(&query).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].at, at("2026-05-11T09:00:00Z"));
+
+        let path = export(&journal, &JournalQuery::default(), &dir.join("exports")).unwrap();
+        let exported: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(exported["entries"].as_array().unwrap().len(), 3);
+        assert_eq!(exported["entries"][0]["kind"], "sync");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,12 +1,37 @@
+mod absences;
+mod api;
+mod approvals;
 mod auth;
+mod breaks;
+mod chain;
+mod cli;
 mod clock;
 mod commands;
+mod control;
+mod corrections;
+mod deep_link;
+mod device;
+mod end_of_day;
 mod idle;
+mod journal;
+mod location;
+mod network;
+mod notify;
 mod offline;
+mod organizations;
+mod projects;
+mod schedule;
+mod server_notifications;
 mod settings;
+mod shortcuts;
 mod startup;
 mod state;
+mod status_sync;
+mod timeline;
+mod timezone;
 mod tray;
+mod triggers;
+mod wellness;
 
 use state::AppState;
 use std::sync::Arc;
@@ -14,6 +39,11 @@ use tauri::Manager;
 use tauri_plugin_deep_link::DeepLinkExt;
 use url::Url;
 
+/// Runs a `z8-timer` subcommand; `None` means the desktop app should start
+pub fn run_cli(args: Vec<String>) -> Option<i32> {
+    cli::run(args)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
@@ -24,10 +54,17 @@ pub fn run() {
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
             // Handle deep link URLs passed from second instance
             log::info!("Single instance callback triggered with args: {:?}", args);
 
+            // `z8-timer clock-in` and friends run in the background
+            if let Ok(Some(command)) = cli::parse(args.get(1..).unwrap_or_default()) {
+                cli::handle_in_app(app, command);
+                return;
+            }
+
             // Focus the main window
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.show();
@@ -39,14 +76,7 @@ pub fn run() {
                 if arg.starts_with("z8://") {
                     if let Ok(url) = Url::parse(&arg) {
                         log::info!("Deep link from single-instance: {}", url);
-                        if url.scheme() == "z8" {
-                            let handle = app.clone();
-                            tauri::async_runtime::spawn(async move {
-                                if let Err(e) = auth::handle_deep_link_callback(&handle, &url).await {
-                                    log::error!("OAuth callback error: {}", e);
-                                }
-                            });
-                        }
+                        deep_link::handle(app, &url);
                     }
                 }
             }
@@ -54,29 +84,44 @@ pub fn run() {
         .setup(|app| {
             // Initialize application state
             let state = AppState::new(app.handle().clone())?;
+            let switch_project_shortcut = state.settings.read().switch_project_shortcut.clone();
             app.manage(Arc::new(state));
 
+            let revoked_handle = app.handle().clone();
+            app.state::<Arc<AppState>>()
+                .api
+                .on_device_revoked(move || auth::handle_device_revoked(&revoked_handle));
+
+            // Lets the command line hand its commands to this instance
+            let pid_path = app.path().app_data_dir()?.join(cli::PID_FILE);
+            if let Err(e) = std::fs::write(&pid_path, std::process::id().to_string()) {
+                log::warn!("Failed to write {}: {}", pid_path.display(), e);
+            }
+
             // Setup system tray
             tray::setup_tray(app)?;
+            // Started offline, the state comes from the cached status and the queue
+            let is_clocked_in = app.state::<Arc<AppState>>().is_clocked_in();
+            let _ = tray::update_tray_icon(app.handle(), is_clocked_in);
+
+            // A shortcut taken by another app shouldn't stop the timer from starting
+            if let Err(e) =
+                shortcuts::register_shortcuts(app.handle(), switch_project_shortcut.as_deref())
+            {
+                log::warn!("Failed to register global shortcuts: {}", e);
+            }
 
             // Register deep link protocol (required for Windows/Linux dev mode)
             #[cfg(any(windows, target_os = "linux"))]
             app.deep_link().register("z8")?;
 
-            // Register deep link handler for OAuth callback
+            // Register deep link handler for OAuth callbacks and link actions
             let handle = app.handle().clone();
             app.deep_link().on_open_url(move |event| {
                 let urls = event.urls();
                 for url in urls {
                     log::info!("Deep link received: {}", url);
-                    if url.scheme() == "z8" {
-                        let handle_clone = handle.clone();
-                        tauri::async_runtime::spawn(async move {
-                            if let Err(e) = auth::handle_deep_link_callback(&handle_clone, &url).await {
-                                log::error!("OAuth callback error: {}", e);
-                            }
-                        });
-                    }
+                    deep_link::handle(&handle, &url);
                 }
             });
 
@@ -92,22 +137,135 @@ pub fn run() {
                 offline::start_queue_processor(app_handle).await;
             });
 
+            // Pick up punches made in the webapp or on a phone
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                status_sync::start_status_sync(app_handle).await;
+            });
+
+            // Start break reminders
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                breaks::start_break_reminders(app_handle).await;
+            });
+
+            // Start shift reminders
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                schedule::start_shift_reminders(app_handle).await;
+            });
+
+            // Start end-of-day clock-out prompts
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                end_of_day::start_end_of_day_monitor(app_handle).await;
+            });
+
+            // Start correction approval notifications
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                corrections::start_correction_watcher(app_handle).await;
+            });
+
+            // Start the manager approval inbox watcher
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                approvals::start_approval_watcher(app_handle).await;
+            });
+
+            // Start server notification sync
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                server_notifications::start_notification_sync(app_handle).await;
+            });
+
+            // Start water reminders
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                wellness::start_water_reminders(app_handle).await;
+            });
+
+            // Start watching the network for automatic clock-in/out rules
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                triggers::start_network_watcher(app_handle).await;
+            });
+
+            // Start the localhost control API for scripts and editor plugins
+            control::start(app.handle());
+
+            // Start absence approval notifications
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                absences::start_absence_watcher(app_handle).await;
+            });
+
+            // Started by a subcommand such as `z8-timer login`
+            let args: Vec<String> = std::env::args().collect();
+            if let Ok(Some(command)) = cli::parse(args.get(1..).unwrap_or_default()) {
+                cli::handle_in_app(app.handle(), command);
+            }
+
             log::info!("z8 Timer setup complete");
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_clock_status,
             commands::clock_in,
+            commands::get_network_signals,
+            commands::suggest_work_location,
             commands::clock_out,
+            commands::clock_out_at,
             commands::clock_out_with_break,
+            commands::switch_project,
+            commands::get_today_timeline,
+            commands::get_week_summary,
+            commands::get_break_policy,
+            commands::get_schedule,
+            commands::check_clock_in,
+            commands::confirm_trigger,
+            commands::undo_trigger,
+            commands::dismiss_trigger,
+            commands::confirm_deep_link,
+            commands::dismiss_deep_link,
+            commands::get_journal,
+            commands::export_journal,
+            commands::record_keep_working,
+            commands::get_water_reminder_status,
+            commands::log_water,
+            commands::snooze_water_reminders,
+            commands::submit_correction,
+            commands::submit_missing_period,
+            commands::list_corrections,
+            commands::list_absences,
+            commands::request_absence,
+            commands::cancel_absence,
+            commands::list_my_requests,
+            commands::list_notifications,
+            commands::open_notification,
+            commands::mark_all_notifications_read,
+            commands::list_approvals,
+            commands::approve_request,
+            commands::reject_request,
+            commands::bulk_approve_requests,
+            commands::bulk_reject_requests,
+            commands::list_projects,
             commands::initiate_oauth,
             commands::logout,
             commands::get_session,
+            commands::list_organizations,
+            commands::switch_organization,
+            commands::list_devices,
+            commands::revoke_device,
             commands::get_settings,
             commands::save_settings,
+            commands::test_connection,
             commands::set_always_on_top,
             commands::set_auto_startup,
             commands::get_pending_queue_count,
+            commands::list_unsent_actions,
+            commands::retry_unsent_action,
+            commands::discard_unsent_action,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,409 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+use crate::clock::WorkLocationType;
+
+/// JSON file with rules pushed by IT, evaluated after the user's own rules
+const MANAGED_RULES_ENV: &str = "Z8_LOCATION_RULES";
+
+/// A network property that identifies where the device is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LocationSignal {
+    /// Name of the connected Wi-Fi network
+    WifiSsid,
+    /// Hardware address of the default gateway, stable per office router
+    GatewayMac,
+    /// A network interface whose name starts with the value, e.g. `tun` or `wg`
+    VpnInterface,
+}
+
+impl LocationSignal {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::WifiSsid => "Wi-Fi",
+            Self::GatewayMac => "gateway",
+            Self::VpnInterface => "VPN interface",
+        }
+    }
+}
+
+/// Maps a network signal to the work location it implies
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationRule {
+    pub signal: LocationSignal,
+    pub value: String,
+    pub location: WorkLocationType,
+}
+
+impl LocationRule {
+    fn matches(&self, signals: &NetworkSignals) -> Option<String> {
+        signal_matches(self.signal, &self.value, signals)
+    }
+}
+
+/// Returns the observed value when `signal` matches `value` on the current network
+pub fn signal_matches(
+    signal: LocationSignal,
+    value: &str,
+    signals: &NetworkSignals,
+) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    match signal {
+        LocationSignal::WifiSsid => signals.ssid.as_deref().filter(|ssid| *ssid == value),
+        LocationSignal::GatewayMac => signals
+            .gateway_mac
+            .as_deref()
+            .filter(|mac| *mac == normalize_mac(value)),
+        LocationSignal::VpnInterface => {
+            let prefix = value.to_lowercase();
+            signals
+                .interfaces
+                .iter()
+                .map(String::as_str)
+                .find(|name| name.to_lowercase().starts_with(&prefix))
+        }
+    }
+    .map(str::to_string)
+}
+
+/// What the current network looks like, as far as the rules are concerned
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSignals {
+    pub ssid: Option<String>,
+    pub gateway_mac: Option<String>,
+    pub interfaces: Vec<String>,
+}
+
+/// A location picked by a rule, with the signal that matched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationSuggestion {
+    pub location: WorkLocationType,
+    pub signal: LocationSignal,
+    /// The observed value, e.g. the SSID or interface name
+    pub value: String,
+}
+
+impl LocationSuggestion {
+    fn source(&self) -> String {
+        format!("{} \"{}\"", self.signal.label(), self.value)
+    }
+}
+
+/// How the work location of a clock-in was decided
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "camelCase")]
+pub enum LocationDetection {
+    /// The suggested location was kept
+    Detected { suggestion: LocationSuggestion },
+    /// The user picked something else than the suggestion
+    Override { suggestion: LocationSuggestion },
+}
+
+impl LocationDetection {
+    /// Compares the user's choice against the suggestion shown to them
+    pub fn for_choice(
+        chosen: WorkLocationType,
+        suggestion: Option<LocationSuggestion>,
+    ) -> Option<Self> {
+        let suggestion = suggestion?;
+        Some(if suggestion.location == chosen {
+            Self::Detected { suggestion }
+        } else {
+            Self::Override { suggestion }
+        })
+    }
+
+    /// Text stored in the time entry's location field
+    pub fn describe(&self, chosen: WorkLocationType) -> String {
+        match self {
+            Self::Detected { suggestion } => {
+                format!("{} (detected via {})", chosen.as_str(), suggestion.source())
+            }
+            Self::Override { suggestion } => format!(
+                "{} (selected manually, {} suggested via {})",
+                chosen.as_str(),
+                suggestion.location.as_str(),
+                suggestion.source()
+            ),
+        }
+    }
+}
+
+/// Returns the first rule matching the current network
+pub fn suggest(rules: &[LocationRule], signals: &NetworkSignals) -> Option<LocationSuggestion> {
+    rules.iter().find_map(|rule| {
+        rule.matches(signals).map(|value| LocationSuggestion {
+            location: rule.location,
+            signal: rule.signal,
+            value,
+        })
+    })
+}
+
+/// Loads the rules IT deployed through `Z8_LOCATION_RULES`, if any
+pub fn managed_rules() -> Vec<LocationRule> {
+    let Some(path) = std::env::var(MANAGED_RULES_ENV)
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+    else {
+        return Vec::new();
+    };
+
+    match std::fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|contents| Ok(serde_json::from_str(&contents)?))
+    {
+        Ok(rules) => rules,
+        Err(e) => {
+            log::warn!("Ignoring managed location rules in {}: {}", path, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Lowercase, colon-separated form so `AA-BB-..` and `aa:bb:..` compare equal
+fn normalize_mac(value: &str) -> String {
+    value.trim().to_lowercase().replace('-', ":")
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Reads the current network signals. Spawns system tools, so call it off the async runtime.
+pub fn read_signals() -> Result<NetworkSignals> {
+    let signals = NetworkSignals {
+        ssid: read_ssid().filter(|ssid| !ssid.is_empty()),
+        gateway_mac: read_gateway_mac().map(|mac| normalize_mac(&mac)),
+        interfaces: read_interfaces(),
+    };
+    log::debug!("Network signals: {:?}", signals);
+    Ok(signals)
+}
+
+#[cfg(target_os = "linux")]
+fn read_ssid() -> Option<String> {
+    if let Some(ssid) = run("iwgetid", &["-r"]) {
+        return Some(ssid.trim().to_string());
+    }
+    run("nmcli", &["-t", "-f", "active,ssid", "dev", "wifi"])?
+        .lines()
+        .find_map(|line| line.strip_prefix("yes:"))
+        .map(str::to_string)
+}
+
+#[cfg(target_os = "linux")]
+fn read_gateway_mac() -> Option<String> {
+    let route = std::fs::read_to_string("/proc/net/route").ok()?;
+    let gateway = parse_proc_route_gateway(&route)?;
+    let arp = std::fs::read_to_string("/proc/net/arp").ok()?;
+    parse_proc_arp(&arp, &gateway)
+}
+
+#[cfg(target_os = "linux")]
+fn read_interfaces() -> Vec<String> {
+    std::fs::read_dir("/sys/class/net")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn read_ssid() -> Option<String> {
+    run("networksetup", &["-getairportnetwork", "en0"])?
+        .trim()
+        .strip_prefix("Current Wi-Fi Network: ")
+        .map(str::to_string)
+}
+
+#[cfg(target_os = "macos")]
+fn read_gateway_mac() -> Option<String> {
+    let route = run("route", &["-n", "get", "default"])?;
+    let gateway = route
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("gateway: "))?
+        .to_string();
+    // "? (192.168.1.1) at aa:bb:cc:dd:ee:ff on en0 ifscope [ethernet]"
+    let arp = run("arp", &["-n", &gateway])?;
+    arp.split_whitespace()
+        .skip_while(|word| *word != "at")
+        .nth(1)
+        .filter(|mac| mac.contains(':'))
+        .map(str::to_string)
+}
+
+#[cfg(target_os = "macos")]
+fn read_interfaces() -> Vec<String> {
+    run("ifconfig", &["-l"])
+        .map(|names| names.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(windows)]
+fn read_ssid() -> Option<String> {
+    parse_netsh_ssid(&run("netsh", &["wlan", "show", "interfaces"])?)
+}
+
+#[cfg(windows)]
+fn read_gateway_mac() -> Option<String> {
+    // "0.0.0.0          0.0.0.0      192.168.1.1    192.168.1.20     25"
+    let route = run("route", &["print", "-4", "0.0.0.0"])?;
+    let gateway = route.lines().find_map(|line| {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        (columns.len() >= 5 && columns[0] == "0.0.0.0" && columns[1] == "0.0.0.0")
+            .then(|| columns[2].to_string())
+    })?;
+    let arp = run("arp", &["-a", &gateway])?;
+    arp.lines().find_map(|line| {
+        let mut columns = line.split_whitespace();
+        (columns.next() == Some(gateway.as_str()))
+            .then(|| columns.next().map(str::to_string))
+            .flatten()
+    })
+}
+
+#[cfg(windows)]
+fn read_interfaces() -> Vec<String> {
+    // "Enabled        Connected      Dedicated        Ethernet 2"
+    run("netsh", &["interface", "show", "interface"])
+        .map(|output| {
+            output
+                .lines()
+                .filter(|line| line.contains("Connected"))
+                .map(|line| {
+                    line.split_whitespace()
+                        .skip(3)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .filter(|name| !name.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Finds the default route's gateway in `/proc/net/route`, where addresses are little-endian hex
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_route_gateway(route: &str) -> Option<String> {
+    route.lines().skip(1).find_map(|line| {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        if columns.len() < 3 || columns[1] != "00000000" {
+            return None;
+        }
+        let gateway = u32::from_str_radix(columns[2], 16).ok()?;
+        (gateway != 0).then(|| std::net::Ipv4Addr::from(gateway.swap_bytes()).to_string())
+    })
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_arp(arp: &str, ip: &str) -> Option<String> {
+    arp.lines().skip(1).find_map(|line| {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        (columns.len() >= 4 && columns[0] == ip && columns[3] != "00:00:00:00:00:00")
+            .then(|| columns[3].to_string())
+    })
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_netsh_ssid(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "SSID").then(|| value.trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_netsh_ssid, parse_proc_arp, parse_proc_route_gateway, suggest, LocationDetection,
+        LocationRule, LocationSignal, NetworkSignals,
+    };
+    use crate::clock::WorkLocationType;
+
+    fn rule(signal: LocationSignal, value: &str, location: WorkLocationType) -> LocationRule {
+        LocationRule {
+            signal,
+            value: value.to_string(),
+            location,
+        }
+    }
+
+    #[test]
+    fn suggests_the_first_matching_rule_and_records_overrides() {
+        let rules = [
+            rule(LocationSignal::VpnInterface, "wg", WorkLocationType::Remote),
+            rule(
+                LocationSignal::GatewayMac,
+                "AA-BB-CC-DD-EE-FF",
+                WorkLocationType::Office,
+            ),
+            rule(LocationSignal::WifiSsid, "Home", WorkLocationType::Home),
+        ];
+        let at_office = NetworkSignals {
+            ssid: Some("Corp".to_string()),
+            gateway_mac: Some("aa:bb:cc:dd:ee:ff".to_string()),
+            interfaces: vec!["lo".to_string(), "wlan0".to_string()],
+        };
+
+        let suggestion = suggest(&rules, &at_office).unwrap();
+        assert_eq!(suggestion.location, WorkLocationType::Office);
+        assert_eq!(suggestion.signal, LocationSignal::GatewayMac);
+
+        let on_vpn = NetworkSignals {
+            interfaces: vec!["wg0".to_string()],
+            ..at_office.clone()
+        };
+        assert_eq!(
+            suggest(&rules, &on_vpn).map(|s| s.location),
+            Some(WorkLocationType::Remote)
+        );
+        assert!(suggest(&rules, &NetworkSignals::default()).is_none());
+
+        let detection =
+            LocationDetection::for_choice(WorkLocationType::Home, Some(suggestion)).unwrap();
+        assert_eq!(
+            detection.describe(WorkLocationType::Home),
+            "home (selected manually, office suggested via gateway \"aa:bb:cc:dd:ee:ff\")"
+        );
+        assert!(LocationDetection::for_choice(WorkLocationType::Home, None).is_none());
+    }
+
+    #[test]
+    fn parses_platform_network_output() {
+        let route = "Iface\tDestination\tGateway \tFlags\n\
+                     wlan0\t0000A8C0\t00000000\t0001\n\
+                     wlan0\t00000000\t0101A8C0\t0003\n";
+        assert_eq!(
+            parse_proc_route_gateway(route).as_deref(),
+            Some("192.168.1.1")
+        );
+
+        let arp =
+            "IP address       HW type     Flags       HW address            Mask     Device\n\
+                   192.168.1.1      0x1         0x2         aa:bb:cc:dd:ee:ff     *        wlan0\n";
+        assert_eq!(
+            parse_proc_arp(arp, "192.168.1.1").as_deref(),
+            Some("aa:bb:cc:dd:ee:ff")
+        );
+
+        let netsh = "    Name                   : Wi-Fi\n    SSID                   : Corp: Guest\n    BSSID                  : aa:bb:cc:dd:ee:ff\n";
+        assert_eq!(parse_netsh_ssid(netsh).as_deref(), Some("Corp: Guest"));
+    }
+}
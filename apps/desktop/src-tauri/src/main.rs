@@ -1,5 +1,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if let Some(code) = z8_timer_lib::run_cli(std::env::args().collect()) {
+        std::process::exit(code);
+    }
+
     z8_timer_lib::run()
 }
@@ -0,0 +1,251 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::fs;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::api::{is_network_error, ApiRequest, ReqwestTransport, Z8ApiClient, STATUS_TIMEOUT};
+use crate::settings::NetworkSettings;
+
+const PROXY_ENV: &str = "Z8_PROXY";
+const NO_PROXY_ENV: &str = "Z8_NO_PROXY";
+const CA_CERT_ENV: &str = "Z8_CA_CERT";
+const SYSTEM_ROOTS_ENV: &str = "Z8_USE_SYSTEM_ROOTS";
+
+/// Network configuration after merging settings with environment overrides
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkConfig {
+    pub proxy_url: Option<String>,
+    pub no_proxy: Option<String>,
+    pub ca_certificate_path: Option<String>,
+    pub use_system_roots: bool,
+}
+
+impl NetworkConfig {
+    /// Settings win over `Z8_*` environment variables. Without either, reqwest
+    /// still honours `HTTPS_PROXY`/`NO_PROXY` and the OS proxy configuration.
+    pub fn resolve(settings: &NetworkSettings) -> Self {
+        Self::resolve_with(settings, |key| std::env::var(key).ok())
+    }
+
+    fn resolve_with(settings: &NetworkSettings, env: impl Fn(&str) -> Option<String>) -> Self {
+        let pick = |value: &Option<String>, key: &str| {
+            non_empty(value.clone()).or_else(|| non_empty(env(key)))
+        };
+
+        let use_system_roots = settings.use_system_roots
+            || env(SYSTEM_ROOTS_ENV)
+                .map(|value| matches!(value.trim(), "1" | "true" | "yes"))
+                .unwrap_or(false);
+
+        Self {
+            proxy_url: pick(&settings.proxy_url, PROXY_ENV),
+            no_proxy: pick(&settings.no_proxy, NO_PROXY_ENV),
+            ca_certificate_path: pick(&settings.ca_certificate_path, CA_CERT_ENV),
+            use_system_roots,
+        }
+    }
+
+    /// Applies proxy and trust-store settings to a client builder
+    pub fn apply(&self, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder> {
+        let mut builder = builder.tls_built_in_native_certs(self.use_system_roots);
+
+        if let Some(proxy_url) = &self.proxy_url {
+            let proxy = reqwest::Proxy::all(proxy_url)
+                .map_err(|e| anyhow!("Invalid proxy URL {}: {}", proxy_url, e))?
+                .no_proxy(
+                    self.no_proxy
+                        .as_deref()
+                        .and_then(reqwest::NoProxy::from_string),
+                );
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(path) = &self.ca_certificate_path {
+            let pem = fs::read(path)
+                .map_err(|e| anyhow!("Cannot read CA certificate file {}: {}", path, e))?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+                .map_err(|e| anyhow!("Invalid CA certificate file {}: {}", path, e))?;
+
+            if certificates.is_empty() {
+                return Err(anyhow!(
+                    "CA certificate file {} contains no certificates",
+                    path
+                ));
+            }
+
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        Ok(builder)
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionStage {
+    Ok,
+    Config,
+    Proxy,
+    Tls,
+    Dns,
+    Connect,
+    Timeout,
+    Http,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTestResult {
+    pub ok: bool,
+    pub stage: ConnectionStage,
+    pub message: String,
+    pub status: Option<u16>,
+    pub proxy: Option<String>,
+    pub elapsed_ms: u64,
+}
+
+/// Maps a transport error chain to the stage that failed
+fn classify_failure(message: &str) -> ConnectionStage {
+    let message = message.to_lowercase();
+
+    if message.contains("proxy") || message.contains("tunnel") {
+        ConnectionStage::Proxy
+    } else if message.contains("certificate")
+        || message.contains("tls")
+        || message.contains("handshake")
+        || message.contains("unknownissuer")
+    {
+        ConnectionStage::Tls
+    } else if message.contains("dns") || message.contains("failed to lookup") {
+        ConnectionStage::Dns
+    } else if message.contains("timed out") || message.contains("timeout") {
+        ConnectionStage::Timeout
+    } else {
+        ConnectionStage::Connect
+    }
+}
+
+/// Calls the webapp health endpoint with the given configuration and reports
+/// exactly where the connection failed
+pub async fn test_connection(webapp_url: &str, config: &NetworkConfig) -> ConnectionTestResult {
+    let started = Instant::now();
+    let result = |ok, stage, message: String, status| ConnectionTestResult {
+        ok,
+        stage,
+        message,
+        status,
+        proxy: config.proxy_url.clone(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    };
+
+    let transport = match ReqwestTransport::new(config) {
+        Ok(transport) => transport,
+        Err(e) => return result(false, ConnectionStage::Config, e.to_string(), None),
+    };
+
+    let api = Z8ApiClient::with_transport(webapp_url, None, Arc::new(transport));
+    let request = ApiRequest::get("/api/health")
+        .unauthenticated()
+        .timeout(STATUS_TIMEOUT);
+
+    match api.send(request).await {
+        Ok(response) if response.is_success() => result(
+            true,
+            ConnectionStage::Ok,
+            "Connected".to_string(),
+            Some(response.status),
+        ),
+        Ok(response) => result(
+            false,
+            ConnectionStage::Http,
+            format!("Webapp responded with HTTP {}", response.status),
+            Some(response.status),
+        ),
+        Err(e) => {
+            let message = e.to_string();
+            let stage = if is_network_error(&e) {
+                classify_failure(&message)
+            } else {
+                ConnectionStage::Config
+            };
+            result(false, stage, message, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify_failure, ConnectionStage, NetworkConfig};
+    use crate::settings::NetworkSettings;
+
+    #[test]
+    fn settings_take_precedence_over_environment() {
+        let env = |key: &str| match key {
+            "Z8_PROXY" => Some("http://env-proxy:3128".to_string()),
+            "Z8_NO_PROXY" => Some("localhost".to_string()),
+            "Z8_USE_SYSTEM_ROOTS" => Some("1".to_string()),
+            _ => None,
+        };
+        let settings = NetworkSettings {
+            proxy_url: Some("http://settings-proxy:8080".to_string()),
+            no_proxy: Some("  ".to_string()),
+            ..NetworkSettings::default()
+        };
+
+        assert_eq!(
+            NetworkConfig::resolve_with(&settings, env),
+            NetworkConfig {
+                proxy_url: Some("http://settings-proxy:8080".to_string()),
+                no_proxy: Some("localhost".to_string()),
+                ca_certificate_path: None,
+                use_system_roots: true,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_unreadable_ca_certificate_files() {
+        let config = NetworkConfig {
+            ca_certificate_path: Some("/nonexistent/z8-ca.pem".to_string()),
+            ..NetworkConfig::default()
+        };
+
+        let error = config.apply(reqwest::Client::builder()).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Cannot read CA certificate file /nonexistent/z8-ca.pem"));
+    }
+
+    #[test]
+    fn classifies_transport_failures_by_stage() {
+        assert_eq!(
+            classify_failure("network error: error sending request: unsuccessful tunnel"),
+            ConnectionStage::Proxy
+        );
+        assert_eq!(
+            classify_failure("network error: invalid peer certificate: UnknownIssuer"),
+            ConnectionStage::Tls
+        );
+        assert_eq!(
+            classify_failure("network error: dns error: failed to lookup address information"),
+            ConnectionStage::Dns
+        );
+        assert_eq!(
+            classify_failure("network error: operation timed out"),
+            ConnectionStage::Timeout
+        );
+        assert_eq!(
+            classify_failure("network error: Connection refused (os error 111)"),
+            ConnectionStage::Connect
+        );
+    }
+}
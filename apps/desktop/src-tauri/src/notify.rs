@@ -0,0 +1,15 @@
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// Shows a desktop notification; failures are logged since reminders are best effort
+pub fn send(app_handle: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+    {
+        log::warn!("Failed to show notification \"{}\": {}", title, e);
+    }
+}
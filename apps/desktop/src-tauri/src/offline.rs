@@ -1,21 +1,62 @@
 use anyhow::Result;
 use chrono::{DateTime, TimeZone, Utc};
-use rusqlite::{params, Connection};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
-use crate::clock::{ClockService, WorkLocationType};
+use crate::api::{is_network_error, is_unknown_outcome};
+use crate::chain::{ChainLink, ReplayChain, GENESIS_HASH};
+use crate::clock::{
+    ClockService, ClockStatus, CorrectionRequest, PunchCapture, PunchDetails, SwitchProjectError,
+    WorkLocationType, CLOCK_STATUS_CACHE_KEY,
+};
+use crate::device::DeviceKey;
+use crate::journal::{EventSource, Journal, JournalEvent, JournalKind};
+use crate::location::LocationDetection;
+use crate::notify;
 use crate::state::AppState;
 use crate::tray;
+use crate::wellness::{WaterAction, WellnessService};
+
+/// Actions the webapp refused this often are no longer retried automatically
+const MAX_RETRIES: i32 = 5;
+/// Chain action recording that a queued row was dropped before it was sent
+const REMOVE_LINK_ACTION: &str = "remove";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ActionType {
     ClockIn,
     ClockOut,
     ClockOutWithBreak,
+    SwitchProject,
+    SubmitCorrection,
+    WaterAction,
+}
+
+impl ActionType {
+    /// Punches build on each other, so they have to reach the webapp in order
+    pub fn is_punch(&self) -> bool {
+        matches!(
+            self,
+            Self::ClockIn | Self::ClockOut | Self::ClockOutWithBreak | Self::SwitchProject
+        )
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ClockIn => "clock_in",
+            Self::ClockOut => "clock_out",
+            Self::ClockOutWithBreak => "clock_out_with_break",
+            Self::SwitchProject => "switch_project",
+            Self::SubmitCorrection => "submit_correction",
+            Self::WaterAction => "water_action",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,42 +67,210 @@ pub struct QueuedAction {
     pub payload: Option<String>,
     pub retry_count: i32,
     pub created_at: i64,
+    /// The row's latest link in the hash chain; `None` for rows queued before the chain
+    #[serde(skip)]
+    pub chain_sequence: Option<i64>,
+}
+
+impl QueuedAction {
+    /// Punches are sent with the hash chain; other actions' links go with the next punch
+    fn carries_chain(&self) -> bool {
+        self.action_type.is_punch()
+    }
+
+    /// The punches this action records once replayed, as `(entry type, timestamp)`
+    pub fn punches(&self) -> Vec<(&'static str, DateTime<Utc>)> {
+        let Some(timestamp) = Utc.timestamp_opt(self.timestamp, 0).single() else {
+            return Vec::new();
+        };
+
+        match self.action_type {
+            ActionType::ClockIn => vec![("clock_in", timestamp)],
+            ActionType::ClockOut => vec![("clock_out", timestamp)],
+            ActionType::ClockOutWithBreak => self
+                .payload
+                .as_deref()
+                .and_then(|payload| parse_clock_out_with_break_payload(payload).ok())
+                .map(|(break_capture, _, _, _)| {
+                    vec![
+                        ("clock_out", break_capture.timestamp),
+                        ("clock_in", timestamp),
+                    ]
+                })
+                .unwrap_or_default(),
+            ActionType::SwitchProject => vec![("clock_out", timestamp), ("clock_in", timestamp)],
+            // Corrections only take effect once approved
+            ActionType::SubmitCorrection | ActionType::WaterAction => Vec::new(),
+        }
+    }
+}
+
+/// A queued action the webapp refused too often to be retried automatically; the
+/// user retries or discards it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsentAction {
+    pub id: i64,
+    /// e.g. `clock_in`, see `ActionType::as_str`
+    pub action: &'static str,
+    pub queued_at: Option<DateTime<Utc>>,
+    pub retry_count: i32,
+    /// Punches hold back the punches queued after them
+    pub is_punch: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockInPayload {
+    pub work_location_type: String,
+    #[serde(flatten)]
+    pub details: PunchDetails,
+    #[serde(default)]
+    pub capture: Option<PunchCapture>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detection: Option<LocationDetection>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct ClockOutWithBreakPayload {
-    break_start_time: String,
-    work_location_type: String,
+pub struct ClockOutPayload {
+    #[serde(flatten)]
+    pub details: PunchDetails,
+    #[serde(default)]
+    pub capture: Option<PunchCapture>,
 }
 
-fn parse_clock_out_with_break_payload(payload: &str) -> Result<(DateTime<Utc>, WorkLocationType)> {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockOutWithBreakPayload {
+    pub break_start_time: String,
+    pub work_location_type: String,
+    #[serde(flatten)]
+    pub details: PunchDetails,
+    #[serde(default)]
+    pub break_capture: Option<PunchCapture>,
+    #[serde(default)]
+    pub resume_capture: Option<PunchCapture>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchProjectPayload {
+    pub work_location_type: String,
+    pub previous: PunchDetails,
+    pub next: PunchDetails,
+    pub clock_out_capture: PunchCapture,
+    pub clock_in_capture: PunchCapture,
+}
+
+/// A value stored in the local cache together with when it was written
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cached<T> {
+    pub value: T,
+    pub updated_at: i64,
+}
+
+fn parse_clock_in_payload(
+    payload: Option<&str>,
+) -> (
+    WorkLocationType,
+    PunchDetails,
+    Option<PunchCapture>,
+    Option<LocationDetection>,
+) {
+    let Some(payload) = payload else {
+        return (
+            WorkLocationType::Office,
+            PunchDetails::default(),
+            None,
+            None,
+        );
+    };
+
+    if let Ok(parsed_payload) = serde_json::from_str::<ClockInPayload>(payload) {
+        let work_location_type = WorkLocationType::from_str(&parsed_payload.work_location_type)
+            .unwrap_or(WorkLocationType::Office);
+        return (
+            work_location_type,
+            parsed_payload.details,
+            parsed_payload.capture,
+            parsed_payload.detection,
+        );
+    }
+
+    // Legacy rows store the bare work location type
+    let work_location_type =
+        WorkLocationType::from_str(payload).unwrap_or(WorkLocationType::Office);
+    (work_location_type, PunchDetails::default(), None, None)
+}
+
+fn parse_clock_out_payload(payload: Option<&str>) -> (PunchDetails, Option<PunchCapture>) {
+    payload
+        .and_then(|payload| serde_json::from_str::<ClockOutPayload>(payload).ok())
+        .map(|parsed_payload| (parsed_payload.details, parsed_payload.capture))
+        .unwrap_or_default()
+}
+
+/// Returns the break capture, work location, details and (if stored) the resume capture
+fn parse_clock_out_with_break_payload(
+    payload: &str,
+) -> Result<(
+    PunchCapture,
+    WorkLocationType,
+    PunchDetails,
+    Option<PunchCapture>,
+)> {
     if let Ok(parsed_payload) = serde_json::from_str::<ClockOutWithBreakPayload>(payload) {
-        let break_time = DateTime::parse_from_rfc3339(&parsed_payload.break_start_time)
-            .map(|time| time.with_timezone(&Utc))?;
         let work_location_type = WorkLocationType::from_str(&parsed_payload.work_location_type)
             .ok_or_else(|| anyhow::anyhow!("Invalid work location type payload"))?;
+        let break_capture = match parsed_payload.break_capture {
+            Some(capture) => capture,
+            None => PunchCapture::without_zone(
+                DateTime::parse_from_rfc3339(&parsed_payload.break_start_time)
+                    .map(|time| time.with_timezone(&Utc))?,
+            ),
+        };
 
-        return Ok((break_time, work_location_type));
+        return Ok((
+            break_capture,
+            work_location_type,
+            parsed_payload.details,
+            parsed_payload.resume_capture,
+        ));
     }
 
     let break_time = DateTime::parse_from_rfc3339(payload).map(|time| time.with_timezone(&Utc))?;
-    Ok((break_time, WorkLocationType::Office))
+    Ok((
+        PunchCapture::without_zone(break_time),
+        WorkLocationType::Office,
+        PunchDetails::default(),
+        None,
+    ))
 }
 
-fn queued_timestamp_to_rfc3339(timestamp: i64) -> Result<String> {
+fn parse_switch_project_payload(payload: &str) -> Result<(WorkLocationType, SwitchProjectPayload)> {
+    let parsed_payload = serde_json::from_str::<SwitchProjectPayload>(payload)?;
+    let work_location_type = WorkLocationType::from_str(&parsed_payload.work_location_type)
+        .ok_or_else(|| anyhow::anyhow!("Invalid work location type payload"))?;
+
+    Ok((work_location_type, parsed_payload))
+}
+
+fn queued_timestamp(timestamp: i64) -> Result<DateTime<Utc>> {
     Utc.timestamp_opt(timestamp, 0)
         .single()
-        .map(|time| time.to_rfc3339())
         .ok_or_else(|| anyhow::anyhow!("Invalid queued timestamp: {}", timestamp))
 }
 
 pub struct OfflineQueue {
     conn: Connection,
+    device_key: Arc<DeviceKey>,
 }
 
 impl OfflineQueue {
-    pub fn new(app_data_dir: &Path) -> Result<Self> {
+    /// Opens the queue in the app data directory; `device_key` signs its chain
+    pub fn new(app_data_dir: &Path, device_key: Arc<DeviceKey>) -> Result<Self> {
         let db_path = app_data_dir.join("offline_queue.db");
         let conn = Connection::open(&db_path)?;
 
@@ -82,28 +291,80 @@ impl OfflineQueue {
             [],
         )?;
 
-        Ok(Self { conn })
+        let has_chain_column: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('queue') WHERE name = 'chain_sequence'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_chain_column {
+            conn.execute("ALTER TABLE queue ADD COLUMN chain_sequence INTEGER", [])?;
+        }
+
+        // Links stay until a replay carried them to the webapp; the head is kept for good
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS chain (
+                sequence INTEGER PRIMARY KEY AUTOINCREMENT,
+                action TEXT NOT NULL,
+                timestamp INTEGER NOT NULL,
+                payload TEXT,
+                created_at INTEGER NOT NULL,
+                previous_hash TEXT NOT NULL,
+                hash TEXT NOT NULL,
+                signature TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS chain_head (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                hash TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cache (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
+        Ok(Self { conn, device_key })
     }
 
     pub fn enqueue(&mut self, action_type: ActionType, timestamp: i64, payload: Option<String>) -> Result<i64> {
         let action_str = serde_json::to_string(&action_type)?;
         let now = Utc::now().timestamp();
 
-        self.conn.execute(
-            "INSERT INTO queue (action_type, timestamp, payload, created_at) VALUES (?, ?, ?, ?)",
-            params![action_str, timestamp, payload, now],
+        let tx = self.conn.transaction()?;
+        let sequence = append_link(
+            &tx,
+            &self.device_key,
+            action_type.as_str(),
+            timestamp,
+            payload.as_deref(),
+            now,
         )?;
+        tx.execute(
+            "INSERT INTO queue (action_type, timestamp, payload, created_at, chain_sequence)
+             VALUES (?, ?, ?, ?, ?)",
+            params![action_str, timestamp, payload, now, sequence],
+        )?;
+        let id = tx.last_insert_rowid();
+        tx.commit()?;
 
-        let id = self.conn.last_insert_rowid();
         log::info!("Enqueued action: {:?} (id: {})", action_type, id);
         Ok(id)
     }
 
     pub fn get_pending(&self) -> Result<Vec<QueuedAction>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, action_type, timestamp, payload, retry_count, created_at
+            "SELECT id, action_type, timestamp, payload, retry_count, created_at, chain_sequence
              FROM queue
-             ORDER BY created_at ASC",
+             ORDER BY timestamp ASC, id ASC",
         )?;
 
         let mut rows = stmt.query([])?;
@@ -127,18 +388,114 @@ impl OfflineQueue {
                 payload: row.get(3)?,
                 retry_count: row.get(4)?,
                 created_at: row.get(5)?,
+                chain_sequence: row.get(6)?,
             });
         }
 
         Ok(actions)
     }
 
+    /// Punches still to be sent, oldest first, as `(entry type, timestamp)`. Those
+    /// that failed too often count until the user discards them.
+    pub fn pending_punches(&self) -> Result<Vec<(&'static str, DateTime<Utc>)>> {
+        let mut punches: Vec<_> = self
+            .get_pending()?
+            .iter()
+            .flat_map(QueuedAction::punches)
+            .collect();
+        // Stable, so a switch's clock-out stays ahead of its clock-in
+        punches.sort_by_key(|(_, at)| *at);
+        Ok(punches)
+    }
+
+    /// The last status the webapp reported with the queued punches applied; `None`
+    /// when the webapp never answered and nothing is queued
+    pub fn local_clock_status(&self) -> Result<Option<ClockStatus>> {
+        let cached = self
+            .get_cached::<ClockStatus>(CLOCK_STATUS_CACHE_KEY)?
+            .map(|cached| cached.value);
+        let punches = self.pending_punches()?;
+        if cached.is_none() && punches.is_empty() {
+            return Ok(None);
+        }
+
+        let status = cached.unwrap_or_else(ClockStatus::unknown);
+        Ok(Some(status.with_punches(&punches)))
+    }
+
     pub fn mark_completed(&mut self, id: i64) -> Result<()> {
         self.conn.execute("DELETE FROM queue WHERE id = ?", params![id])?;
         log::info!("Removed completed action from queue (id: {})", id);
         Ok(())
     }
 
+    /// Drops an action before it is sent; returns whether it was still queued.
+    /// The dropped row's link stays and a `remove` link naming it follows, so the
+    /// webapp sees the action was recorded and then taken back.
+    pub fn remove(&mut self, id: i64) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+        let removed: Option<(i64, Option<i64>)> = tx
+            .query_row(
+                "SELECT timestamp, chain_sequence FROM queue WHERE id = ?",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((timestamp, sequence)) = removed else {
+            return Ok(false);
+        };
+
+        tx.execute("DELETE FROM queue WHERE id = ?", params![id])?;
+        if let Some(sequence) = sequence {
+            let payload = serde_json::json!({ "sequence": sequence }).to_string();
+            append_link(
+                &tx,
+                &self.device_key,
+                REMOVE_LINK_ACTION,
+                timestamp,
+                Some(&payload),
+                Utc::now().timestamp(),
+            )?;
+        }
+        tx.commit()?;
+
+        log::info!("Removed action from queue before sending (id: {})", id);
+        Ok(true)
+    }
+
+    /// Rewrites a queued action in place, keeping its position in the queue. The
+    /// rewrite gets a link of its own, so the chain still matches the row.
+    pub fn update_action(
+        &mut self,
+        id: i64,
+        action_type: ActionType,
+        payload: Option<String>,
+    ) -> Result<()> {
+        let action_str = serde_json::to_string(&action_type)?;
+
+        let tx = self.conn.transaction()?;
+        let timestamp: i64 = tx.query_row(
+            "SELECT timestamp FROM queue WHERE id = ?",
+            params![id],
+            |row| row.get(0),
+        )?;
+        let sequence = append_link(
+            &tx,
+            &self.device_key,
+            action_type.as_str(),
+            timestamp,
+            payload.as_deref(),
+            Utc::now().timestamp(),
+        )?;
+        tx.execute(
+            "UPDATE queue SET action_type = ?, payload = ?, chain_sequence = ? WHERE id = ?",
+            params![action_str, payload, sequence, id],
+        )?;
+        tx.commit()?;
+        log::info!("Updated queued action {} to {:?}", id, action_type);
+        Ok(())
+    }
+
     pub fn increment_retry(&mut self, id: i64) -> Result<()> {
         self.conn.execute(
             "UPDATE queue SET retry_count = retry_count + 1 WHERE id = ?",
@@ -147,130 +504,479 @@ impl OfflineQueue {
         Ok(())
     }
 
+    /// Actions no longer retried automatically, oldest first
+    pub fn unsent(&self) -> Result<Vec<UnsentAction>> {
+        Ok(self
+            .get_pending()?
+            .into_iter()
+            .filter(|action| action.retry_count >= MAX_RETRIES)
+            .map(|action| UnsentAction {
+                id: action.id,
+                action: action.action_type.as_str(),
+                queued_at: queued_timestamp(action.timestamp).ok(),
+                retry_count: action.retry_count,
+                is_punch: action.action_type.is_punch(),
+            })
+            .collect())
+    }
+
+    /// Lets the queue processor try an action again; returns whether it was still queued
+    pub fn reset_retries(&mut self, id: i64) -> Result<bool> {
+        let updated = self
+            .conn
+            .execute("UPDATE queue SET retry_count = 0 WHERE id = ?", params![id])?;
+        Ok(updated > 0)
+    }
+
     pub fn count(&self) -> Result<i64> {
         let count: i64 = self
             .conn
             .query_row("SELECT COUNT(*) FROM queue", [], |row| row.get(0))?;
         Ok(count)
     }
-}
 
-/// Starts the background queue processor
-pub async fn start_queue_processor(app_handle: AppHandle) {
-    log::info!("Starting offline queue processor");
+    /// The links not yet accepted by the webapp, up to and including `through`
+    pub fn replay_chain(&self, through: Option<i64>) -> Result<ReplayChain> {
+        let mut links = Vec::new();
 
-    let clock_service = ClockService::new();
+        if let Some(through) = through {
+            let mut stmt = self.conn.prepare(
+                "SELECT sequence, action, timestamp, payload, created_at, previous_hash, hash, signature
+                 FROM chain
+                 WHERE sequence <= ?
+                 ORDER BY sequence ASC",
+            )?;
+            let mut rows = stmt.query(params![through])?;
 
-    loop {
-        tokio::time::sleep(Duration::from_secs(30)).await;
+            while let Some(row) = rows.next()? {
+                links.push(ChainLink {
+                    sequence: row.get(0)?,
+                    action: row.get(1)?,
+                    timestamp: row.get(2)?,
+                    payload: row.get(3)?,
+                    created_at: row.get(4)?,
+                    previous_hash: row.get(5)?,
+                    hash: row.get(6)?,
+                    signature: row.get(7)?,
+                });
+            }
+        }
 
-        let state = app_handle.state::<Arc<AppState>>();
-        let token = match state.get_session_token() {
-            Some(t) => t,
-            None => continue, // Not logged in
-        };
+        Ok(ReplayChain {
+            public_key: self.device_key.public_key(),
+            links,
+        })
+    }
 
-        let webapp_url = state.get_webapp_url();
-        if webapp_url.is_empty() {
-            continue;
-        }
+    /// Drops the links the webapp accepted with a replay. Links from the oldest row
+    /// still queued on are kept, e.g. those of a failed correction queued before the
+    /// punch that was sent, so they go out again with the next punch.
+    pub fn acknowledge_chain(&mut self, through: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM chain
+             WHERE sequence <= ?1
+               AND sequence < COALESCE((SELECT MIN(chain_sequence) FROM queue), ?1 + 1)",
+            params![through],
+        )?;
+        Ok(())
+    }
 
-        // Get pending actions
-        let pending = {
-            let queue = state.offline_queue.lock();
-            match queue.get_pending() {
-                Ok(p) => p,
-                Err(e) => {
-                    log::error!("Failed to get pending queue: {}", e);
-                    continue;
-                }
-            }
+    /// Stores a server response so it is still available offline
+    pub fn put_cached<T: Serialize>(&mut self, key: &str, value: &T) -> Result<()> {
+        let value = serde_json::to_string(value)?;
+
+        self.conn.execute(
+            "INSERT INTO cache (key, value, updated_at) VALUES (?, ?, ?)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+            params![key, value, Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_cached<T: DeserializeOwned>(&self, key: &str) -> Result<Option<Cached<T>>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT value, updated_at FROM cache WHERE key = ?")?;
+        let mut rows = stmt.query(params![key])?;
+
+        let Some(row) = rows.next()? else {
+            return Ok(None);
         };
 
-        if pending.is_empty() {
-            continue;
+        let value: String = row.get(0)?;
+        match serde_json::from_str(&value) {
+            Ok(value) => Ok(Some(Cached {
+                value,
+                updated_at: row.get(1)?,
+            })),
+            Err(e) => {
+                log::warn!("Ignoring malformed cache entry {}: {}", key, e);
+                Ok(None)
+            }
         }
+    }
+
+    pub fn remove_cached(&mut self, key: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM cache WHERE key = ?", params![key])?;
+        Ok(())
+    }
+}
 
-        log::info!("Processing {} pending offline actions", pending.len());
+/// Signs and stores the next link after the chain head, returning its sequence
+fn append_link(
+    conn: &Connection,
+    device_key: &DeviceKey,
+    action: &str,
+    timestamp: i64,
+    payload: Option<&str>,
+    created_at: i64,
+) -> Result<i64> {
+    let head: Option<String> = conn
+        .query_row("SELECT hash FROM chain_head WHERE id = 1", [], |row| {
+            row.get(0)
+        })
+        .optional()?;
+    let link = ChainLink::new(
+        head.as_deref().unwrap_or(GENESIS_HASH),
+        action,
+        timestamp,
+        payload,
+        created_at,
+        device_key,
+    );
 
-        for action in pending {
-            // Skip if too many retries
-            if action.retry_count >= 5 {
+    conn.execute(
+        "INSERT INTO chain (action, timestamp, payload, created_at, previous_hash, hash, signature)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        params![
+            link.action,
+            link.timestamp,
+            link.payload,
+            link.created_at,
+            link.previous_hash,
+            link.hash,
+            link.signature
+        ],
+    )?;
+    let sequence = conn.last_insert_rowid();
+    conn.execute(
+        "INSERT INTO chain_head (id, hash) VALUES (1, ?)
+         ON CONFLICT(id) DO UPDATE SET hash = excluded.hash",
+        params![link.hash],
+    )?;
+
+    Ok(sequence)
+}
+
+/// Outcome of one pass over the offline queue
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueRun {
+    pub pending: usize,
+    pub sent: usize,
+    pub failed: usize,
+    /// A punch that failed too often; the punches after it wait until it is sent
+    pub blocked_by: Option<i64>,
+}
+
+/// Replays the queued actions in order, removing the ones the webapp accepted.
+/// A failed punch ends the pass; the punches after it wait for the next one. One
+/// that failed too often stays at the head of the queue and is reported as blocking.
+pub async fn process_queue(
+    queue: &Mutex<OfflineQueue>,
+    journal: &Mutex<Journal>,
+    clock_service: &ClockService,
+    wellness_service: &WellnessService,
+) -> Result<QueueRun> {
+    let pending = queue.lock().get_pending()?;
+    let mut run = QueueRun {
+        pending: pending.len(),
+        ..QueueRun::default()
+    };
+    if pending.is_empty() {
+        return Ok(run);
+    }
+
+    log::info!("Processing {} pending offline actions", pending.len());
+
+    for action in pending {
+        if action.retry_count >= MAX_RETRIES {
+            // Sending the punches after it would record them out of order
+            if action.action_type.is_punch() {
                 log::warn!(
-                    "Skipping action {} after {} retries",
+                    "Holding back the queue behind punch {} after {} retries",
                     action.id,
                     action.retry_count
                 );
+                run.blocked_by = Some(action.id);
+                break;
+            }
+            log::warn!(
+                "Skipping action {} after {} retries",
+                action.id,
+                action.retry_count
+            );
+            continue;
+        }
+
+        // Rows queued before captures were stored replay at their queued time
+        let queued_at = match queued_timestamp(action.timestamp) {
+            Ok(queued_at) => queued_at,
+            Err(e) => {
+                log::error!("Failed to process queued action {}: {}", action.id, e);
+                if action.action_type.is_punch() {
+                    break;
+                }
                 continue;
             }
+        };
 
-            let result = match action.action_type {
-                ActionType::ClockIn => {
-                    let work_location_type = action
-                        .payload
-                        .as_deref()
-                        .and_then(WorkLocationType::from_str)
-                        .unwrap_or(WorkLocationType::Office);
-                    match queued_timestamp_to_rfc3339(action.timestamp) {
-                        Ok(timestamp) => {
-                            clock_service
-                                .clock_in(
-                                    &webapp_url,
-                                    &token,
+        let chain = match queue.lock().replay_chain(action.chain_sequence) {
+            Ok(chain) => chain,
+            Err(e) => {
+                log::error!("Failed to read the chain for action {}: {}", action.id, e);
+                if action.action_type.is_punch() {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        let result = match action.action_type {
+            ActionType::ClockIn => {
+                let (work_location_type, details, capture, detection) =
+                    parse_clock_in_payload(action.payload.as_deref());
+                let capture = capture.unwrap_or_else(|| PunchCapture::without_zone(queued_at));
+                let result = clock_service
+                    .clock_in(
+                        work_location_type,
+                        &details,
+                        &capture,
+                        detection.as_ref(),
+                        Some(&chain),
+                    )
+                    .await;
+                clock_service
+                    .settle(
+                        result,
+                        |status| status.started_at(capture.timestamp),
+                        |status| !status.is_clocked_in,
+                    )
+                    .await
+            }
+            ActionType::ClockOut => {
+                let (details, capture) = parse_clock_out_payload(action.payload.as_deref());
+                let capture = capture.unwrap_or_else(|| PunchCapture::without_zone(queued_at));
+                let result = clock_service
+                    .clock_out(&details, &capture, Some(&chain))
+                    .await;
+                clock_service
+                    .settle(
+                        result,
+                        |status| !status.is_clocked_in,
+                        |status| status.started_before(capture.timestamp),
+                    )
+                    .await
+            }
+            ActionType::ClockOutWithBreak => {
+                if let Some(payload) = &action.payload {
+                    match parse_clock_out_with_break_payload(payload) {
+                        Ok((break_capture, work_location_type, details, resume_capture)) => {
+                            let resume_capture = resume_capture
+                                .unwrap_or_else(|| PunchCapture::without_zone(queued_at));
+                            let result = clock_service
+                                .clock_out_with_break(
+                                    &break_capture,
                                     work_location_type,
-                                    Some(&timestamp),
+                                    &details,
+                                    &resume_capture,
+                                    Some(&chain),
+                                )
+                                .await;
+                            clock_service
+                                .settle(
+                                    result,
+                                    |status| status.started_at(resume_capture.timestamp),
+                                    |status| status.started_before(break_capture.timestamp),
                                 )
                                 .await
-                                .map(|_| ())
                         }
                         Err(e) => Err(e),
                     }
+                } else {
+                    Err(anyhow::anyhow!("Missing break time payload"))
                 }
-                ActionType::ClockOut => {
-                    clock_service.clock_out(&webapp_url, &token).await.map(|_| ())
-                }
-                ActionType::ClockOutWithBreak => {
-                    if let Some(payload) = &action.payload {
-                        match parse_clock_out_with_break_payload(payload) {
-                            Ok((break_time, work_location_type)) => {
-                                match queued_timestamp_to_rfc3339(action.timestamp) {
-                                    Ok(resume_timestamp) => {
-                                        clock_service
-                                            .clock_out_with_break(
-                                                &webapp_url,
-                                                &token,
-                                                break_time,
-                                                work_location_type,
-                                                Some(&resume_timestamp),
-                                            )
-                                            .await
-                                    }
-                                    Err(e) => Err(e),
+            }
+            ActionType::SwitchProject => {
+                match action
+                    .payload
+                    .as_deref()
+                    .ok_or_else(|| anyhow::anyhow!("Missing switch project payload"))
+                    .and_then(parse_switch_project_payload)
+                {
+                    Ok((work_location_type, payload)) => {
+                        let result = clock_service
+                            .switch_project(
+                                work_location_type,
+                                &payload.previous,
+                                &payload.next,
+                                &payload.clock_out_capture,
+                                &payload.clock_in_capture,
+                                Some(&chain),
+                            )
+                            .await;
+                        let switched_at = payload.clock_out_capture.timestamp;
+                        match clock_service.settle_switch(result, switched_at).await {
+                            Ok(()) => Ok(()),
+                            Err(SwitchProjectError::ClockOut(e)) => Err(e),
+                            Err(SwitchProjectError::ClockIn(e)) => {
+                                // The previous period is already closed, so only the
+                                // clock-in may be retried
+                                let clock_in_payload = serde_json::to_string(&ClockInPayload {
+                                    work_location_type: payload.work_location_type,
+                                    details: payload.next,
+                                    capture: Some(payload.clock_in_capture),
+                                    detection: None,
+                                })
+                                .ok();
+                                let mut queue = queue.lock();
+                                if let Err(update_error) = queue.update_action(
+                                    action.id,
+                                    ActionType::ClockIn,
+                                    clock_in_payload,
+                                ) {
+                                    log::error!(
+                                        "Failed to update queued switch {}: {}",
+                                        action.id,
+                                        update_error
+                                    );
                                 }
+                                Err(e)
                             }
-                            Err(e) => Err(e),
                         }
-                    } else {
-                        Err(anyhow::anyhow!("Missing break time payload"))
                     }
+                    Err(e) => Err(e),
                 }
-            };
+            }
+            ActionType::SubmitCorrection => match action
+                .payload
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("Missing correction payload"))
+                .and_then(|payload| Ok(serde_json::from_str::<CorrectionRequest>(payload)?))
+            {
+                Ok(request) => clock_service.submit_correction(&request).await.map(|_| ()),
+                Err(e) => Err(e),
+            },
+            ActionType::WaterAction => match action
+                .payload
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("Missing water action payload"))
+                .and_then(|payload| Ok(serde_json::from_str::<WaterAction>(payload)?))
+            {
+                Ok(water_action) => wellness_service.act(&water_action).await.map(|_| ()),
+                Err(e) => Err(e),
+            },
+        };
 
-            match result {
-                Ok(_) => {
-                    let mut queue = state.offline_queue.lock();
-                    let _ = queue.mark_completed(action.id);
+        let event = JournalEvent::sent(
+            JournalKind::Sync,
+            EventSource::System,
+            action.action_type.as_str(),
+            result.as_ref().err(),
+        )
+        .details(serde_json::json!({
+            "queuedId": action.id,
+            "queuedAt": queued_at,
+            "retryCount": action.retry_count,
+        }));
+        if let Err(e) = journal.lock().append(event, Utc::now()) {
+            log::warn!("Failed to write journal entry: {}", e);
+        }
+
+        match result {
+            Ok(_) => {
+                run.sent += 1;
+                let mut queue = queue.lock();
+                let _ = queue.mark_completed(action.id);
+                if let (true, Some(sequence)) = (action.carries_chain(), action.chain_sequence) {
+                    let _ = queue.acknowledge_chain(sequence);
                 }
-                Err(e) => {
-                    log::error!("Failed to process queued action {}: {}", action.id, e);
-                    let mut queue = state.offline_queue.lock();
-                    let _ = queue.increment_retry(action.id);
+            }
+            Err(e) => {
+                run.failed += 1;
+                log::error!("Failed to process queued action {}: {}", action.id, e);
+                // Being offline or losing the answer says nothing about the action
+                if !is_network_error(&e) && !is_unknown_outcome(&e) {
+                    let _ = queue.lock().increment_retry(action.id);
+                }
+
+                // Sending later punches first would record them out of order
+                if action.action_type.is_punch() {
+                    break;
                 }
             }
         }
+    }
+
+    Ok(run)
+}
+
+/// Starts the background queue processor
+pub async fn start_queue_processor(app_handle: AppHandle) {
+    log::info!("Starting offline queue processor");
+
+    let state = app_handle.state::<Arc<AppState>>();
+    let clock_service = ClockService::new(state.api.clone());
+    let wellness_service = WellnessService::new(state.api.clone());
+    let mut reported_block = None;
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(30)) => {}
+            _ = state.queue_wakeup.notified() => {}
+        }
+
+        if state.get_session_token().is_none() {
+            continue; // Not logged in
+        }
+
+        if state.get_webapp_url().is_empty() {
+            continue;
+        }
+
+        let run = match process_queue(
+            &state.offline_queue,
+            &state.journal,
+            &clock_service,
+            &wellness_service,
+        )
+        .await
+        {
+            Ok(run) => run,
+            Err(e) => {
+                log::error!("Failed to get pending queue: {}", e);
+                continue;
+            }
+        };
+
+        // Told once per punch, not on every pass
+        if run.blocked_by.is_some() && run.blocked_by != reported_block {
+            notify::send(
+                &app_handle,
+                "A punch could not be sent",
+                "The webapp refused a queued punch several times, so the punches after it \
+                 are held back. Open z8 to retry or discard it.",
+            );
+        }
+        reported_block = run.blocked_by;
+        if run.pending == 0 {
+            continue;
+        }
 
         // Update clock status after processing queue
-        if let Ok(status) = clock_service.get_status(&webapp_url, &token).await {
+        if let Ok(status) = clock_service.get_status().await {
+            let status = state.remember_clock_status(&status);
             state.set_clocked_in(status.is_clocked_in);
             let _ = tray::update_tray_icon(&app_handle, status.is_clocked_in);
         }
@@ -279,22 +985,84 @@ pub async fn start_queue_processor(app_handle: AppHandle) {
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_clock_out_with_break_payload, queued_timestamp_to_rfc3339, ActionType, OfflineQueue};
-    use crate::clock::WorkLocationType;
+    use super::{
+        parse_clock_in_payload, parse_clock_out_with_break_payload, parse_switch_project_payload,
+        process_queue, queued_timestamp, ActionType, ClockInPayload, OfflineQueue,
+        SwitchProjectPayload, MAX_RETRIES,
+    };
+    use crate::api::testing::fake_client;
+    use crate::chain::{hash_link, GENESIS_HASH};
+    use crate::clock::{
+        ClockService, ClockStatus, PunchCapture, PunchDetails, WorkLocationType,
+        CLOCK_STATUS_CACHE_KEY,
+    };
+    use crate::device::DeviceKey;
+    use crate::journal::Journal;
+    use crate::wellness::WellnessService;
+    use parking_lot::Mutex;
     use rusqlite::params;
     use std::fs;
+    use std::path::Path;
+    use std::sync::Arc;
+
+    fn open_queue(dir: &Path) -> OfflineQueue {
+        OfflineQueue::new(dir, Arc::new(DeviceKey::load_or_create(dir).unwrap())).unwrap()
+    }
 
     #[test]
     fn parses_clock_out_with_break_payloads_with_legacy_default() {
         let legacy = "2026-05-09T10:15:30Z";
-        let (break_time, work_location_type) = parse_clock_out_with_break_payload(legacy).unwrap();
-        assert_eq!(break_time.to_rfc3339(), "2026-05-09T10:15:30+00:00");
+        let (break_capture, work_location_type, _, resume_capture) =
+            parse_clock_out_with_break_payload(legacy).unwrap();
+        assert_eq!(
+            break_capture.timestamp.to_rfc3339(),
+            "2026-05-09T10:15:30+00:00"
+        );
+        assert!(break_capture.timezone.is_none());
+        assert!(resume_capture.is_none());
         assert_eq!(work_location_type.as_str(), WorkLocationType::Office.as_str());
 
-        let current = r#"{"breakStartTime":"2026-05-09T10:15:30Z","workLocationType":"remote"}"#;
-        let (break_time, work_location_type) = parse_clock_out_with_break_payload(current).unwrap();
-        assert_eq!(break_time.to_rfc3339(), "2026-05-09T10:15:30+00:00");
+        let current = r#"{"breakStartTime":"2026-05-09T10:15:30Z","workLocationType":"remote","projectId":"project-1"}"#;
+        let (break_capture, work_location_type, details, _) =
+            parse_clock_out_with_break_payload(current).unwrap();
+        assert_eq!(
+            break_capture.timestamp.to_rfc3339(),
+            "2026-05-09T10:15:30+00:00"
+        );
         assert_eq!(work_location_type.as_str(), WorkLocationType::Remote.as_str());
+        assert_eq!(details.project_id.as_deref(), Some("project-1"));
+    }
+
+    #[test]
+    fn parses_clock_in_payloads_with_legacy_location_only_rows() {
+        let (work_location_type, details, capture, _) = parse_clock_in_payload(Some("home"));
+        assert_eq!(work_location_type.as_str(), "home");
+        assert!(details.is_empty());
+        assert!(capture.is_none());
+
+        let capture = PunchCapture {
+            action_id: "f47ac10b-58cc-4372-a567-0e02b2c3d479".to_string(),
+            timestamp: queued_timestamp(1_777_593_600).unwrap(),
+            timezone: Some("Europe/Berlin".to_string()),
+            utc_offset_minutes: Some(120),
+        };
+        let payload = serde_json::to_string(&ClockInPayload {
+            work_location_type: "remote".to_string(),
+            details: PunchDetails::new(
+                Some("project-1".to_string()),
+                None,
+                Some("Sprint review".to_string()),
+            ),
+            capture: Some(capture.clone()),
+            detection: None,
+        })
+        .unwrap();
+        let (work_location_type, details, parsed_capture, _) =
+            parse_clock_in_payload(Some(&payload));
+        assert_eq!(work_location_type.as_str(), "remote");
+        assert_eq!(details.project_id.as_deref(), Some("project-1"));
+        assert_eq!(details.notes.as_deref(), Some("Sprint review"));
+        assert_eq!(parsed_capture, Some(capture));
     }
 
     #[test]
@@ -304,7 +1072,7 @@ mod tests {
             chrono::Utc::now().timestamp_nanos_opt().unwrap()
         ));
         fs::create_dir_all(&dir).unwrap();
-        let queue = OfflineQueue::new(&dir).unwrap();
+        let queue = open_queue(&dir);
 
         queue
             .conn
@@ -331,9 +1099,327 @@ mod tests {
     }
 
     #[test]
-    fn converts_queued_timestamp_seconds_to_rfc3339() {
+    fn converts_switch_project_rows_to_clock_in_after_partial_replay() {
+        let dir = std::env::temp_dir().join(format!(
+            "z8-offline-switch-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let mut queue = open_queue(&dir);
+
+        let switch_payload = serde_json::to_string(&SwitchProjectPayload {
+            work_location_type: "remote".to_string(),
+            previous: PunchDetails::new(Some("project-a".to_string()), None, None),
+            next: PunchDetails::new(Some("project-b".to_string()), None, None),
+            clock_out_capture: PunchCapture::without_zone(queued_timestamp(1_777_593_600).unwrap()),
+            clock_in_capture: PunchCapture::without_zone(queued_timestamp(1_777_593_600).unwrap()),
+        })
+        .unwrap();
+        let (work_location_type, payload) = parse_switch_project_payload(&switch_payload).unwrap();
+        assert_eq!(work_location_type.as_str(), "remote");
+        assert_eq!(payload.previous.project_id.as_deref(), Some("project-a"));
+
+        let id = queue
+            .enqueue(
+                ActionType::SwitchProject,
+                1_777_593_600,
+                Some(switch_payload),
+            )
+            .unwrap();
+        let clock_in_payload = serde_json::to_string(&ClockInPayload {
+            work_location_type: "remote".to_string(),
+            details: payload.next,
+            capture: Some(payload.clock_in_capture.clone()),
+            detection: None,
+        })
+        .unwrap();
+        queue
+            .update_action(id, ActionType::ClockIn, Some(clock_in_payload))
+            .unwrap();
+
+        let actions = queue.get_pending().unwrap();
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(actions[0].action_type, ActionType::ClockIn));
+        assert_eq!(actions[0].timestamp, 1_777_593_600);
+        let (_, details, capture, _) = parse_clock_in_payload(actions[0].payload.as_deref());
+        assert_eq!(details.project_id.as_deref(), Some("project-b"));
+        assert_eq!(capture, Some(payload.clock_in_capture));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn chains_queued_actions_and_sends_unacknowledged_links() {
+        let dir = std::env::temp_dir().join(format!(
+            "z8-offline-chain-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let mut queue = open_queue(&dir);
+
+        let clock_in = queue
+            .enqueue(ActionType::ClockIn, 1_777_593_600, Some("home".to_string()))
+            .unwrap();
+        queue
+            .enqueue(ActionType::ClockOut, 1_777_600_800, None)
+            .unwrap();
+        queue.remove(clock_in).unwrap();
+
+        // The dropped clock-in stays in the chain, so the webapp sees the gap
+        let actions = queue.get_pending().unwrap();
+        let chain = queue.replay_chain(actions[0].chain_sequence).unwrap();
+        assert_eq!(chain.links.len(), 2);
+        assert_eq!(chain.links[0].previous_hash, GENESIS_HASH);
+        assert_eq!(chain.links[1].previous_hash, chain.links[0].hash);
+        assert_eq!(
+            chain.links[1].hash,
+            hash_link(
+                &chain.links[0].hash,
+                "clock_out",
+                1_777_600_800,
+                None,
+                chain.links[1].created_at
+            )
+        );
+
+        // Accepted links are not sent again, but the next link still follows them.
+        // The removal was chained after the clock-out and goes with the next punch.
+        queue.mark_completed(actions[0].id).unwrap();
+        queue.acknowledge_chain(chain.links[1].sequence).unwrap();
+        queue
+            .enqueue(ActionType::ClockIn, 1_777_604_400, None)
+            .unwrap();
+        let actions = queue.get_pending().unwrap();
+        let next = queue.replay_chain(actions[0].chain_sequence).unwrap();
+        assert_eq!(next.links.len(), 2);
+        assert_eq!(next.links[0].action, "remove");
+        assert_eq!(
+            next.links[0].payload.as_deref(),
+            Some(format!(r#"{{"sequence":{}}}"#, chain.links[0].sequence).as_str())
+        );
+        assert_eq!(next.links[0].previous_hash, chain.links[1].hash);
+        assert_eq!(next.links[1].previous_hash, next.links[0].hash);
+        assert_eq!(next.public_key, chain.public_key);
+        assert!(!queue.remove(actions[0].id + 1).unwrap());
+
+        // Rows queued before the chain replay without links
+        assert!(queue.replay_chain(None).unwrap().links.is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn stops_replaying_at_the_first_failed_punch() {
+        let dir = std::env::temp_dir().join(format!(
+            "z8-offline-order-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let queue = Mutex::new(open_queue(&dir));
+        let journal = Mutex::new(Journal::new(&dir).unwrap());
+        let (api, transport) = fake_client();
+        let clock_service = ClockService::new(api.clone());
+        let wellness_service = WellnessService::new(api);
+
+        {
+            let mut queue = queue.lock();
+            queue
+                .enqueue(ActionType::ClockIn, 1_777_593_600, None)
+                .unwrap();
+            queue
+                .enqueue(ActionType::ClockOut, 1_777_600_800, None)
+                .unwrap();
+        }
+
+        transport.fail_network();
+        let run = process_queue(&queue, &journal, &clock_service, &wellness_service)
+            .await
+            .unwrap();
+        assert_eq!((run.pending, run.sent, run.failed), (2, 0, 1));
+        assert_eq!(transport.requests().len(), 1);
+        let pending = queue.lock().get_pending().unwrap();
+        assert_eq!(pending.len(), 2);
+        // Being offline doesn't use up the punch's retries
+        assert_eq!(pending[0].retry_count, 0);
+
+        let entry = |entry_type: &str| {
+            serde_json::json!({
+                "entry": {
+                    "id": format!("entry-{}", entry_type),
+                    "employeeId": "employee-1",
+                    "type": entry_type,
+                    "timestamp": "2026-05-01T00:00:00Z",
+                }
+            })
+        };
+        transport.respond(201, entry("clock_in"));
+        transport.respond(201, entry("clock_out"));
+        let run = process_queue(&queue, &journal, &clock_service, &wellness_service)
+            .await
+            .unwrap();
+        assert_eq!((run.sent, run.failed), (2, 0));
+        let requests = transport.requests();
+        assert_eq!(requests[1].body.as_ref().unwrap()["type"], "clock_in");
+        assert_eq!(requests[2].body.as_ref().unwrap()["type"], "clock_out");
+        assert!(queue.lock().get_pending().unwrap().is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn holds_the_queue_behind_a_punch_that_failed_too_often() {
+        let dir = std::env::temp_dir().join(format!(
+            "z8-offline-blocked-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let queue = Mutex::new(open_queue(&dir));
+        let journal = Mutex::new(Journal::new(&dir).unwrap());
+        let (api, transport) = fake_client();
+        let clock_service = ClockService::new(api.clone());
+        let wellness_service = WellnessService::new(api);
+
+        // Queued out of order: the clock-in still goes first
+        let clock_in = {
+            let mut queue = queue.lock();
+            queue
+                .enqueue(ActionType::ClockOut, 1_777_600_800, None)
+                .unwrap();
+            let clock_in = queue
+                .enqueue(ActionType::ClockIn, 1_777_593_600, None)
+                .unwrap();
+            queue
+                .conn
+                .execute(
+                    "UPDATE queue SET retry_count = ? WHERE id = ?",
+                    params![MAX_RETRIES, clock_in],
+                )
+                .unwrap();
+            clock_in
+        };
+        let pending = queue.lock().get_pending().unwrap();
+        assert_eq!(pending[0].id, clock_in);
+
+        let run = process_queue(&queue, &journal, &clock_service, &wellness_service)
+            .await
+            .unwrap();
+        assert_eq!(run.blocked_by, Some(clock_in));
+        assert_eq!(run.sent, 0);
+        assert!(transport.requests().is_empty());
+        assert_eq!(queue.lock().get_pending().unwrap().len(), 2);
+
+        // It still counts and is shown to the user, who can send it again
+        assert_eq!(queue.lock().pending_punches().unwrap().len(), 2);
+        let unsent = queue.lock().unsent().unwrap();
+        assert_eq!(unsent.len(), 1);
+        assert_eq!((unsent[0].id, unsent[0].action), (clock_in, "clock_in"));
+        assert!(queue.lock().reset_retries(clock_in).unwrap());
+        assert!(queue.lock().unsent().unwrap().is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_the_links_of_rows_still_waiting() {
+        let dir = std::env::temp_dir().join(format!(
+            "z8-offline-acknowledge-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let mut queue = open_queue(&dir);
+
+        queue
+            .enqueue(ActionType::SubmitCorrection, 1_777_593_600, None)
+            .unwrap();
+        queue
+            .enqueue(ActionType::ClockIn, 1_777_597_200, None)
+            .unwrap();
+
+        // The clock-in was accepted while the correction before it failed
+        let actions = queue.get_pending().unwrap();
+        let sent = queue.replay_chain(actions[1].chain_sequence).unwrap();
+        queue.mark_completed(actions[1].id).unwrap();
+        queue.acknowledge_chain(sent.links[1].sequence).unwrap();
+
+        queue
+            .enqueue(ActionType::ClockOut, 1_777_600_800, None)
+            .unwrap();
+        let actions = queue.get_pending().unwrap();
+        let next = queue.replay_chain(actions[1].chain_sequence).unwrap();
+        let sequences: Vec<i64> = next.links.iter().map(|link| link.sequence).collect();
+        assert_eq!(
+            sequences,
+            vec![
+                sent.links[0].sequence,
+                sent.links[1].sequence,
+                sent.links[1].sequence + 1
+            ]
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cache_round_trips_and_overwrites_values() {
+        let dir = std::env::temp_dir().join(format!(
+            "z8-offline-cache-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let mut queue = open_queue(&dir);
+
+        assert!(queue.get_cached::<Vec<String>>("projects").unwrap().is_none());
+
+        queue.put_cached("projects", &vec!["a".to_string()]).unwrap();
+        queue.put_cached("projects", &vec!["b".to_string()]).unwrap();
+        let cached = queue.get_cached::<Vec<String>>("projects").unwrap().unwrap();
+        assert_eq!(cached.value, vec!["b".to_string()]);
+
+        queue.remove_cached("projects").unwrap();
+        assert!(queue.get_cached::<Vec<String>>("projects").unwrap().is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn local_status_applies_queued_punches_to_the_cached_one() {
+        let dir = std::env::temp_dir().join(format!(
+            "z8-offline-status-test-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let mut queue = open_queue(&dir);
+        assert!(queue.local_clock_status().unwrap().is_none());
+
+        let server = ClockStatus {
+            has_employee: true,
+            employee_id: Some("employee-1".to_string()),
+            is_clocked_in: false,
+            active_work_period: None,
+        };
+        queue.put_cached(CLOCK_STATUS_CACHE_KEY, &server).unwrap();
+        queue
+            .enqueue(ActionType::ClockIn, 1_777_593_600, None)
+            .unwrap();
+
+        // Reopened, as after a restart without network
+        drop(queue);
+        let queue = open_queue(&dir);
+        let status = queue.local_clock_status().unwrap().unwrap();
+        assert!(status.is_clocked_in);
+        assert_eq!(status.employee_id.as_deref(), Some("employee-1"));
+        assert_eq!(
+            status.active_work_period.unwrap().start_time,
+            "2026-05-01T00:00:00+00:00"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn converts_queued_timestamp_seconds_to_utc() {
         assert_eq!(
-            queued_timestamp_to_rfc3339(1_777_593_600).unwrap(),
+            queued_timestamp(1_777_593_600).unwrap().to_rfc3339(),
             "2026-05-01T00:00:00+00:00"
         );
     }
@@ -0,0 +1,122 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::api::{ApiRequest, Z8ApiClient};
+
+/// An organization the signed-in user is a member of
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Organization {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+    pub logo: Option<String>,
+    pub member_role: String,
+    /// Only organizations with an employee record can track time
+    pub has_employee_record: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationList {
+    pub organizations: Vec<Organization>,
+    pub active_organization_id: Option<String>,
+}
+
+pub struct OrganizationService {
+    api: Z8ApiClient,
+}
+
+impl OrganizationService {
+    pub fn new(api: Z8ApiClient) -> Self {
+        Self { api }
+    }
+
+    pub async fn list(&self) -> Result<OrganizationList> {
+        let response = self
+            .api
+            .send(ApiRequest::get("/api/desktop/organizations"))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!(
+                "Failed to fetch organizations: {}",
+                response.status
+            ));
+        }
+
+        response.json()
+    }
+
+    /// Makes `organization_id` the active organization of the session
+    pub async fn switch(&self, organization_id: &str) -> Result<()> {
+        let response = self
+            .api
+            .send(ApiRequest::post(
+                "/api/organizations/switch",
+                serde_json::json!({ "organizationId": organization_id }),
+            ))
+            .await?;
+
+        if !response.is_success() {
+            let message = response
+                .json::<serde_json::Value>()
+                .ok()
+                .and_then(|body| body["error"].as_str().map(str::to_string))
+                .unwrap_or_else(|| format!("Failed to switch organization: {}", response.status));
+            return Err(anyhow!(message));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OrganizationService;
+    use crate::api::testing::fake_client;
+
+    #[tokio::test]
+    async fn lists_organizations_with_the_active_one() {
+        let (client, transport) = fake_client();
+        transport.respond(
+            200,
+            serde_json::json!({
+                "organizations": [{
+                    "id": "org-1",
+                    "name": "Acme",
+                    "slug": "acme",
+                    "logo": null,
+                    "memberRole": "member",
+                    "hasEmployeeRecord": true,
+                }],
+                "activeOrganizationId": "org-1",
+            }),
+        );
+
+        let list = OrganizationService::new(client).list().await.unwrap();
+        assert_eq!(list.active_organization_id.as_deref(), Some("org-1"));
+        assert!(list.organizations[0].has_employee_record);
+        assert_eq!(
+            transport.requests()[0].header("Authorization"),
+            Some("Bearer session-token")
+        );
+    }
+
+    #[tokio::test]
+    async fn switches_and_reports_the_webapp_error() {
+        let (client, transport) = fake_client();
+        let service = OrganizationService::new(client);
+
+        transport.respond(200, serde_json::json!({ "success": true }));
+        service.switch("org-2").await.unwrap();
+        assert_eq!(
+            transport.requests()[0].body,
+            Some(serde_json::json!({ "organizationId": "org-2" }))
+        );
+
+        transport.respond(403, serde_json::json!({ "error": "Not a member" }));
+        let error = service.switch("org-3").await.unwrap_err();
+        assert_eq!(error.to_string(), "Not a member");
+    }
+}
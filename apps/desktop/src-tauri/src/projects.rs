@@ -0,0 +1,83 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::api::{ApiRequest, Z8ApiClient};
+
+/// Cache key for the assignable project list in the local store
+pub const PROJECTS_CACHE_KEY: &str = "projects";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectsResponse {
+    projects: Vec<Project>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectList {
+    pub projects: Vec<Project>,
+    /// True when the webapp was unreachable and the list came from the local cache
+    pub from_cache: bool,
+    pub updated_at: Option<i64>,
+}
+
+pub struct ProjectService {
+    api: Z8ApiClient,
+}
+
+impl ProjectService {
+    pub fn new(api: Z8ApiClient) -> Self {
+        Self { api }
+    }
+
+    /// Lists active projects assigned to the user directly or via their team
+    pub async fn list_projects(&self) -> Result<Vec<Project>> {
+        let response = self
+            .api
+            .send(ApiRequest::get("/api/extension/projects"))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Failed to fetch projects: {}", response.status));
+        }
+
+        let payload: ProjectsResponse = response.json()?;
+        Ok(payload.projects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProjectService;
+    use crate::api::testing::fake_client;
+
+    #[tokio::test]
+    async fn lists_assigned_projects() {
+        let (api, transport) = fake_client();
+        transport.respond(
+            200,
+            serde_json::json!({
+                "projects": [
+                    { "id": "project-1", "name": "Website", "color": "#ff0000", "icon": null },
+                ]
+            }),
+        );
+
+        let projects = ProjectService::new(api).list_projects().await.unwrap();
+
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name, "Website");
+        assert_eq!(
+            transport.requests()[0].url,
+            "https://z8.example/api/extension/projects"
+        );
+    }
+}
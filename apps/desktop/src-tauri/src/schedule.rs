@@ -0,0 +1,428 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::api::{ApiRequest, Z8ApiClient};
+use crate::notify;
+use crate::state::AppState;
+
+/// Cache key for the employee's published shifts in the local store
+pub const SCHEDULE_CACHE_KEY: &str = "schedule";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const SCHEDULE_REFRESH_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// How long before a shift starts or ends the reminder fires
+const REMINDER_LEAD_MINUTES: i64 = 10;
+/// Punches further than this outside the planned window ask for confirmation
+const PUNCH_TOLERANCE_MINUTES: i64 = 30;
+/// Shifts further away than this are not compared against a punch
+const PUNCH_MATCH_HOURS: i64 = 12;
+
+/// A published shift as planned, in the organization's wall-clock time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Shift {
+    pub id: String,
+    pub date: NaiveDate,
+    /// `HH:MM`
+    pub start_time: String,
+    /// `HH:MM`; at or before the start time for shifts that run past midnight
+    pub end_time: String,
+    pub notes: Option<String>,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    /// IANA zone the shift times are planned in
+    pub timezone: String,
+    pub shifts: Vec<Shift>,
+}
+
+/// A shift resolved to absolute start and end instants
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftWindow {
+    pub id: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub notes: Option<String>,
+    pub color: Option<String>,
+}
+
+/// The schedule as shown in the app: resolved shifts plus the one to highlight
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleView {
+    pub timezone: String,
+    pub shifts: Vec<ShiftWindow>,
+    pub next_shift: Option<ShiftWindow>,
+}
+
+fn parse_shift_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M:%S"))
+        .ok()
+}
+
+impl Shift {
+    fn window(&self, zone: &Tz) -> Option<ShiftWindow> {
+        let start_time = parse_shift_time(&self.start_time)?;
+        let end_time = parse_shift_time(&self.end_time)?;
+        let end_date = if end_time <= start_time {
+            self.date.succ_opt()?
+        } else {
+            self.date
+        };
+
+        let start = zone
+            .from_local_datetime(&self.date.and_time(start_time))
+            .earliest()?;
+        let end = zone
+            .from_local_datetime(&end_date.and_time(end_time))
+            .earliest()?;
+
+        Some(ShiftWindow {
+            id: self.id.clone(),
+            start: start.with_timezone(&Utc),
+            end: end.with_timezone(&Utc),
+            notes: self.notes.clone(),
+            color: self.color.clone(),
+        })
+    }
+}
+
+impl Schedule {
+    fn zone(&self) -> Tz {
+        self.timezone.parse().unwrap_or_else(|_| {
+            log::warn!("Unknown schedule timezone {}, using UTC", self.timezone);
+            Tz::UTC
+        })
+    }
+
+    /// All shifts as absolute windows, skipping any with unparseable times
+    pub fn windows(&self) -> Vec<ShiftWindow> {
+        let zone = self.zone();
+        let mut windows: Vec<ShiftWindow> = self
+            .shifts
+            .iter()
+            .filter_map(|shift| shift.window(&zone))
+            .collect();
+        windows.sort_by_key(|window| window.start);
+        windows
+    }
+
+    /// The shift currently running, or else the next one to start
+    pub fn next_shift(&self, now: DateTime<Utc>) -> Option<ShiftWindow> {
+        self.windows().into_iter().find(|window| window.end > now)
+    }
+
+    pub fn view(&self, now: DateTime<Utc>) -> ScheduleView {
+        ScheduleView {
+            timezone: self.timezone.clone(),
+            shifts: self.windows(),
+            next_shift: self.next_shift(now),
+        }
+    }
+
+    /// Warns when clocking in at `now` is well before or after the nearest planned shift
+    pub fn clock_in_warning(&self, now: DateTime<Utc>) -> Option<PunchWarning> {
+        let match_range = ChronoDuration::hours(PUNCH_MATCH_HOURS);
+        let tolerance = ChronoDuration::minutes(PUNCH_TOLERANCE_MINUTES);
+
+        let nearest = self
+            .windows()
+            .into_iter()
+            .filter(|window| now >= window.start - match_range && now <= window.end + match_range)
+            .min_by_key(|window| {
+                if now < window.start {
+                    window.start - now
+                } else if now > window.end {
+                    now - window.end
+                } else {
+                    ChronoDuration::zero()
+                }
+            })?;
+
+        if now < nearest.start - tolerance {
+            Some(PunchWarning {
+                timing: PunchTiming::Early,
+                minutes: (nearest.start - now).num_minutes(),
+                shift: nearest,
+            })
+        } else if now > nearest.end + tolerance {
+            Some(PunchWarning {
+                timing: PunchTiming::AfterShift,
+                minutes: (now - nearest.end).num_minutes(),
+                shift: nearest,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PunchTiming {
+    /// Before the shift starts
+    Early,
+    /// After the shift has already ended
+    AfterShift,
+}
+
+/// Clocking in outside the planned shift, so the user can confirm it was intended
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PunchWarning {
+    pub timing: PunchTiming,
+    /// Distance to the shift window
+    pub minutes: i64,
+    pub shift: ShiftWindow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShiftReminderKind {
+    StartsSoon,
+    EndsSoon,
+}
+
+/// A reminder shortly before a shift starts or ends
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShiftReminder {
+    pub kind: ShiftReminderKind,
+    pub minutes_left: i64,
+    pub shift: ShiftWindow,
+}
+
+impl ShiftReminder {
+    fn message(&self) -> (&'static str, String) {
+        match self.kind {
+            ShiftReminderKind::StartsSoon => (
+                "Shift starts soon",
+                format!(
+                    "Your shift starts in {} min. Don't forget to clock in.",
+                    self.minutes_left
+                ),
+            ),
+            ShiftReminderKind::EndsSoon => (
+                "Shift ends soon",
+                format!("Your shift ends in {} min.", self.minutes_left),
+            ),
+        }
+    }
+}
+
+/// Reminders that are due now: before a start while clocked out, before an end while clocked in
+pub fn due_reminders(
+    windows: &[ShiftWindow],
+    now: DateTime<Utc>,
+    is_clocked_in: bool,
+) -> Vec<ShiftReminder> {
+    let lead = ChronoDuration::minutes(REMINDER_LEAD_MINUTES);
+
+    windows
+        .iter()
+        .filter_map(|window| {
+            let (kind, at) = if is_clocked_in {
+                (ShiftReminderKind::EndsSoon, window.end)
+            } else {
+                (ShiftReminderKind::StartsSoon, window.start)
+            };
+            (at > now && at - now <= lead).then(|| ShiftReminder {
+                kind,
+                minutes_left: (at - now).num_minutes().max(1),
+                shift: window.clone(),
+            })
+        })
+        .collect()
+}
+
+pub struct ScheduleService {
+    api: Z8ApiClient,
+}
+
+impl ScheduleService {
+    pub fn new(api: Z8ApiClient) -> Self {
+        Self { api }
+    }
+
+    /// Fetches published shifts from yesterday through the next four weeks
+    pub async fn fetch(&self) -> Result<Schedule> {
+        let response = self
+            .api
+            .send(ApiRequest::get("/api/desktop/schedule"))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!("Failed to fetch schedule: {}", response.status));
+        }
+
+        response.json()
+    }
+}
+
+/// Reads the cached schedule; empty when nothing has been fetched yet
+pub fn cached_schedule(state: &AppState) -> Schedule {
+    let queue = state.offline_queue.lock();
+    match queue.get_cached::<Schedule>(SCHEDULE_CACHE_KEY) {
+        Ok(cached) => cached.map(|cached| cached.value).unwrap_or_default(),
+        Err(e) => {
+            log::warn!("Failed to read cached schedule: {}", e);
+            Schedule::default()
+        }
+    }
+}
+
+/// Keeps the cached schedule fresh and reminds before shifts start and end.
+/// Works from the cache, so reminders keep firing offline.
+pub async fn start_shift_reminders(app_handle: AppHandle) {
+    log::info!("Starting shift reminder scheduler");
+
+    let state = app_handle.state::<Arc<AppState>>();
+    let schedule_service = ScheduleService::new(state.api.clone());
+
+    let mut fetched_at: Option<Instant> = None;
+    let mut sent: HashSet<(String, ShiftReminderKind)> = HashSet::new();
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let online = state.get_session_token().is_some() && !state.get_webapp_url().is_empty();
+        if online && fetched_at.is_none_or(|at| at.elapsed() >= SCHEDULE_REFRESH_INTERVAL) {
+            match schedule_service.fetch().await {
+                Ok(schedule) => {
+                    fetched_at = Some(Instant::now());
+                    let mut queue = state.offline_queue.lock();
+                    if let Err(e) = queue.put_cached(SCHEDULE_CACHE_KEY, &schedule) {
+                        log::warn!("Failed to cache schedule: {}", e);
+                    }
+                    drop(queue);
+                    let _ = app_handle.emit("schedule_updated", &schedule);
+                }
+                Err(e) => log::warn!("Failed to refresh schedule: {}", e),
+            }
+        }
+
+        if !state.settings.read().shift_reminders_enabled {
+            continue;
+        }
+
+        let now = Utc::now();
+        let windows = cached_schedule(&state).windows();
+        sent.retain(|(id, _)| {
+            windows
+                .iter()
+                .any(|window| &window.id == id && window.end > now)
+        });
+
+        for reminder in due_reminders(&windows, now, state.is_clocked_in()) {
+            if !sent.insert((reminder.shift.id.clone(), reminder.kind)) {
+                continue;
+            }
+
+            log::info!("Shift reminder: {:?}", reminder);
+            let (title, body) = reminder.message();
+            notify::send(&app_handle, title, &body);
+            if let Err(e) = app_handle.emit("shift_reminder", &reminder) {
+                log::error!("Failed to emit shift reminder: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{due_reminders, PunchTiming, Schedule, Shift, ShiftReminderKind};
+    use chrono::{DateTime, Utc};
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn schedule(shifts: &[(&str, &str, &str, &str)]) -> Schedule {
+        Schedule {
+            timezone: "Europe/Berlin".to_string(),
+            shifts: shifts
+                .iter()
+                .map(|(id, date, start, end)| Shift {
+                    id: id.to_string(),
+                    date: date.parse().unwrap(),
+                    start_time: start.to_string(),
+                    end_time: end.to_string(),
+                    notes: None,
+                    color: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn resolves_shifts_in_the_organization_timezone() {
+        let schedule = schedule(&[
+            ("night", "2026-10-20", "22:00", "06:00"),
+            ("day", "2026-10-20", "09:00", "17:00"),
+        ]);
+        let windows = schedule.windows();
+
+        assert_eq!(windows[0].id, "day");
+        assert_eq!(windows[0].start, at("2026-10-20T07:00:00Z"));
+        assert_eq!(windows[1].end, at("2026-10-21T04:00:00Z"));
+
+        // While the day shift runs it is the next one; afterwards the night shift is
+        assert_eq!(
+            schedule.next_shift(at("2026-10-20T12:00:00Z")).unwrap().id,
+            "day"
+        );
+        assert_eq!(
+            schedule.next_shift(at("2026-10-20T16:00:00Z")).unwrap().id,
+            "night"
+        );
+    }
+
+    #[test]
+    fn warns_only_for_punches_well_outside_the_shift() {
+        let schedule = schedule(&[("day", "2026-10-20", "09:00", "17:00")]);
+        let warning = |now: &str| {
+            schedule
+                .clock_in_warning(at(now))
+                .map(|warning| (warning.timing, warning.minutes))
+        };
+
+        assert_eq!(
+            warning("2026-10-20T06:00:00Z"),
+            Some((PunchTiming::Early, 60))
+        );
+        assert_eq!(warning("2026-10-20T06:45:00Z"), None);
+        assert_eq!(warning("2026-10-20T10:00:00Z"), None);
+        assert_eq!(
+            warning("2026-10-20T16:00:00Z"),
+            Some((PunchTiming::AfterShift, 60))
+        );
+        // No shift nearby means nothing to compare against
+        assert_eq!(warning("2026-10-22T08:00:00Z"), None);
+    }
+
+    #[test]
+    fn reminds_before_start_when_out_and_before_end_when_in() {
+        let windows = schedule(&[("day", "2026-10-20", "09:00", "17:00")]).windows();
+
+        let starts = due_reminders(&windows, at("2026-10-20T06:52:00Z"), false);
+        assert_eq!(starts.len(), 1);
+        assert_eq!(starts[0].kind, ShiftReminderKind::StartsSoon);
+        assert_eq!(starts[0].minutes_left, 8);
+        assert!(due_reminders(&windows, at("2026-10-20T06:52:00Z"), true).is_empty());
+
+        let ends = due_reminders(&windows, at("2026-10-20T14:55:00Z"), true);
+        assert_eq!(ends[0].kind, ShiftReminderKind::EndsSoon);
+        assert!(due_reminders(&windows, at("2026-10-20T14:30:00Z"), true).is_empty());
+    }
+}
@@ -0,0 +1,305 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use url::Url;
+
+use crate::api::{ApiRequest, Method, Z8ApiClient};
+use crate::notify;
+use crate::state::AppState;
+
+/// Cache key for the ids already shown, so restarts don't repeat notifications
+const SEEN_CACHE_KEY: &str = "server_notifications_seen";
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Unread notifications fetched when the unread count changes
+const PAGE_SIZE: u32 = 20;
+/// Ids remembered for deduplication; older ones have long left the unread page
+const MAX_SEEN_IDS: usize = 500;
+/// More new notifications than this are announced with a single summary
+const MAX_INDIVIDUAL_NOTIFICATIONS: usize = 3;
+
+/// A notification stored by the webapp for the current user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerNotification {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub notification_type: String,
+    pub title: String,
+    pub message: String,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    /// Webapp path to open, e.g. `/time-tracking`
+    pub action_url: Option<String>,
+    pub is_read: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPage {
+    pub notifications: Vec<ServerNotification>,
+    pub total: u32,
+    pub unread_count: u32,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CountResponse {
+    count: u32,
+}
+
+pub struct NotificationService {
+    api: Z8ApiClient,
+}
+
+impl NotificationService {
+    pub fn new(api: Z8ApiClient) -> Self {
+        Self { api }
+    }
+
+    pub async fn unread_count(&self) -> Result<u32> {
+        let response = self
+            .api
+            .send(ApiRequest::get("/api/notifications/count"))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!(
+                "Failed to fetch notification count: {}",
+                response.status
+            ));
+        }
+
+        let payload: CountResponse = response.json()?;
+        Ok(payload.count)
+    }
+
+    /// Lists notifications newest first
+    pub async fn list(
+        &self,
+        unread_only: bool,
+        limit: u32,
+        offset: u32,
+    ) -> Result<NotificationPage> {
+        let response = self
+            .api
+            .send(
+                ApiRequest::get("/api/notifications")
+                    .query("limit", &limit.to_string())
+                    .query("offset", &offset.to_string())
+                    .query("unreadOnly", if unread_only { "true" } else { "false" }),
+            )
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!(
+                "Failed to fetch notifications: {}",
+                response.status
+            ));
+        }
+
+        response.json()
+    }
+
+    pub async fn mark_read(&self, notification_id: &str) -> Result<()> {
+        self.patch(serde_json::json!({ "id": notification_id }))
+            .await
+    }
+
+    pub async fn mark_all_read(&self) -> Result<()> {
+        self.patch(serde_json::json!({ "markAllRead": true })).await
+    }
+
+    async fn patch(&self, body: serde_json::Value) -> Result<()> {
+        let response = self
+            .api
+            .send(ApiRequest::new(Method::Patch, "/api/notifications").json(body))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!(
+                "Failed to mark notifications read: {}",
+                response.status
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolves a notification's action path against the webapp, refusing other origins
+pub fn action_url(webapp_url: &str, action: &str) -> Result<Url> {
+    let base = Url::parse(webapp_url)?;
+    let url = base.join(action)?;
+
+    if url.origin() != base.origin() {
+        bail!("Notification link leaves the webapp: {}", url);
+    }
+
+    Ok(url)
+}
+
+/// Opens a webapp page in the default browser
+#[allow(deprecated)] // tauri-plugin-opener is not a dependency yet, same as the login flow
+pub fn open_in_browser(app_handle: &AppHandle, url: &Url) -> Result<()> {
+    tauri_plugin_shell::ShellExt::shell(app_handle).open(url.as_str(), None)?;
+    Ok(())
+}
+
+/// Bounded, ordered set of notification ids that were already shown
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SeenIds(VecDeque<String>);
+
+impl SeenIds {
+    fn contains(&self, id: &str) -> bool {
+        self.0.iter().any(|seen| seen == id)
+    }
+
+    /// Records the page and returns the notifications it had not seen, oldest first
+    fn record<'a>(
+        &mut self,
+        notifications: &'a [ServerNotification],
+    ) -> Vec<&'a ServerNotification> {
+        let mut fresh: Vec<&ServerNotification> = notifications
+            .iter()
+            .filter(|notification| !self.contains(&notification.id))
+            .collect();
+        fresh.sort_by_key(|notification| notification.created_at);
+
+        for notification in &fresh {
+            self.0.push_back(notification.id.clone());
+        }
+        while self.0.len() > MAX_SEEN_IDS {
+            self.0.pop_front();
+        }
+
+        fresh
+    }
+}
+
+/// Polls the webapp's unread count and shows new server notifications as OS notifications.
+/// Ids are remembered in the local store so each notification pops up only once.
+pub async fn start_notification_sync(app_handle: AppHandle) {
+    log::info!("Starting server notification sync");
+
+    let state = app_handle.state::<Arc<AppState>>();
+    let service = NotificationService::new(state.api.clone());
+
+    let mut last_count: Option<u32> = None;
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        if state.get_session_token().is_none() || state.get_webapp_url().is_empty() {
+            continue;
+        }
+
+        let count = match service.unread_count().await {
+            Ok(count) => count,
+            Err(e) => {
+                log::warn!("Failed to check server notifications: {}", e);
+                continue;
+            }
+        };
+        if last_count == Some(count) {
+            continue;
+        }
+        let _ = app_handle.emit("server_notification_count", count);
+
+        let page = match service.list(true, PAGE_SIZE, 0).await {
+            Ok(page) => page,
+            Err(e) => {
+                log::warn!("Failed to fetch server notifications: {}", e);
+                continue;
+            }
+        };
+        last_count = Some(count);
+
+        let cached: Option<SeenIds> = {
+            let queue = state.offline_queue.lock();
+            queue
+                .get_cached(SEEN_CACHE_KEY)
+                .ok()
+                .flatten()
+                .map(|cached| cached.value)
+        };
+        // Without any history, only remember what is already unread
+        let is_first_sync = cached.is_none();
+        let mut seen = cached.unwrap_or_default();
+        let fresh = seen.record(&page.notifications);
+
+        if !is_first_sync {
+            if fresh.len() > MAX_INDIVIDUAL_NOTIFICATIONS {
+                notify::send(
+                    &app_handle,
+                    "New notifications",
+                    &format!("You have {} new notifications in z8.", fresh.len()),
+                );
+            } else {
+                for notification in &fresh {
+                    notify::send(&app_handle, &notification.title, &notification.message);
+                }
+            }
+            for notification in fresh {
+                let _ = app_handle.emit("server_notification", notification);
+            }
+        }
+
+        let mut queue = state.offline_queue.lock();
+        if let Err(e) = queue.put_cached(SEEN_CACHE_KEY, &seen) {
+            log::warn!("Failed to remember shown notifications: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{action_url, SeenIds, ServerNotification};
+
+    fn notification(id: &str, created_at: &str) -> ServerNotification {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "userId": "user-1",
+            "organizationId": "org-1",
+            "type": "absence_request_approved",
+            "title": "Absence approved",
+            "message": "Your vacation was approved",
+            "entityType": "absence_entry",
+            "entityId": "abs-1",
+            "actionUrl": "/absences",
+            "isRead": false,
+            "readAt": null,
+            "metadata": null,
+            "createdAt": created_at
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn shows_each_notification_once_oldest_first() {
+        let mut seen = SeenIds::default();
+        let page = [
+            notification("b", "2026-10-15T09:00:00.000Z"),
+            notification("a", "2026-10-15T08:00:00.000Z"),
+        ];
+
+        let fresh: Vec<&str> = seen.record(&page).iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(fresh, vec!["a", "b"]);
+        assert!(seen.record(&page).is_empty());
+    }
+
+    #[test]
+    fn resolves_action_links_only_inside_the_webapp() {
+        assert_eq!(
+            action_url("https://z8.example/", "/absences")
+                .unwrap()
+                .as_str(),
+            "https://z8.example/absences"
+        );
+        assert!(action_url("https://z8.example/", "https://evil.example/").is_err());
+    }
+}
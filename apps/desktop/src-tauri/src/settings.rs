@@ -3,13 +3,88 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
+use crate::location::LocationRule;
+use crate::triggers::TriggerRule;
+
 pub const DEFAULT_WEBAPP_URL: &str = "https://ui.z8-time.app";
+pub const DEFAULT_SWITCH_PROJECT_SHORTCUT: &str = "CommandOrControl+Alt+P";
+pub const DEFAULT_WEEKLY_TARGET_HOURS: f64 = 40.0;
+pub const DEFAULT_END_OF_DAY_TIME: &str = "18:00";
+pub const DEFAULT_END_OF_DAY_IDLE_MINUTES: u32 = 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub webapp_url: String,
     pub always_on_top: bool,
     pub auto_startup: bool,
+    #[serde(default)]
+    pub network: NetworkSettings,
+    /// Global shortcut that opens the project picker; `None` disables it
+    #[serde(default = "default_switch_project_shortcut")]
+    pub switch_project_shortcut: Option<String>,
+    /// Contracted hours per week, compared against worked time in the weekly summary
+    #[serde(default = "default_weekly_target_hours")]
+    pub weekly_target_hours: f64,
+    /// Desktop notifications ahead of legally required breaks and daily limits
+    #[serde(default = "default_break_reminders_enabled")]
+    pub break_reminders_enabled: bool,
+    /// Desktop notifications shortly before a planned shift starts or ends
+    #[serde(default = "default_shift_reminders_enabled")]
+    pub shift_reminders_enabled: bool,
+    /// Local time (`HH:MM`) after which a running clock prompts for clock-out; `None` disables it
+    #[serde(default = "default_end_of_day_time")]
+    pub end_of_day_time: Option<String>,
+    /// Idle minutes after the end of day that count as having left without clocking out
+    #[serde(default = "default_end_of_day_idle_minutes")]
+    pub end_of_day_idle_minutes: u32,
+    /// Network signals that pre-select the work location on clock-in
+    #[serde(default)]
+    pub location_rules: Vec<LocationRule>,
+    /// Device and network events that punch automatically or propose a punch
+    #[serde(default)]
+    pub trigger_rules: Vec<TriggerRule>,
+    /// Localhost API for scripts and editor plugins, see `control.rs`
+    #[serde(default)]
+    pub control_api_enabled: bool,
+}
+
+fn default_switch_project_shortcut() -> Option<String> {
+    Some(DEFAULT_SWITCH_PROJECT_SHORTCUT.to_string())
+}
+
+fn default_weekly_target_hours() -> f64 {
+    DEFAULT_WEEKLY_TARGET_HOURS
+}
+
+fn default_break_reminders_enabled() -> bool {
+    true
+}
+
+fn default_shift_reminders_enabled() -> bool {
+    true
+}
+
+fn default_end_of_day_time() -> Option<String> {
+    Some(DEFAULT_END_OF_DAY_TIME.to_string())
+}
+
+fn default_end_of_day_idle_minutes() -> u32 {
+    DEFAULT_END_OF_DAY_IDLE_MINUTES
+}
+
+/// Proxy and TLS overrides for corporate networks. Empty values fall back to
+/// the `Z8_*` environment variables, then to the system proxy configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NetworkSettings {
+    /// HTTP(S) proxy for all webapp requests, e.g. `http://proxy.corp:8080`
+    pub proxy_url: Option<String>,
+    /// Comma-separated hosts, domains or CIDR ranges that bypass the proxy
+    pub no_proxy: Option<String>,
+    /// PEM bundle with extra root certificates (e.g. a TLS-inspecting proxy's CA)
+    pub ca_certificate_path: Option<String>,
+    /// Trust the operating system's certificate store in addition to the bundled roots
+    pub use_system_roots: bool,
 }
 
 impl Default for Settings {
@@ -18,6 +93,16 @@ impl Default for Settings {
             webapp_url: DEFAULT_WEBAPP_URL.to_string(),
             always_on_top: true,
             auto_startup: false,
+            network: NetworkSettings::default(),
+            switch_project_shortcut: default_switch_project_shortcut(),
+            weekly_target_hours: DEFAULT_WEEKLY_TARGET_HOURS,
+            break_reminders_enabled: true,
+            shift_reminders_enabled: true,
+            end_of_day_time: default_end_of_day_time(),
+            end_of_day_idle_minutes: DEFAULT_END_OF_DAY_IDLE_MINUTES,
+            location_rules: Vec::new(),
+            trigger_rules: Vec::new(),
+            control_api_enabled: false,
         }
     }
 }
@@ -0,0 +1,31 @@
+use anyhow::Result;
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::tray;
+
+/// Checks that a shortcut such as `CmdOrCtrl+Shift+P` can be registered
+pub fn validate_shortcut(shortcut: &str) -> Result<()> {
+    shortcut.trim().parse::<Shortcut>()?;
+    Ok(())
+}
+
+/// Registers the configured global shortcuts, replacing any registered before
+pub fn register_shortcuts(app_handle: &AppHandle, switch_project: Option<&str>) -> Result<()> {
+    let global_shortcut = app_handle.global_shortcut();
+    global_shortcut.unregister_all()?;
+
+    if let Some(shortcut) = switch_project
+        .map(str::trim)
+        .filter(|shortcut| !shortcut.is_empty())
+    {
+        global_shortcut.on_shortcut(shortcut, |app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                tray::open_switch_project(app);
+            }
+        })?;
+        log::info!("Registered switch project shortcut: {}", shortcut);
+    }
+
+    Ok(())
+}
@@ -1,23 +1,46 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use parking_lot::{Mutex, RwLock};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
 
+use crate::api::Z8ApiClient;
+use crate::auth::Profile;
+use crate::clock::{ClockStatus, PunchDetails, CLOCK_STATUS_CACHE_KEY};
+use crate::deep_link::DeepLinkState;
+use crate::device::DeviceKey;
+use crate::journal::Journal;
+use crate::network::NetworkConfig;
 use crate::offline::OfflineQueue;
 use crate::settings::Settings;
+use crate::triggers::TriggerState;
 
 pub struct AppState {
     pub app_handle: AppHandle,
-    pub session_token: RwLock<Option<String>>,
+    pub api: Z8ApiClient, // Owns the session token and webapp base URL
     pub pending_app_auth_verifier: RwLock<Option<String>>,
+    pub device_key: Arc<DeviceKey>, // Registered at sign-in, signs the offline queue
     pub settings: RwLock<Settings>,
     pub offline_queue: Mutex<OfflineQueue>, // Mutex for SQLite thread safety
+    pub journal: Mutex<Journal>,            // Append-only record of punches, idle time and syncs
     pub is_clocked_in: RwLock<bool>,
+    pub active_punch_details: RwLock<PunchDetails>, // Project/category chosen at clock-in
+    pub last_activity: RwLock<Option<DateTime<Utc>>>, // Last keyboard/mouse input seen by the idle monitor
+    pub triggers: Mutex<TriggerState>, // Latest network signals and fired automatic punches
+    pub control_server: Mutex<Option<JoinHandle<()>>>, // Running localhost control API, if enabled
+    pub deep_links: Mutex<DeepLinkState>, // z8:// link actions waiting for confirmation
+    pub queue_wakeup: Notify,          // Starts a queue pass without waiting for the next tick
     app_data_dir: PathBuf,
 }
 
-const TOKEN_FILE: &str = "session_token.txt";
+pub const TOKEN_FILE: &str = "session_token.txt";
+pub const DEVICE_ID_FILE: &str = "device_id.txt";
+pub const ACTIVE_PUNCH_DETAILS_KEY: &str = "active_punch_details";
+const PROFILE_CACHE_KEY: &str = "profile";
 
 impl AppState {
     pub fn new(app_handle: AppHandle) -> Result<Self> {
@@ -34,7 +57,26 @@ impl AppState {
         let settings = Settings::load(&app_data_dir)?;
 
         // Initialize offline queue
-        let queue = OfflineQueue::new(&app_data_dir)?;
+        // Shared with the queue, which signs its chain with it
+        let device_key = Arc::new(DeviceKey::load_or_create(&app_data_dir)?);
+        let queue = OfflineQueue::new(&app_data_dir, device_key.clone())?;
+        let active_punch_details = queue
+            .get_cached::<PunchDetails>(ACTIVE_PUNCH_DETAILS_KEY)
+            .ok()
+            .flatten()
+            .map(|cached| cached.value)
+            .unwrap_or_default();
+        // Start from what the webapp last reported plus queued punches, so the app
+        // shows the right state even when it starts offline
+        let is_clocked_in = match queue.local_clock_status() {
+            Ok(status) => status.is_some_and(|status| status.is_clocked_in),
+            Err(e) => {
+                log::warn!("Failed to read cached clock status: {}", e);
+                false
+            }
+        };
+
+        let journal = Journal::new(&app_data_dir)?;
 
         // Load persisted session token
         let token_path = app_data_dir.join(TOKEN_FILE);
@@ -44,19 +86,52 @@ impl AppState {
             None
         };
 
+        let device_id = fs::read_to_string(app_data_dir.join(DEVICE_ID_FILE))
+            .ok()
+            .map(|device_id| device_id.trim().to_string())
+            .filter(|device_id| !device_id.is_empty());
+
+        let network = NetworkConfig::resolve(&settings.network);
+        let api = match Z8ApiClient::new(&settings.webapp_url, session_token.clone(), &network) {
+            Ok(api) => api,
+            Err(e) => {
+                // A broken proxy or CA path must not stop the app from starting
+                log::error!("Invalid network settings, using defaults: {}", e);
+                Z8ApiClient::new(
+                    &settings.webapp_url,
+                    session_token,
+                    &NetworkConfig::default(),
+                )?
+            }
+        };
+        api.set_device_id(device_id);
+
         Ok(Self {
             app_handle,
-            session_token: RwLock::new(session_token),
+            api,
             pending_app_auth_verifier: RwLock::new(None),
+            device_key,
             settings: RwLock::new(settings),
             offline_queue: Mutex::new(queue),
-            is_clocked_in: RwLock::new(false),
+            journal: Mutex::new(journal),
+            is_clocked_in: RwLock::new(is_clocked_in),
+            active_punch_details: RwLock::new(active_punch_details),
+            last_activity: RwLock::new(None),
+            triggers: Mutex::new(TriggerState::default()),
+            control_server: Mutex::new(None),
+            deep_links: Mutex::new(DeepLinkState::default()),
+            queue_wakeup: Notify::new(),
             app_data_dir,
         })
     }
 
+    /// Sends queued actions now, e.g. a punch queued behind earlier ones
+    pub fn process_queue_soon(&self) {
+        self.queue_wakeup.notify_one();
+    }
+
     pub fn set_session_token(&self, token: Option<String>) {
-        *self.session_token.write() = token.clone();
+        self.api.set_session_token(token.clone());
 
         // Persist to file
         let token_path = self.app_data_dir.join(TOKEN_FILE);
@@ -67,8 +142,20 @@ impl AppState {
         }
     }
 
+    /// The id the webapp gave this device at sign-in; `None` until registered
+    pub fn set_device_id(&self, device_id: Option<String>) {
+        self.api.set_device_id(device_id.clone());
+
+        let device_id_path = self.app_data_dir.join(DEVICE_ID_FILE);
+        if let Some(device_id) = device_id {
+            let _ = fs::write(&device_id_path, device_id);
+        } else {
+            let _ = fs::remove_file(&device_id_path);
+        }
+    }
+
     pub fn get_session_token(&self) -> Option<String> {
-        self.session_token.read().clone()
+        self.api.session_token()
     }
 
     pub fn set_pending_app_auth_verifier(&self, verifier: Option<String>) {
@@ -90,4 +177,94 @@ impl AppState {
     pub fn is_clocked_in(&self) -> bool {
         *self.is_clocked_in.read()
     }
+
+    pub fn set_last_activity(&self, at: DateTime<Utc>) {
+        *self.last_activity.write() = Some(at);
+    }
+
+    pub fn last_activity(&self) -> Option<DateTime<Utc>> {
+        *self.last_activity.read()
+    }
+
+    pub fn active_punch_details(&self) -> PunchDetails {
+        self.active_punch_details.read().clone()
+    }
+
+    pub fn set_active_punch_details(&self, details: PunchDetails) {
+        let mut queue = self.offline_queue.lock();
+        let result = if details.is_empty() {
+            queue.remove_cached(ACTIVE_PUNCH_DETAILS_KEY)
+        } else {
+            queue.put_cached(ACTIVE_PUNCH_DETAILS_KEY, &details)
+        };
+
+        if let Err(e) = result {
+            log::warn!("Failed to persist active punch details: {}", e);
+        }
+
+        *self.active_punch_details.write() = details;
+    }
+
+    /// The signed-in user, kept so the window can show it while offline
+    pub fn profile(&self) -> Option<Profile> {
+        match self.offline_queue.lock().get_cached(PROFILE_CACHE_KEY) {
+            Ok(cached) => cached.map(|cached| cached.value),
+            Err(e) => {
+                log::warn!("Failed to read cached profile: {}", e);
+                None
+            }
+        }
+    }
+
+    pub fn set_profile(&self, profile: Option<Profile>) {
+        let mut queue = self.offline_queue.lock();
+        let result = match &profile {
+            Some(profile) => queue.put_cached(PROFILE_CACHE_KEY, profile),
+            None => queue.remove_cached(PROFILE_CACHE_KEY),
+        };
+
+        if let Err(e) = result {
+            log::warn!("Failed to persist profile: {}", e);
+        }
+    }
+
+    /// Caches the webapp's status for offline starts and returns it with the
+    /// punches still queued applied
+    pub fn remember_clock_status(&self, status: &ClockStatus) -> ClockStatus {
+        let mut queue = self.offline_queue.lock();
+        if let Err(e) = queue.put_cached(CLOCK_STATUS_CACHE_KEY, status) {
+            log::warn!("Failed to cache clock status: {}", e);
+        }
+
+        match queue.local_clock_status() {
+            Ok(Some(local)) => local,
+            Ok(None) => status.clone(),
+            Err(e) => {
+                log::warn!("Failed to apply queued punches: {}", e);
+                status.clone()
+            }
+        }
+    }
+
+    /// The status as far as the app knows without the webapp
+    pub fn local_clock_status(&self) -> ClockStatus {
+        match self.offline_queue.lock().local_clock_status() {
+            Ok(status) => status.unwrap_or_else(ClockStatus::unknown),
+            Err(e) => {
+                log::warn!("Failed to read cached clock status: {}", e);
+                ClockStatus::unknown()
+            }
+        }
+    }
+
+    /// Drops the cached status, e.g. so the next user doesn't start with it
+    pub fn forget_clock_status(&self) {
+        if let Err(e) = self
+            .offline_queue
+            .lock()
+            .remove_cached(CLOCK_STATUS_CACHE_KEY)
+        {
+            log::warn!("Failed to clear cached clock status: {}", e);
+        }
+    }
 }
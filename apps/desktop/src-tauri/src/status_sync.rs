@@ -0,0 +1,307 @@
+//! Keeps the clock state in step with punches made in the webapp or on a phone.
+//!
+//! Status is polled more often while the window is open and less often while the
+//! user is away. The webapp's server-sent events channel at
+//! `/api/desktop/clock-status/stream` sends `clock_status` events, which are applied as
+//! they arrive; polling then only guards against missed events. Webapps without the
+//! channel are polled only.
+
+use chrono::Utc;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::clock::{ClockService, ClockStatus, PunchDetails};
+use crate::state::AppState;
+use crate::tray;
+
+/// How often the loop reconsiders whether a poll is due
+const TICK: Duration = Duration::from_secs(5);
+const VISIBLE_INTERVAL: Duration = Duration::from_secs(15);
+const HIDDEN_INTERVAL: Duration = Duration::from_secs(60);
+const AWAY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Polling while the push channel is open, in case an event was missed
+const PUSHED_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const AWAY_THRESHOLD_MINUTES: i64 = 5;
+
+/// Reconnect delays for the push channel, doubled after each failure
+const STREAM_RETRY_MIN: Duration = Duration::from_secs(15);
+const STREAM_RETRY_MAX: Duration = Duration::from_secs(10 * 60);
+/// How long to wait before asking again when the webapp has no push channel
+const STREAM_UNAVAILABLE_RETRY: Duration = Duration::from_secs(60 * 60);
+/// The webapp sends a comment at least this often; silence means a dead connection
+const STREAM_SILENCE_TIMEOUT: Duration = Duration::from_secs(2 * 60);
+
+/// Name of the server-sent event that carries a `ClockStatus`
+const STATUS_EVENT: &str = "clock_status";
+
+/// How long to wait between polls
+fn poll_interval(window_visible: bool, away: bool, pushed: bool) -> Duration {
+    if pushed {
+        PUSHED_INTERVAL
+    } else if window_visible {
+        VISIBLE_INTERVAL
+    } else if away {
+        AWAY_INTERVAL
+    } else {
+        HIDDEN_INTERVAL
+    }
+}
+
+/// The last status published, shared by the poll loop and the push channel
+#[derive(Default)]
+struct Published {
+    status: Mutex<Option<ClockStatus>>,
+    stream_open: AtomicBool,
+}
+
+/// Stores a status from the webapp and, if it changed, updates the tray and the window
+fn apply(app_handle: &AppHandle, published: &Published, server: &ClockStatus) {
+    let state = app_handle.state::<Arc<AppState>>();
+    let status = state.remember_clock_status(server);
+
+    let mut last = published.status.lock();
+    if last.as_ref() == Some(&status) {
+        return;
+    }
+
+    // Clocked out elsewhere: the project chosen here no longer applies
+    if state.is_clocked_in() && !status.is_clocked_in {
+        state.set_active_punch_details(PunchDetails::default());
+    }
+    if state.is_clocked_in() != status.is_clocked_in {
+        log::info!(
+            "Clock status changed elsewhere: clocked_in={}",
+            status.is_clocked_in
+        );
+    }
+
+    state.set_clocked_in(status.is_clocked_in);
+    let _ = tray::update_tray_icon(app_handle, status.is_clocked_in);
+    let _ = app_handle.emit("clock_status_changed", &status);
+    *last = Some(status);
+}
+
+/// Polls the clock status and listens for pushed changes
+pub async fn start_status_sync(app_handle: AppHandle) {
+    log::info!("Starting clock status sync");
+
+    let published = Arc::new(Published::default());
+
+    let push_handle = app_handle.clone();
+    let push_published = published.clone();
+    tauri::async_runtime::spawn(async move {
+        run_push_channel(push_handle, push_published).await;
+    });
+
+    let state = app_handle.state::<Arc<AppState>>();
+    let clock_service = ClockService::new(state.api.clone());
+    let mut last_poll: Option<Instant> = None;
+
+    loop {
+        tokio::time::sleep(TICK).await;
+
+        if state.get_session_token().is_none() || state.get_webapp_url().is_empty() {
+            *published.status.lock() = None;
+            continue;
+        }
+
+        let window_visible = app_handle
+            .get_webview_window("main")
+            .and_then(|window| window.is_visible().ok())
+            .unwrap_or(false);
+        let away = state
+            .last_activity()
+            .is_some_and(|at| (Utc::now() - at).num_minutes() >= AWAY_THRESHOLD_MINUTES);
+        let interval = poll_interval(
+            window_visible,
+            away,
+            published.stream_open.load(Ordering::Relaxed),
+        );
+        if last_poll.is_some_and(|at| at.elapsed() < interval) {
+            continue;
+        }
+        last_poll = Some(Instant::now());
+
+        match clock_service.get_status().await {
+            Ok(status) => apply(&app_handle, &published, &status),
+            Err(e) => log::debug!("Failed to refresh clock status: {}", e),
+        }
+    }
+}
+
+/// Keeps the push channel open while signed in, backing off while it fails
+async fn run_push_channel(app_handle: AppHandle, published: Arc<Published>) {
+    let state = app_handle.state::<Arc<AppState>>();
+    let clock_service = ClockService::new(state.api.clone());
+    let mut retry = STREAM_RETRY_MIN;
+
+    loop {
+        if state.get_session_token().is_none() || state.get_webapp_url().is_empty() {
+            tokio::time::sleep(STREAM_RETRY_MIN).await;
+            continue;
+        }
+
+        let mut stream = match clock_service.status_stream().await {
+            Ok(Some(stream)) => stream,
+            Ok(None) => {
+                log::debug!("Webapp has no clock status stream; polling only");
+                tokio::time::sleep(STREAM_UNAVAILABLE_RETRY).await;
+                continue;
+            }
+            Err(e) => {
+                log::debug!("Failed to open clock status stream: {}", e);
+                tokio::time::sleep(retry).await;
+                retry = (retry * 2).min(STREAM_RETRY_MAX);
+                continue;
+            }
+        };
+
+        log::info!("Listening for clock status changes");
+        published.stream_open.store(true, Ordering::Relaxed);
+        let mut parser = EventParser::default();
+
+        loop {
+            let chunk =
+                match tokio::time::timeout(STREAM_SILENCE_TIMEOUT, stream.next_chunk()).await {
+                    Ok(Some(Ok(chunk))) => chunk,
+                    Ok(Some(Err(e))) => {
+                        log::debug!("Clock status stream failed: {}", e);
+                        break;
+                    }
+                    Ok(None) | Err(_) => break,
+                };
+            // Opened and delivering, so a later drop is not the webapp refusing us
+            retry = STREAM_RETRY_MIN;
+
+            for event in parser.push(&chunk) {
+                if event.event != STATUS_EVENT {
+                    continue;
+                }
+                match serde_json::from_str::<ClockStatus>(&event.data) {
+                    Ok(status) => apply(&app_handle, &published, &status),
+                    Err(e) => log::warn!("Ignoring malformed clock status event: {}", e),
+                }
+            }
+
+            if state.get_session_token().is_none() {
+                break;
+            }
+        }
+
+        published.stream_open.store(false, Ordering::Relaxed);
+        log::debug!("Clock status stream closed");
+        tokio::time::sleep(retry).await;
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct PushEvent {
+    event: String,
+    data: String,
+}
+
+/// Splits a `text/event-stream` body into events, across chunk boundaries
+#[derive(Default)]
+struct EventParser {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl EventParser {
+    fn push(&mut self, chunk: &[u8]) -> Vec<PushEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            // A blank line ends the event; lines starting with `:` are keep-alives
+            if line.is_empty() {
+                let event = self.event.take();
+                if !self.data.is_empty() {
+                    events.push(PushEvent {
+                        event: event.unwrap_or_else(|| "message".to_string()),
+                        data: self.data.join("\n"),
+                    });
+                    self.data.clear();
+                }
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.event = Some(value.to_string()),
+                "data" => self.data.push(value.to_string()),
+                _ => {}
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{poll_interval, EventParser, PushEvent, HIDDEN_INTERVAL, VISIBLE_INTERVAL};
+    use crate::api::testing::fake_client;
+    use crate::clock::ClockService;
+
+    #[test]
+    fn polls_faster_with_the_window_open_and_slower_with_push() {
+        assert_eq!(poll_interval(true, false, false), VISIBLE_INTERVAL);
+        assert_eq!(poll_interval(false, false, false), HIDDEN_INTERVAL);
+        assert!(poll_interval(false, true, false) > HIDDEN_INTERVAL);
+        assert!(poll_interval(true, false, true) > HIDDEN_INTERVAL);
+    }
+
+    #[test]
+    fn parses_events_split_across_chunks() {
+        let mut parser = EventParser::default();
+        assert!(parser.push(b": keep-alive\n\nevent: clock_st").is_empty());
+        assert_eq!(
+            parser.push(b"atus\r\ndata: {\"isClockedIn\":\ndata: true}\r\n\r\ndata: hi\n\n"),
+            vec![
+                PushEvent {
+                    event: "clock_status".to_string(),
+                    data: "{\"isClockedIn\":\ntrue}".to_string(),
+                },
+                PushEvent {
+                    event: "message".to_string(),
+                    data: "hi".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_polling_without_a_stream_endpoint() {
+        let (client, transport) = fake_client();
+        transport.respond(404, serde_json::json!({ "error": "Not found" }));
+        transport.respond_text(
+            200,
+            "event: clock_status\ndata: {\"hasEmployee\":true,\"employeeId\":\"e1\",\"isClockedIn\":false,\"activeWorkPeriod\":null}\n\n",
+        );
+
+        let service = ClockService::new(client);
+        assert!(service.status_stream().await.unwrap().is_none());
+
+        let mut stream = service.status_stream().await.unwrap().unwrap();
+        let chunk = stream.next_chunk().await.unwrap().unwrap();
+        let events = EventParser::default().push(&chunk);
+        assert_eq!(events[0].event, "clock_status");
+        assert_eq!(
+            transport.requests()[1].header("Accept"),
+            Some("text/event-stream")
+        );
+    }
+}
@@ -0,0 +1,406 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::api::Z8ApiClient;
+use crate::clock::{ClockService, TimeEntry};
+use crate::offline::QueuedAction;
+
+const CLOCK_IN: &str = "clock_in";
+const CLOCK_OUT: &str = "clock_out";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEntry {
+    /// Server id; `None` for punches still waiting in the offline queue
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    pub timestamp: DateTime<Utc>,
+    pub notes: Option<String>,
+    pub pending: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayTimeline {
+    pub date: NaiveDate,
+    pub entries: Vec<TimelineEntry>,
+    pub worked_minutes: i64,
+    pub break_minutes: i64,
+    pub is_clocked_in: bool,
+    /// Minutes since the last clock-in while a period is running
+    pub current_session_minutes: Option<i64>,
+    pub pending_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayTotal {
+    pub date: NaiveDate,
+    pub worked_minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeekSummary {
+    pub week_start: NaiveDate,
+    pub days: Vec<DayTotal>,
+    pub worked_minutes: i64,
+    pub target_minutes: i64,
+    /// Positive when ahead of the target, negative when behind
+    pub difference_minutes: i64,
+    pub pending_count: usize,
+}
+
+type Interval = (DateTime<Utc>, DateTime<Utc>);
+
+/// Work and break intervals reconstructed from a sorted list of punches
+#[derive(Debug, Default)]
+struct Intervals {
+    work: Vec<Interval>,
+    breaks: Vec<Interval>,
+    is_open: bool,
+}
+
+pub struct TimelineService {
+    clock_service: ClockService,
+}
+
+impl TimelineService {
+    pub fn new(api: Z8ApiClient) -> Self {
+        Self {
+            clock_service: ClockService::new(api),
+        }
+    }
+
+    /// Today's punches with worked and break time, including punches not yet synced
+    pub async fn today(&self, queued: &[QueuedAction]) -> Result<DayTimeline> {
+        let now = Utc::now();
+        let (_, start, end) = today_range(now);
+
+        let server_entries = self.clock_service.list_entries(start, end).await?;
+        Ok(build_today(server_entries, queued, now))
+    }
+
+    /// Worked time per day for the current week (Monday to Sunday) against the target
+    pub async fn week(&self, queued: &[QueuedAction], target_minutes: i64) -> Result<WeekSummary> {
+        let now = Utc::now();
+        let week_start = week_start(now);
+        let (start, _) = day_bounds(&Local, week_start);
+        let (_, end) = day_bounds(&Local, week_start + Days::new(6));
+
+        let server_entries = self.clock_service.list_entries(start, end).await?;
+        let entries = merge_entries(server_entries, queued, start, end);
+
+        Ok(week_summary(
+            &Local,
+            week_start,
+            &entries,
+            start,
+            now,
+            target_minutes,
+        ))
+    }
+}
+
+/// The Monday of the local week containing `now`
+pub fn week_start(now: DateTime<Utc>) -> NaiveDate {
+    let today = now.with_timezone(&Local).date_naive();
+    today - Days::new(today.weekday().num_days_from_monday() as u64)
+}
+
+/// The local calendar day containing `now` and its bounds as UTC instants
+pub fn today_range(now: DateTime<Utc>) -> (NaiveDate, DateTime<Utc>, DateTime<Utc>) {
+    let today = now.with_timezone(&Local).date_naive();
+    let (start, end) = day_bounds(&Local, today);
+    (today, start, end)
+}
+
+/// Builds today's timeline from already fetched server entries, e.g. a cached copy while offline
+pub fn build_today(
+    server_entries: Vec<TimeEntry>,
+    queued: &[QueuedAction],
+    now: DateTime<Utc>,
+) -> DayTimeline {
+    let (today, start, end) = today_range(now);
+    let entries = merge_entries(server_entries, queued, start, end);
+    day_timeline(today, entries, start, end, now)
+}
+
+/// Combines server entries with queued punches in the range, ordered by time
+fn merge_entries(
+    server_entries: Vec<TimeEntry>,
+    queued: &[QueuedAction],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<TimelineEntry> {
+    let mut entries: Vec<TimelineEntry> = server_entries
+        .into_iter()
+        .filter_map(|entry| {
+            let timestamp = DateTime::parse_from_rfc3339(&entry.timestamp).ok()?;
+            Some(TimelineEntry {
+                id: Some(entry.id),
+                entry_type: entry.entry_type,
+                timestamp: timestamp.with_timezone(&Utc),
+                notes: entry.notes,
+                pending: false,
+            })
+        })
+        .collect();
+
+    entries.extend(
+        queued
+            .iter()
+            .flat_map(QueuedAction::punches)
+            .filter(|(_, timestamp)| *timestamp >= start && *timestamp < end)
+            .map(|(entry_type, timestamp)| TimelineEntry {
+                id: None,
+                entry_type: entry_type.to_string(),
+                timestamp,
+                notes: None,
+                pending: true,
+            }),
+    );
+
+    // A switch records its clock-out and clock-in at the same instant; keep that order
+    entries.sort_by_key(|entry| (entry.timestamp, entry.entry_type == CLOCK_IN));
+    entries
+}
+
+/// Pairs punches into work and break intervals. A period already running when the
+/// range starts is counted from `start`, and one still running is counted up to `now`.
+fn intervals(entries: &[TimelineEntry], start: DateTime<Utc>, now: DateTime<Utc>) -> Intervals {
+    let mut result = Intervals::default();
+    let mut open_since: Option<DateTime<Utc>> = None;
+    let mut last_clock_out: Option<DateTime<Utc>> = None;
+
+    for entry in entries {
+        match entry.entry_type.as_str() {
+            CLOCK_IN if open_since.is_none() => {
+                if let Some(clock_out) = last_clock_out.take() {
+                    result.breaks.push((clock_out, entry.timestamp));
+                }
+                open_since = Some(entry.timestamp);
+            }
+            CLOCK_OUT => {
+                let since = match open_since.take() {
+                    Some(since) => since,
+                    None if result.work.is_empty() && last_clock_out.is_none() => start,
+                    None => continue,
+                };
+                result.work.push((since, entry.timestamp));
+                last_clock_out = Some(entry.timestamp);
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(since) = open_since {
+        result.work.push((since, now.max(since)));
+        result.is_open = true;
+    }
+
+    result
+}
+
+fn overlap_minutes(intervals: &[Interval], start: DateTime<Utc>, end: DateTime<Utc>) -> i64 {
+    intervals
+        .iter()
+        .map(|(from, to)| {
+            let from = (*from).max(start);
+            let to = (*to).min(end);
+            (to - from).num_seconds().max(0)
+        })
+        .sum::<i64>()
+        / 60
+}
+
+fn day_timeline(
+    date: NaiveDate,
+    entries: Vec<TimelineEntry>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> DayTimeline {
+    let intervals = intervals(&entries, start, now);
+
+    DayTimeline {
+        date,
+        worked_minutes: overlap_minutes(&intervals.work, start, end),
+        break_minutes: overlap_minutes(&intervals.breaks, start, end),
+        is_clocked_in: intervals.is_open,
+        current_session_minutes: intervals
+            .work
+            .last()
+            .filter(|_| intervals.is_open)
+            .map(|(since, _)| (now - *since).num_minutes()),
+        pending_count: entries.iter().filter(|entry| entry.pending).count(),
+        entries,
+    }
+}
+
+fn week_summary<Tz: TimeZone>(
+    tz: &Tz,
+    week_start: NaiveDate,
+    entries: &[TimelineEntry],
+    start: DateTime<Utc>,
+    now: DateTime<Utc>,
+    target_minutes: i64,
+) -> WeekSummary {
+    let intervals = intervals(entries, start, now);
+
+    let days: Vec<DayTotal> = (0..7)
+        .map(|offset| {
+            let date = week_start + Days::new(offset);
+            let (day_start, day_end) = day_bounds(tz, date);
+            DayTotal {
+                date,
+                worked_minutes: overlap_minutes(&intervals.work, day_start, day_end),
+            }
+        })
+        .collect();
+    let worked_minutes = days.iter().map(|day| day.worked_minutes).sum();
+
+    WeekSummary {
+        week_start,
+        days,
+        worked_minutes,
+        target_minutes,
+        difference_minutes: worked_minutes - target_minutes,
+        pending_count: entries.iter().filter(|entry| entry.pending).count(),
+    }
+}
+
+/// Start and end of a calendar day in the given timezone, as UTC instants
+fn day_bounds<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let local_midnight = |date: NaiveDate| {
+        let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+        tz.from_local_datetime(&midnight)
+            .earliest()
+            .map(|time| time.with_timezone(&Utc))
+            // Midnight skipped by a DST change; fall back to treating it as UTC
+            .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+    };
+
+    (local_midnight(date), local_midnight(date + Days::new(1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{day_bounds, day_timeline, merge_entries, week_summary};
+    use crate::clock::TimeEntry;
+    use crate::offline::{ActionType, QueuedAction};
+    use chrono::{DateTime, NaiveDate, Utc};
+
+    fn at(time: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(time)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn server_entry(id: &str, entry_type: &str, timestamp: &str) -> TimeEntry {
+        TimeEntry {
+            id: id.to_string(),
+            employee_id: "employee-1".to_string(),
+            entry_type: entry_type.to_string(),
+            timestamp: timestamp.to_string(),
+            notes: None,
+        }
+    }
+
+    fn queued(action_type: ActionType, timestamp: &str, payload: Option<&str>) -> QueuedAction {
+        QueuedAction {
+            id: 1,
+            action_type,
+            timestamp: at(timestamp).timestamp(),
+            payload: payload.map(str::to_string),
+            retry_count: 0,
+            created_at: 0,
+            chain_sequence: None,
+        }
+    }
+
+    #[test]
+    fn merges_queued_punches_into_worked_and_break_time() {
+        let date = NaiveDate::from_ymd_opt(2026, 5, 11).unwrap();
+        let (start, end) = day_bounds(&Utc, date);
+        let server_entries = vec![
+            server_entry("entry-2", "clock_out", "2026-05-11T12:00:00Z"),
+            server_entry("entry-1", "clock_in", "2026-05-11T08:00:00Z"),
+        ];
+        let queued = vec![
+            queued(ActionType::ClockIn, "2026-05-11T12:30:00Z", Some("office")),
+            queued(ActionType::ClockOut, "2026-05-11T16:00:00Z", None),
+            // Outside the day and ignored
+            queued(ActionType::ClockIn, "2026-05-12T08:00:00Z", None),
+        ];
+
+        let entries = merge_entries(server_entries, &queued, start, end);
+        let timeline = day_timeline(date, entries, start, end, at("2026-05-11T18:00:00Z"));
+
+        assert_eq!(timeline.entries.len(), 4);
+        assert_eq!(timeline.entries[0].id.as_deref(), Some("entry-1"));
+        assert_eq!(timeline.worked_minutes, 7 * 60 + 30);
+        assert_eq!(timeline.break_minutes, 30);
+        assert_eq!(timeline.pending_count, 2);
+        assert!(!timeline.is_clocked_in);
+        assert_eq!(timeline.current_session_minutes, None);
+    }
+
+    #[test]
+    fn counts_periods_running_across_midnight_and_until_now() {
+        let date = NaiveDate::from_ymd_opt(2026, 5, 12).unwrap();
+        let (start, end) = day_bounds(&Utc, date);
+        let entries = merge_entries(
+            vec![
+                server_entry("entry-1", "clock_out", "2026-05-12T02:00:00Z"),
+                server_entry("entry-2", "clock_in", "2026-05-12T09:00:00Z"),
+            ],
+            &[],
+            start,
+            end,
+        );
+
+        let timeline = day_timeline(date, entries, start, end, at("2026-05-12T10:15:00Z"));
+
+        assert_eq!(timeline.worked_minutes, 2 * 60 + 75);
+        assert_eq!(timeline.break_minutes, 7 * 60);
+        assert!(timeline.is_clocked_in);
+        assert_eq!(timeline.current_session_minutes, Some(75));
+    }
+
+    #[test]
+    fn summarises_the_week_against_the_target() {
+        let week_start = NaiveDate::from_ymd_opt(2026, 5, 11).unwrap();
+        let (start, _) = day_bounds(&Utc, week_start);
+        let (_, end) = day_bounds(&Utc, NaiveDate::from_ymd_opt(2026, 5, 17).unwrap());
+        let switch = queued(ActionType::SwitchProject, "2026-05-12T12:00:00Z", None);
+        let entries = merge_entries(
+            vec![
+                server_entry("entry-1", "clock_in", "2026-05-11T09:00:00Z"),
+                server_entry("entry-2", "clock_out", "2026-05-11T17:00:00Z"),
+                server_entry("entry-3", "clock_in", "2026-05-12T09:00:00Z"),
+            ],
+            &[switch],
+            start,
+            end,
+        );
+
+        let summary = week_summary(
+            &Utc,
+            week_start,
+            &entries,
+            start,
+            at("2026-05-12T15:00:00Z"),
+            40 * 60,
+        );
+
+        assert_eq!(summary.days.len(), 7);
+        assert_eq!(summary.days[0].worked_minutes, 8 * 60);
+        assert_eq!(summary.days[1].worked_minutes, 6 * 60);
+        assert_eq!(summary.worked_minutes, 14 * 60);
+        assert_eq!(summary.difference_minutes, -26 * 60);
+        assert_eq!(summary.pending_count, 2);
+    }
+}
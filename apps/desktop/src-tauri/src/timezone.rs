@@ -0,0 +1,59 @@
+use chrono::{DateTime, Offset, Utc};
+use chrono_tz::Tz;
+
+/// The device's IANA timezone, or `None` when the OS reports a zone that isn't in the tz database
+pub fn device_timezone() -> Option<Tz> {
+    let name = match iana_time_zone::get_timezone() {
+        Ok(name) => name,
+        Err(e) => {
+            log::warn!("Cannot determine device timezone: {}", e);
+            return None;
+        }
+    };
+
+    match name.parse::<Tz>() {
+        Ok(zone) => Some(zone),
+        Err(_) => {
+            log::warn!("Device timezone {} is not a known IANA zone", name);
+            None
+        }
+    }
+}
+
+/// Offset from UTC in minutes that `zone` observes at `instant`
+pub fn utc_offset_minutes(zone: Tz, instant: DateTime<Utc>) -> i32 {
+    instant
+        .with_timezone(&zone)
+        .offset()
+        .fix()
+        .local_minus_utc()
+        / 60
+}
+
+#[cfg(test)]
+mod tests {
+    use super::utc_offset_minutes;
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn offset_follows_daylight_saving_at_the_instant() {
+        let at = |time: &str| {
+            DateTime::parse_from_rfc3339(time)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        assert_eq!(
+            utc_offset_minutes(chrono_tz::Europe::Berlin, at("2026-01-15T12:00:00Z")),
+            60
+        );
+        assert_eq!(
+            utc_offset_minutes(chrono_tz::Europe::Berlin, at("2026-07-15T12:00:00Z")),
+            120
+        );
+        assert_eq!(
+            utc_offset_minutes(chrono_tz::America::St_Johns, at("2026-01-15T12:00:00Z")),
+            -210
+        );
+    }
+}
@@ -1,18 +1,103 @@
 use anyhow::Result;
 use tauri::{
     include_image,
-    menu::{Menu, MenuItem},
+    menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    App, AppHandle, Emitter, Manager,
+    App, AppHandle, Emitter, Manager, Wry,
 };
 
+use crate::approvals::{self, ApprovalItem, MENU_APPROVE_PREFIX, MENU_REJECT_PREFIX};
+use crate::wellness;
+
+/// Pending approvals listed individually in the tray; the rest are in the window
+const TRAY_APPROVAL_ITEMS: usize = 5;
+
+/// Builds the tray menu, with an approvals submenu while requests are waiting
+fn build_menu<M: Manager<Wry>>(
+    manager: &M,
+    pending_approvals: u32,
+    approvals: &[ApprovalItem],
+) -> tauri::Result<Menu<Wry>> {
+    let show = MenuItem::with_id(manager, "show", "Show Window", true, None::<&str>)?;
+    let switch_project = MenuItem::with_id(
+        manager,
+        "switch_project",
+        "Switch Project…",
+        true,
+        None::<&str>,
+    )?;
+    let log_water = MenuItem::with_id(manager, "log_water", "Log Water", true, None::<&str>)?;
+    let settings = MenuItem::with_id(manager, "settings", "Settings", true, None::<&str>)?;
+    let quit = MenuItem::with_id(manager, "quit", "Quit", true, None::<&str>)?;
+
+    if pending_approvals == 0 {
+        return Menu::with_items(
+            manager,
+            &[&show, &switch_project, &log_water, &settings, &quit],
+        );
+    }
+
+    let mut approval_menus = Vec::new();
+    for item in approvals.iter().take(TRAY_APPROVAL_ITEMS) {
+        // Requests that need reconciliation can only be decided in the webapp
+        let actionable = item.is_actionable.unwrap_or(true);
+        let approve = MenuItem::with_id(
+            manager,
+            format!("{}{}", MENU_APPROVE_PREFIX, item.id),
+            "Approve",
+            actionable,
+            None::<&str>,
+        )?;
+        let reject = MenuItem::with_id(
+            manager,
+            format!("{}{}", MENU_REJECT_PREFIX, item.id),
+            "Reject…",
+            actionable,
+            None::<&str>,
+        )?;
+        approval_menus.push(Submenu::with_items(
+            manager,
+            item.label(),
+            true,
+            &[&approve, &reject],
+        )?);
+    }
+
+    let open_inbox =
+        MenuItem::with_id(manager, "approvals", "Open Approvals…", true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(manager)?;
+    let mut approval_entries: Vec<&dyn IsMenuItem<Wry>> = approval_menus
+        .iter()
+        .map(|submenu| submenu as &dyn IsMenuItem<Wry>)
+        .collect();
+    if !approval_entries.is_empty() {
+        approval_entries.push(&separator);
+    }
+    approval_entries.push(&open_inbox);
+
+    let approvals_menu = Submenu::with_items(
+        manager,
+        format!("Approvals ({})", pending_approvals),
+        true,
+        &approval_entries,
+    )?;
+
+    Menu::with_items(
+        manager,
+        &[
+            &show,
+            &switch_project,
+            &log_water,
+            &approvals_menu,
+            &settings,
+            &quit,
+        ],
+    )
+}
+
 /// Sets up the system tray icon and menu
 pub fn setup_tray(app: &App) -> Result<()> {
-    let show = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
-    let settings = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-    let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-
-    let menu = Menu::with_items(app, &[&show, &settings, &quit])?;
+    let menu = build_menu(app, 0, &[])?;
 
     let tray = TrayIconBuilder::new()
         .icon(include_image!("icons/tray-gray.png"))
@@ -40,6 +125,15 @@ pub fn setup_tray(app: &App) -> Result<()> {
                     let _ = window.set_focus();
                 }
             }
+            "switch_project" => open_switch_project(app),
+            "log_water" => wellness::log_from_tray(app),
+            "approvals" => {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+                let _ = app.emit("open_approvals", ());
+            }
             "settings" => {
                 // Emit settings event to frontend
                 let _ = app.emit("open_settings", ());
@@ -47,7 +141,7 @@ pub fn setup_tray(app: &App) -> Result<()> {
             "quit" => {
                 app.exit(0);
             }
-            _ => {}
+            id => approvals::handle_menu_event(app, id),
         })
         .build(app)?;
 
@@ -58,6 +152,15 @@ pub fn setup_tray(app: &App) -> Result<()> {
     Ok(())
 }
 
+/// Brings the window forward with the project picker open
+pub fn open_switch_project(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    let _ = app_handle.emit("open_switch_project", ());
+}
+
 /// Updates the tray icon based on clock status
 pub fn update_tray_icon(app_handle: &AppHandle, is_clocked_in: bool) -> Result<()> {
     // Use compile-time embedded icons
@@ -75,3 +178,22 @@ pub fn update_tray_icon(app_handle: &AppHandle, is_clocked_in: bool) -> Result<(
 
     Ok(())
 }
+
+/// Shows the number of waiting approvals in the tray menu and tooltip
+pub fn update_approvals(
+    app_handle: &AppHandle,
+    pending_approvals: u32,
+    approvals: &[ApprovalItem],
+) -> Result<()> {
+    if let Some(tray) = app_handle.try_state::<tauri::tray::TrayIcon>() {
+        tray.set_menu(Some(build_menu(app_handle, pending_approvals, approvals)?))?;
+        let tooltip = match pending_approvals {
+            0 => "z8 Timer".to_string(),
+            1 => "z8 Timer – 1 approval waiting".to_string(),
+            count => format!("z8 Timer – {} approvals waiting", count),
+        };
+        tray.set_tooltip(Some(tooltip))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,714 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::clock::{PunchCapture, PunchDetails, WorkLocationType};
+use crate::journal::{self, EventSource, JournalEvent, JournalKind, JournalOutcome};
+use crate::location::{self, LocationDetection, LocationSignal, NetworkSignals};
+use crate::notify;
+use crate::offline::{ActionType, ClockInPayload, ClockOutPayload};
+use crate::state::AppState;
+use crate::tray;
+
+const NETWORK_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Fired triggers kept so they can still be confirmed or undone
+const RECENT_TRIGGERS: usize = 10;
+
+/// What a rule reacts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TriggerEvent {
+    /// Keyboard or mouse input; fires at most once per day
+    Activity,
+    /// No input for the idle threshold, which includes a locked session
+    Idle,
+    /// The network condition started to match
+    NetworkJoined,
+    /// The network condition stopped matching, e.g. the VPN dropped
+    NetworkLeft,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TriggerAction {
+    ClockIn,
+    ClockOut,
+    /// Clocks out but keeps the project for the next clock-in
+    Pause,
+}
+
+impl TriggerAction {
+    fn needs_clocked_in(self) -> bool {
+        !matches!(self, Self::ClockIn)
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::ClockIn => "clock_in",
+            Self::ClockOut => "clock_out",
+            Self::Pause => "pause",
+        }
+    }
+
+    /// Notification titles for a performed and a proposed punch
+    fn titles(self) -> (&'static str, &'static str) {
+        match self {
+            Self::ClockIn => ("Clocked in automatically", "Clock in?"),
+            Self::ClockOut => ("Clocked out automatically", "Clock out?"),
+            Self::Pause => ("Paused automatically", "Pause?"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TriggerMode {
+    /// Asks before punching
+    Propose,
+    /// Punches right away and offers an undo
+    Perform,
+}
+
+/// A network signal that must match, reusing the work location signals
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkCondition {
+    pub signal: LocationSignal,
+    pub value: String,
+}
+
+impl NetworkCondition {
+    fn matches(&self, signals: &NetworkSignals) -> bool {
+        location::signal_matches(self.signal, &self.value, signals).is_some()
+    }
+
+    fn describe(&self) -> String {
+        format!("{} \"{}\"", self.signal.label(), self.value.trim())
+    }
+}
+
+/// Punches automatically (or proposes to) when a device or network event happens
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerRule {
+    pub event: TriggerEvent,
+    /// Required for network events; for the others the device must also be on this network
+    #[serde(default)]
+    pub network: Option<NetworkCondition>,
+    /// Local time (`HH:MM`) from which the rule applies
+    #[serde(default)]
+    pub after: Option<String>,
+    /// Local time (`HH:MM`) until which the rule applies
+    #[serde(default)]
+    pub before: Option<String>,
+    pub action: TriggerAction,
+    pub mode: TriggerMode,
+}
+
+/// Something the idle monitor or the network watcher noticed
+#[derive(Debug, Clone)]
+pub enum Observation {
+    Activity,
+    Idle,
+    /// The network changed; the new signals are already current
+    Network {
+        previous: NetworkSignals,
+    },
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+impl TriggerRule {
+    /// Checks the times and that network events name a network
+    pub fn validate(&self) -> Result<()> {
+        for time in [&self.after, &self.before].into_iter().flatten() {
+            if parse_time(time).is_none() {
+                return Err(anyhow!(
+                    "Trigger times must look like 07:00, got \"{}\"",
+                    time
+                ));
+            }
+        }
+
+        let has_network = self
+            .network
+            .as_ref()
+            .is_some_and(|condition| !condition.value.trim().is_empty());
+        if matches!(
+            self.event,
+            TriggerEvent::NetworkJoined | TriggerEvent::NetworkLeft
+        ) && !has_network
+        {
+            return Err(anyhow!("Network triggers need a network to watch"));
+        }
+        Ok(())
+    }
+
+    fn applies(
+        &self,
+        observation: &Observation,
+        signals: &NetworkSignals,
+        is_clocked_in: bool,
+        time: NaiveTime,
+    ) -> bool {
+        if self.action.needs_clocked_in() != is_clocked_in {
+            return false;
+        }
+
+        let after = self.after.as_deref().and_then(parse_time);
+        let before = self.before.as_deref().and_then(parse_time);
+        if after.is_some_and(|after| time < after) || before.is_some_and(|before| time >= before) {
+            return false;
+        }
+
+        match (self.event, observation) {
+            (TriggerEvent::Activity, Observation::Activity)
+            | (TriggerEvent::Idle, Observation::Idle) => self
+                .network
+                .as_ref()
+                .is_none_or(|condition| condition.matches(signals)),
+            (TriggerEvent::NetworkJoined, Observation::Network { previous }) => {
+                self.network.as_ref().is_some_and(|condition| {
+                    condition.matches(signals) && !condition.matches(previous)
+                })
+            }
+            (TriggerEvent::NetworkLeft, Observation::Network { previous }) => {
+                self.network.as_ref().is_some_and(|condition| {
+                    !condition.matches(signals) && condition.matches(previous)
+                })
+            }
+            _ => false,
+        }
+    }
+
+    /// Why the rule fired, e.g. `First activity after 07:00 on Wi-Fi "Office"`
+    fn reason(&self) -> String {
+        let network = self
+            .network
+            .as_ref()
+            .map(NetworkCondition::describe)
+            .unwrap_or_default();
+        let mut reason = match self.event {
+            TriggerEvent::Activity => "First activity".to_string(),
+            TriggerEvent::Idle => "Away from the computer".to_string(),
+            TriggerEvent::NetworkJoined => format!("Connected to {}", network),
+            TriggerEvent::NetworkLeft => format!("Disconnected from {}", network),
+        };
+
+        if let Some(after) = &self.after {
+            reason.push_str(&format!(" after {}", after.trim()));
+        }
+        if let Some(before) = &self.before {
+            reason.push_str(&format!(" before {}", before.trim()));
+        }
+        if matches!(self.event, TriggerEvent::Activity | TriggerEvent::Idle)
+            && self.network.is_some()
+        {
+            reason.push_str(&format!(" on {}", network));
+        }
+        reason
+    }
+}
+
+/// A rule that fired, shown to the user to confirm or undo
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FiredTrigger {
+    pub id: u64,
+    pub action: TriggerAction,
+    pub mode: TriggerMode,
+    pub reason: String,
+    /// Time of the punch; for idle rules, when the last input was seen
+    pub at: DateTime<Utc>,
+    /// Proposals are only punched once confirmed
+    pub performed: bool,
+    #[serde(skip)]
+    queued_id: Option<i64>,
+    /// Project selection before the punch, restored on undo
+    #[serde(skip)]
+    previous_details: PunchDetails,
+}
+
+impl FiredTrigger {
+    /// The punch that reverts this one after it was sent, with the project it
+    /// applies to: the running one after a clock-in, the previous one after a clock-out
+    fn opposite(&self, active: &PunchDetails) -> (TriggerAction, PunchDetails) {
+        match self.action {
+            TriggerAction::ClockIn => (TriggerAction::ClockOut, active.clone()),
+            TriggerAction::ClockOut | TriggerAction::Pause => {
+                (TriggerAction::ClockIn, self.previous_details.clone())
+            }
+        }
+    }
+}
+
+/// Rule bookkeeping shared by the idle monitor, the network watcher and the commands
+#[derive(Debug, Default)]
+pub struct TriggerState {
+    /// `None` until the network was read once
+    signals: Option<NetworkSignals>,
+    /// Activity rules that already fired, with the local day they fired on
+    activity_fired: Vec<(TriggerRule, NaiveDate)>,
+    recent: Vec<FiredTrigger>,
+    next_id: u64,
+}
+
+impl TriggerState {
+    /// Returns the first rule that applies, leaving out activity rules that already
+    /// fired today
+    fn select<Tz: TimeZone>(
+        &mut self,
+        rules: &[TriggerRule],
+        observation: &Observation,
+        is_clocked_in: bool,
+        zone: &Tz,
+        at: DateTime<Utc>,
+    ) -> Option<TriggerRule> {
+        let signals = self.signals.clone().unwrap_or_default();
+        let local = at.with_timezone(zone);
+        let today = local.date_naive();
+        self.activity_fired.retain(|(_, day)| *day == today);
+
+        let rule = rules
+            .iter()
+            .filter(|rule| !self.activity_fired.iter().any(|(fired, _)| fired == *rule))
+            .find(|rule| rule.applies(observation, &signals, is_clocked_in, local.time()))?
+            .clone();
+        Some(rule)
+    }
+
+    /// Remembers an activity rule for the rest of the day once its punch was queued
+    /// or proposed; one that failed is tried again on the next activity
+    fn fired<Tz: TimeZone>(&mut self, rule: &TriggerRule, zone: &Tz, at: DateTime<Utc>) {
+        if rule.event == TriggerEvent::Activity {
+            let today = at.with_timezone(zone).date_naive();
+            self.activity_fired.push((rule.clone(), today));
+        }
+    }
+
+    fn remember(&mut self, rule: &TriggerRule, at: DateTime<Utc>) -> FiredTrigger {
+        self.next_id += 1;
+        let trigger = FiredTrigger {
+            id: self.next_id,
+            action: rule.action,
+            mode: rule.mode,
+            reason: rule.reason(),
+            at,
+            performed: false,
+            queued_id: None,
+            previous_details: PunchDetails::default(),
+        };
+
+        self.recent.push(trigger.clone());
+        if self.recent.len() > RECENT_TRIGGERS {
+            self.recent.remove(0);
+        }
+        trigger
+    }
+
+    fn update(&mut self, trigger: &FiredTrigger) {
+        if let Some(existing) = self
+            .recent
+            .iter_mut()
+            .find(|recent| recent.id == trigger.id)
+        {
+            *existing = trigger.clone();
+        }
+    }
+
+    fn take(&mut self, id: u64) -> Option<FiredTrigger> {
+        let index = self.recent.iter().position(|recent| recent.id == id)?;
+        Some(self.recent.remove(index))
+    }
+}
+
+/// Evaluates the rules against an observation and punches or proposes.
+/// Called from the idle monitor thread, so everything here is synchronous.
+pub fn observe(app_handle: &AppHandle, observation: Observation, at: DateTime<Utc>) {
+    let state = app_handle.state::<Arc<AppState>>();
+    if state.get_session_token().is_none() {
+        return;
+    }
+
+    let rules = state.settings.read().trigger_rules.clone();
+    if rules.is_empty() {
+        return;
+    }
+
+    let (rule, trigger) = {
+        let mut triggers = state.triggers.lock();
+        let Some(rule) = triggers.select(&rules, &observation, state.is_clocked_in(), &Local, at)
+        else {
+            return;
+        };
+        let trigger = triggers.remember(&rule, at);
+        (rule, trigger)
+    };
+    log::info!("Trigger fired: {:?} ({})", trigger.action, trigger.reason);
+
+    let (performed_title, proposed_title) = trigger.action.titles();
+    match trigger.mode {
+        TriggerMode::Propose => {
+            if let Err(e) = app_handle.emit("trigger_proposed", &trigger) {
+                log::error!("Failed to emit trigger proposal: {}", e);
+                return;
+            }
+            notify::send(
+                app_handle,
+                proposed_title,
+                &format!("{}. Open z8 to confirm.", trigger.reason),
+            );
+        }
+        TriggerMode::Perform => {
+            if let Err(e) = perform(app_handle, trigger.id) {
+                log::error!("Failed to perform automatic punch: {}", e);
+                return;
+            }
+            notify::send(
+                app_handle,
+                performed_title,
+                &format!("{}. Open z8 to undo.", trigger.reason),
+            );
+        }
+    }
+    state.triggers.lock().fired(&rule, &Local, at);
+}
+
+/// Queues the punch of a fired trigger and wakes the queue processor to send it
+pub fn perform(app_handle: &AppHandle, id: u64) -> Result<FiredTrigger> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let mut trigger = state
+        .triggers
+        .lock()
+        .recent
+        .iter()
+        .find(|recent| recent.id == id)
+        .cloned()
+        .ok_or_else(|| anyhow!("This automatic punch has expired"))?;
+    if trigger.performed {
+        return Ok(trigger);
+    }
+    if trigger.action.needs_clocked_in() != state.is_clocked_in() {
+        return Err(anyhow!("The clock status changed in the meantime"));
+    }
+
+    // Proposals punch at the time they were confirmed
+    if trigger.mode == TriggerMode::Propose {
+        trigger.at = Utc::now();
+    }
+    trigger.previous_details = state.active_punch_details();
+    trigger.queued_id = Some(enqueue_punch(
+        &state,
+        trigger.action,
+        trigger.previous_details.clone(),
+        trigger.at,
+    )?);
+    trigger.performed = true;
+    journal::record(
+        &state,
+        JournalEvent::new(
+            JournalKind::Punch,
+            EventSource::Rule,
+            trigger.action.as_str(),
+            JournalOutcome::Queued,
+        )
+        .message(trigger.reason.clone())
+        .details(serde_json::json!({
+            "at": trigger.at,
+            "mode": trigger.mode,
+            "queuedId": trigger.queued_id,
+        })),
+    );
+
+    match trigger.action {
+        TriggerAction::ClockIn => state.set_clocked_in(true),
+        TriggerAction::ClockOut => {
+            state.set_active_punch_details(PunchDetails::default());
+            state.set_clocked_in(false);
+        }
+        TriggerAction::Pause => state.set_clocked_in(false),
+    }
+    let _ = tray::update_tray_icon(app_handle, state.is_clocked_in());
+
+    state.triggers.lock().update(&trigger);
+    if let Err(e) = app_handle.emit("trigger_performed", &trigger) {
+        log::error!("Failed to emit automatic punch: {}", e);
+    }
+    Ok(trigger)
+}
+
+/// Reverts an automatic punch: drops it if it is still queued, otherwise
+/// queues the opposite punch
+pub fn undo(app_handle: &AppHandle, id: u64) -> Result<()> {
+    let state = app_handle.state::<Arc<AppState>>();
+    let trigger = state
+        .triggers
+        .lock()
+        .take(id)
+        .ok_or_else(|| anyhow!("This automatic punch can no longer be undone"))?;
+    let Some(queued_id) = trigger.queued_id.filter(|_| trigger.performed) else {
+        return Ok(()); // A proposal that was never confirmed
+    };
+
+    let was_queued = state.offline_queue.lock().remove(queued_id)?;
+    if !was_queued {
+        let (opposite, details) = trigger.opposite(&state.active_punch_details());
+        enqueue_punch(&state, opposite, details, Utc::now())?;
+    }
+    journal::record(
+        &state,
+        JournalEvent::new(
+            JournalKind::Punch,
+            EventSource::Ui,
+            &format!("undo_{}", trigger.action.as_str()),
+            if was_queued {
+                JournalOutcome::Noted
+            } else {
+                JournalOutcome::Queued
+            },
+        )
+        .message(if was_queued {
+            "Removed the automatic punch from the offline queue"
+        } else {
+            "Queued the opposite punch"
+        })
+        .details(serde_json::json!({ "at": trigger.at, "reason": trigger.reason })),
+    );
+
+    state.set_active_punch_details(trigger.previous_details);
+    state.set_clocked_in(trigger.action.needs_clocked_in());
+    let _ = tray::update_tray_icon(app_handle, state.is_clocked_in());
+    log::info!(
+        "Undid automatic {:?} ({})",
+        trigger.action,
+        if was_queued {
+            "removed from queue"
+        } else {
+            "reverted"
+        }
+    );
+    Ok(())
+}
+
+/// Drops a proposal the user declined
+pub fn dismiss(app_handle: &AppHandle, id: u64) {
+    let state = app_handle.state::<Arc<AppState>>();
+    state.triggers.lock().take(id);
+}
+
+fn enqueue_punch(
+    state: &AppState,
+    action: TriggerAction,
+    details: PunchDetails,
+    at: DateTime<Utc>,
+) -> Result<i64> {
+    let capture = PunchCapture::at(at);
+
+    let (action_type, payload) = match action {
+        TriggerAction::ClockIn => {
+            let signals = state.triggers.lock().signals.clone().unwrap_or_default();
+            let mut rules = state.settings.read().location_rules.clone();
+            rules.extend(location::managed_rules());
+            let suggestion = location::suggest(&rules, &signals);
+            let work_location_type = suggestion
+                .as_ref()
+                .map_or(WorkLocationType::Office, |suggestion| suggestion.location);
+
+            let payload = ClockInPayload {
+                work_location_type: work_location_type.as_str().to_string(),
+                details,
+                capture: Some(capture),
+                detection: suggestion.map(|suggestion| LocationDetection::Detected { suggestion }),
+            };
+            (ActionType::ClockIn, serde_json::to_string(&payload)?)
+        }
+        TriggerAction::ClockOut | TriggerAction::Pause => {
+            let payload = ClockOutPayload {
+                details,
+                capture: Some(capture),
+            };
+            (ActionType::ClockOut, serde_json::to_string(&payload)?)
+        }
+    };
+
+    let id = state
+        .offline_queue
+        .lock()
+        .enqueue(action_type, at.timestamp(), Some(payload))?;
+    // Sent right away, so a manual punch soon after can't overtake it
+    state.process_queue_soon();
+    Ok(id)
+}
+
+/// Watches the network for changes that network rules react to
+pub async fn start_network_watcher(app_handle: AppHandle) {
+    log::info!("Starting network trigger watcher");
+
+    let state = app_handle.state::<Arc<AppState>>();
+
+    loop {
+        tokio::time::sleep(NETWORK_CHECK_INTERVAL).await;
+
+        if state.settings.read().trigger_rules.is_empty() {
+            continue;
+        }
+
+        let signals = match tauri::async_runtime::spawn_blocking(location::read_signals).await {
+            Ok(Ok(signals)) => signals,
+            Ok(Err(e)) => {
+                log::warn!("Failed to read network signals: {}", e);
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Network signal reader failed: {}", e);
+                continue;
+            }
+        };
+
+        let previous = state.triggers.lock().signals.replace(signals.clone());
+        match previous {
+            Some(previous) if previous != signals => {
+                log::info!("Network changed: {:?}", signals);
+                observe(&app_handle, Observation::Network { previous }, Utc::now());
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FiredTrigger, Observation, TriggerAction, TriggerMode, TriggerRule, TriggerState};
+    use crate::clock::PunchDetails;
+    use crate::location::NetworkSignals;
+    use chrono::{DateTime, Utc};
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn rule(value: serde_json::Value) -> TriggerRule {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn office_wifi() -> NetworkSignals {
+        NetworkSignals {
+            ssid: Some("Office".to_string()),
+            ..NetworkSignals::default()
+        }
+    }
+
+    #[test]
+    fn clocks_in_on_first_activity_at_the_office() {
+        let rules = vec![rule(serde_json::json!({
+            "event": "activity",
+            "network": { "signal": "wifiSsid", "value": "Office" },
+            "after": "07:00",
+            "action": "clockIn",
+            "mode": "perform"
+        }))];
+        let mut state = TriggerState {
+            signals: Some(office_wifi()),
+            ..TriggerState::default()
+        };
+        let activity = |state: &mut TriggerState, now: &str| {
+            let rule = state.select(&rules, &Observation::Activity, false, &Utc, at(now))?;
+            state.fired(&rule, &Utc, at(now));
+            Some(rule.action)
+        };
+
+        assert_eq!(activity(&mut state, "2026-10-19T06:55:00Z"), None);
+        // A rule whose punch failed is not suppressed
+        assert!(state
+            .select(
+                &rules,
+                &Observation::Activity,
+                false,
+                &Utc,
+                at("2026-10-19T07:00:30Z")
+            )
+            .is_some());
+        assert_eq!(
+            activity(&mut state, "2026-10-19T07:01:00Z"),
+            Some(TriggerAction::ClockIn)
+        );
+        // Only the first activity of the day
+        assert_eq!(activity(&mut state, "2026-10-19T13:00:00Z"), None);
+        assert_eq!(
+            activity(&mut state, "2026-10-20T07:30:00Z"),
+            Some(TriggerAction::ClockIn)
+        );
+
+        state.signals = Some(NetworkSignals::default());
+        assert_eq!(activity(&mut state, "2026-10-21T08:00:00Z"), None);
+    }
+
+    #[test]
+    fn pauses_when_the_vpn_drops() {
+        let rules = vec![rule(serde_json::json!({
+            "event": "networkLeft",
+            "network": { "signal": "vpnInterface", "value": "wg" },
+            "action": "pause",
+            "mode": "propose"
+        }))];
+        let connected = NetworkSignals {
+            interfaces: vec!["eth0".to_string(), "wg0".to_string()],
+            ..NetworkSignals::default()
+        };
+        let mut state = TriggerState {
+            signals: Some(NetworkSignals {
+                interfaces: vec!["eth0".to_string()],
+                ..NetworkSignals::default()
+            }),
+            ..TriggerState::default()
+        };
+        let now = at("2026-10-19T10:00:00Z");
+
+        let dropped = Observation::Network {
+            previous: connected.clone(),
+        };
+        assert_eq!(state.select(&rules, &dropped, false, &Utc, now), None);
+
+        let fired = state.select(&rules, &dropped, true, &Utc, now).unwrap();
+        assert_eq!(fired.action, TriggerAction::Pause);
+        assert_eq!(fired.reason(), "Disconnected from VPN interface \"wg\"");
+
+        // Rejoining does not match a "left" rule
+        let rejoined = Observation::Network {
+            previous: state.signals.replace(connected).unwrap(),
+        };
+        assert_eq!(state.select(&rules, &rejoined, true, &Utc, now), None);
+    }
+
+    #[test]
+    fn undoing_a_sent_clock_out_clocks_in_on_the_previous_project() {
+        let project = PunchDetails::new(Some("project-a".to_string()), None, None);
+        let mut trigger = FiredTrigger {
+            id: 1,
+            action: TriggerAction::ClockOut,
+            mode: TriggerMode::Perform,
+            reason: "Left the office network".to_string(),
+            at: at("2026-10-19T17:00:00Z"),
+            performed: true,
+            queued_id: Some(1),
+            previous_details: project.clone(),
+        };
+
+        // The clock-out cleared the active project
+        let (action, details) = trigger.opposite(&PunchDetails::default());
+        assert_eq!(action, TriggerAction::ClockIn);
+        assert_eq!(details, project);
+
+        // A clock-in is reverted on the project that has been running since
+        trigger.action = TriggerAction::ClockIn;
+        trigger.previous_details = PunchDetails::default();
+        let (action, details) = trigger.opposite(&project);
+        assert_eq!(action, TriggerAction::ClockOut);
+        assert_eq!(details, project);
+    }
+}
@@ -0,0 +1,380 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::api::{is_network_error, ApiRequest, Z8ApiClient};
+use crate::notify;
+use crate::offline::ActionType;
+use crate::state::AppState;
+
+pub const WATER_STATUS_CACHE_KEY: &str = "water_reminder_status";
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const STATUS_REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// Idle time below this is treated as still being at the desk
+const AWAY_THRESHOLD_MINUTES: i64 = 5;
+
+/// The user's water reminder settings as configured in the webapp
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaterReminderStatus {
+    pub enabled: bool,
+    pub interval_minutes: u32,
+    pub daily_goal: u32,
+    pub snoozed_until: Option<DateTime<Utc>>,
+    pub last_intake_time: Option<DateTime<Utc>>,
+}
+
+impl WaterReminderStatus {
+    pub fn is_snoozed(&self, now: DateTime<Utc>) -> bool {
+        self.snoozed_until.is_some_and(|until| until > now)
+    }
+
+    /// Reflects an action locally until the next refresh from the server
+    fn apply(&mut self, action: &WaterAction, now: DateTime<Utc>) {
+        match action {
+            WaterAction::Log { .. } => self.last_intake_time = Some(now),
+            WaterAction::Snooze => self.snoozed_until = Some(end_of_local_day(now)),
+        }
+    }
+}
+
+/// A response to a water reminder, as accepted by `/api/wellness/water-action`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum WaterAction {
+    /// Glasses of water drunk
+    Log { amount: u32 },
+    /// No more reminders today
+    Snooze,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaterActionResult {
+    pub today_intake: Option<u32>,
+    pub goal_progress: Option<u32>,
+    pub current_streak: Option<u32>,
+    #[serde(default)]
+    pub goal_just_met: bool,
+    pub snoozed_until: Option<DateTime<Utc>>,
+    /// Saved offline; sent once the webapp is reachable again
+    #[serde(default)]
+    pub queued: bool,
+}
+
+pub struct WellnessService {
+    api: Z8ApiClient,
+}
+
+impl WellnessService {
+    pub fn new(api: Z8ApiClient) -> Self {
+        Self { api }
+    }
+
+    pub async fn status(&self) -> Result<WaterReminderStatus> {
+        let response = self
+            .api
+            .send(ApiRequest::get("/api/wellness/water-reminder"))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!(
+                "Failed to fetch water reminder status: {}",
+                response.status
+            ));
+        }
+
+        response.json()
+    }
+
+    pub async fn act(&self, action: &WaterAction) -> Result<WaterActionResult> {
+        let response = self
+            .api
+            .send(ApiRequest::post(
+                "/api/wellness/water-action",
+                serde_json::to_value(action)?,
+            ))
+            .await?;
+
+        if !response.is_success() {
+            return Err(anyhow!(
+                "Failed to record water action: {}",
+                response.status
+            ));
+        }
+
+        response.json()
+    }
+}
+
+fn end_of_local_day(now: DateTime<Utc>) -> DateTime<Utc> {
+    let end = now
+        .with_timezone(&Local)
+        .date_naive()
+        .and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap_or_default());
+    Local
+        .from_local_datetime(&end)
+        .latest()
+        .map_or(now, |end| end.with_timezone(&Utc))
+}
+
+/// Returns the last known reminder status, if any was fetched before
+pub fn cached_status(state: &AppState) -> Option<WaterReminderStatus> {
+    let queue = state.offline_queue.lock();
+    match queue.get_cached::<WaterReminderStatus>(WATER_STATUS_CACHE_KEY) {
+        Ok(cached) => cached.map(|cached| cached.value),
+        Err(e) => {
+            log::warn!("Failed to read cached water reminder status: {}", e);
+            None
+        }
+    }
+}
+
+/// Records a water action, queueing it while the webapp is unreachable
+pub async fn perform(app_handle: &AppHandle, action: WaterAction) -> Result<WaterActionResult> {
+    let state = app_handle.state::<Arc<AppState>>();
+    if state.get_session_token().is_none() {
+        return Err(anyhow!("Not authenticated"));
+    }
+
+    let service = WellnessService::new(state.api.clone());
+    let result = match service.act(&action).await {
+        Ok(result) => result,
+        Err(e) if is_network_error(&e) => {
+            let payload = serde_json::to_string(&action)?;
+            let mut queue = state.offline_queue.lock();
+            queue.enqueue(
+                ActionType::WaterAction,
+                Utc::now().timestamp(),
+                Some(payload),
+            )?;
+            WaterActionResult {
+                queued: true,
+                ..Default::default()
+            }
+        }
+        Err(e) => return Err(e),
+    };
+
+    // Keep the local schedule in step so the next reminder counts from now
+    if let Some(mut status) = cached_status(&state) {
+        status.apply(&action, Utc::now());
+        if let Some(until) = result.snoozed_until {
+            status.snoozed_until = Some(until);
+        }
+        let mut queue = state.offline_queue.lock();
+        if let Err(e) = queue.put_cached(WATER_STATUS_CACHE_KEY, &status) {
+            log::warn!("Failed to cache water reminder status: {}", e);
+        }
+    }
+
+    let _ = app_handle.emit("water_action", &result);
+    Ok(result)
+}
+
+/// Logs a glass of water from the tray menu
+pub fn log_from_tray(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        match perform(&app_handle, WaterAction::Log { amount: 1 }).await {
+            Ok(result) if result.goal_just_met => notify::send(
+                &app_handle,
+                "Daily goal reached",
+                "Well done, stay hydrated!",
+            ),
+            Ok(_) => {}
+            Err(e) => notify::send(&app_handle, "Could not log water", &e.to_string()),
+        }
+    });
+}
+
+/// Tracks the running work session and when the user was last reminded
+#[derive(Debug, Default)]
+struct ReminderClock {
+    session_start: Option<DateTime<Utc>>,
+    last_reminder: Option<DateTime<Utc>>,
+}
+
+impl ReminderClock {
+    /// Returns whether a reminder is due now, and remembers it was sent
+    fn due(
+        &mut self,
+        status: &WaterReminderStatus,
+        is_clocked_in: bool,
+        last_activity: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if !is_clocked_in {
+            *self = Self::default();
+            return false;
+        }
+        let session_start = *self.session_start.get_or_insert(now);
+
+        if !status.enabled || status.is_snoozed(now) {
+            return false;
+        }
+
+        // Nobody is at the desk to see it
+        let is_away =
+            last_activity.is_some_and(|at| (now - at).num_minutes() >= AWAY_THRESHOLD_MINUTES);
+        if is_away {
+            return false;
+        }
+
+        let reference = [
+            Some(session_start),
+            status.last_intake_time,
+            self.last_reminder,
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(session_start);
+        if now - reference < ChronoDuration::minutes(i64::from(status.interval_minutes)) {
+            return false;
+        }
+
+        self.last_reminder = Some(now);
+        true
+    }
+}
+
+/// Reminds to drink water at the interval set in the webapp while the user is
+/// clocked in and at the desk. The cached status keeps reminders going offline.
+pub async fn start_water_reminders(app_handle: AppHandle) {
+    log::info!("Starting water reminder scheduler");
+
+    let state = app_handle.state::<Arc<AppState>>();
+    let service = WellnessService::new(state.api.clone());
+
+    let mut status_fetched_at: Option<Instant> = None;
+    let mut clock = ReminderClock::default();
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let online = state.get_session_token().is_some() && !state.get_webapp_url().is_empty();
+        if online && status_fetched_at.is_none_or(|at| at.elapsed() >= STATUS_REFRESH_INTERVAL) {
+            match service.status().await {
+                Ok(status) => {
+                    status_fetched_at = Some(Instant::now());
+                    let mut queue = state.offline_queue.lock();
+                    if let Err(e) = queue.put_cached(WATER_STATUS_CACHE_KEY, &status) {
+                        log::warn!("Failed to cache water reminder status: {}", e);
+                    }
+                }
+                Err(e) => log::warn!("Failed to refresh water reminder status: {}", e),
+            }
+        }
+
+        let Some(status) = cached_status(&state) else {
+            continue;
+        };
+
+        if !clock.due(
+            &status,
+            state.is_clocked_in(),
+            state.last_activity(),
+            Utc::now(),
+        ) {
+            continue;
+        }
+
+        log::info!("Sending water reminder");
+        notify::send(
+            &app_handle,
+            "Time to hydrate!",
+            "Stay healthy - drink some water",
+        );
+        if let Err(e) = app_handle.emit("water_reminder", &status) {
+            log::error!("Failed to emit water reminder event: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReminderClock, WaterAction, WaterReminderStatus, WellnessService};
+    use crate::api::testing::fake_client;
+    use chrono::{DateTime, Utc};
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn status() -> WaterReminderStatus {
+        serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "intervalMinutes": 45,
+            "dailyGoal": 8,
+            "snoozedUntil": null,
+            "lastIntakeTime": "2026-10-19T08:30:00.000Z"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn reminds_only_while_clocked_in_and_at_the_desk() {
+        let status = status();
+        let mut clock = ReminderClock::default();
+        let mut at_desk = |status: &WaterReminderStatus, now: &str| {
+            clock.due(status, true, Some(at(now)), at(now))
+        };
+
+        // Clocked in at 09:00, the interval counts from there
+        assert!(!at_desk(&status, "2026-10-19T09:00:00Z"));
+        assert!(!at_desk(&status, "2026-10-19T09:40:00Z"));
+        assert!(at_desk(&status, "2026-10-19T09:45:00Z"));
+        assert!(!at_desk(&status, "2026-10-19T10:00:00Z"));
+
+        let mut snoozed = status.clone();
+        snoozed.snoozed_until = Some(at("2026-10-19T23:59:59Z"));
+        assert!(!at_desk(&snoozed, "2026-10-19T11:00:00Z"));
+        assert!(at_desk(&status, "2026-10-19T11:00:00Z"));
+
+        // Away from the desk when the next one is due
+        let away_since = Some(at("2026-10-19T11:30:00Z"));
+        assert!(!clock.due(&status, true, away_since, at("2026-10-19T11:50:00Z")));
+
+        // Clocking out resets the session
+        assert!(!clock.due(&status, false, None, at("2026-10-19T12:00:00Z")));
+        assert!(!clock.due(&status, true, None, at("2026-10-19T13:00:00Z")));
+        assert!(clock.due(&status, true, None, at("2026-10-19T13:45:00Z")));
+    }
+
+    #[tokio::test]
+    async fn posts_water_actions() {
+        let (client, transport) = fake_client();
+        transport.respond(
+            200,
+            serde_json::json!({
+                "success": true,
+                "todayIntake": 3,
+                "goalProgress": 38,
+                "currentStreak": 2,
+                "goalJustMet": false
+            }),
+        );
+
+        let result = WellnessService::new(client)
+            .act(&WaterAction::Log { amount: 1 })
+            .await
+            .unwrap();
+        assert_eq!(result.today_intake, Some(3));
+        assert!(!result.queued);
+
+        let requests = transport.requests();
+        assert!(requests[0].url.ends_with("/api/wellness/water-action"));
+        assert_eq!(
+            requests[0].body,
+            Some(serde_json::json!({ "action": "log", "amount": 1 }))
+        );
+        assert_eq!(
+            serde_json::to_value(WaterAction::Snooze).unwrap(),
+            serde_json::json!({ "action": "snooze" })
+        );
+    }
+}
@@ -7,23 +7,44 @@ import {
   IconSun,
   IconMoon,
   IconDeviceDesktop,
+  IconBell,
 } from "@tabler/icons-react";
 import { Toaster, toast } from "sonner";
 
+import { ApprovalsDialog } from "./components/ApprovalsDialog";
 import { ClockButton } from "./components/ClockButton";
+import { DeepLinkDialog } from "./components/DeepLinkDialog";
+import { ForgottenClockOutDialog } from "./components/ForgottenClockOutDialog";
 import { IdleDialog } from "./components/IdleDialog";
 import { LoginScreen } from "./components/LoginScreen";
+import { NextShift } from "./components/NextShift";
+import { NotificationsPanel } from "./components/NotificationsPanel";
 import { OrganizationSelector } from "./components/OrganizationSelector";
 import { Settings } from "./components/Settings";
+import { ShiftWarningDialog } from "./components/ShiftWarningDialog";
+import { TriggerDialog } from "./components/TriggerDialog";
+import { UnsentActions } from "./components/UnsentActions";
+import { WaterReminderDialog } from "./components/WaterReminderDialog";
 import { WorkLocationSelector } from "./components/WorkLocationSelector";
 
+import { useApprovals } from "./hooks/useApprovals";
 import { useAuth } from "./hooks/useAuth";
 import { useClock } from "./hooks/useClock";
+import { useDeepLinks } from "./hooks/useDeepLinks";
+import { useDevices } from "./hooks/useDevices";
+import { useForgottenClockOut } from "./hooks/useForgottenClockOut";
 import { useIdle } from "./hooks/useIdle";
+import { useJournal } from "./hooks/useJournal";
 import { useOrganizations } from "./hooks/useOrganizations";
+import { useSchedule } from "./hooks/useSchedule";
+import { useServerNotifications } from "./hooks/useServerNotifications";
 import { useSettings } from "./hooks/useSettings";
 import { useTheme } from "./hooks/useTheme";
+import { useTriggers } from "./hooks/useTriggers";
+import { useUnsentActions } from "./hooks/useUnsentActions";
+import { useWaterReminder } from "./hooks/useWaterReminder";
 import { useWorkLocation } from "./hooks/useWorkLocation";
+import type { Device, PunchWarning, UnsentAction } from "./types";
 
 const queryClient = new QueryClient({
   defaultOptions: {
@@ -35,35 +56,64 @@ const queryClient = new QueryClient({
 });
 
 function AppContent() {
-  const { isAuthenticated, login, logout, isLoading: isAuthLoading } = useAuth();
+  const { isAuthenticated, profile, login, logout, isLoading: isAuthLoading } = useAuth();
   const {
     settings,
     saveSettings,
     isSaving,
     isSettingsOpen,
     setIsSettingsOpen,
+    readNetworkSignals,
   } = useSettings();
   const {
     isClockedIn,
     activeWorkPeriod,
     clockIn,
     clockOut,
+    clockOutAt,
     clockOutWithBreak,
     isClockingIn,
     isClockingOut,
     isError,
   } = useClock();
-  const { idleEvent, isIdleDialogOpen, dismissIdle } = useIdle();
+  const { idleEvent, isIdleDialogOpen, dismissIdle, keepWorking } = useIdle();
+  const {
+    prompt: clockOutPrompt,
+    isPromptOpen,
+    dismissPrompt,
+    keepWorking: keepWorkingAfterPrompt,
+  } = useForgottenClockOut();
+  const water = useWaterReminder();
+  const triggers = useTriggers();
+  const deepLinks = useDeepLinks();
   const { theme, setTheme, resolvedTheme } = useTheme();
-  const { workLocationType, setWorkLocationType } = useWorkLocation();
+  const {
+    workLocationType,
+    setWorkLocationType,
+    suggestion: locationSuggestion,
+  } = useWorkLocation({ enabled: isAuthenticated });
   const {
     organizations,
     activeOrganizationId,
     switchOrganization,
     isSwitching,
-  } = useOrganizations();
+  } = useOrganizations({ enabled: isAuthenticated });
+  const { nextShift, checkClockIn } = useSchedule({ enabled: isAuthenticated });
+  const approvals = useApprovals({ enabled: isAuthenticated });
+  const serverNotifications = useServerNotifications({ enabled: isAuthenticated });
+  const journal = useJournal({ enabled: isSettingsOpen });
+  const devices = useDevices({ enabled: isAuthenticated && isSettingsOpen });
+  const unsent = useUnsentActions({ enabled: isAuthenticated });
+  const [isNotificationsOpen, setIsNotificationsOpen] = useState(false);
 
   const [isProcessingIdle, setIsProcessingIdle] = useState(false);
+  const [shiftWarning, setShiftWarning] = useState<PunchWarning | null>(null);
+
+  const linkAction = deepLinks.request?.action;
+  const linkOrganizationName =
+    linkAction?.kind === "switchOrganization"
+      ? organizations.find((org) => org.id === linkAction.id)?.name
+      : null;
 
   const cycleTheme = () => {
     if (theme === "system") setTheme("light");
@@ -73,9 +123,10 @@ function AppContent() {
 
   const ThemeIcon = theme === "system" ? IconDeviceDesktop : resolvedTheme === "dark" ? IconMoon : IconSun;
 
-  const handleClockIn = async () => {
+  const clockInNow = async () => {
+    setShiftWarning(null);
     try {
-      await clockIn(workLocationType);
+      await clockIn({ workLocationType, locationSuggestion });
       toast.success("Clocked in successfully");
     } catch (error) {
       toast.error("Failed to clock in");
@@ -83,6 +134,16 @@ function AppContent() {
     }
   };
 
+  // Punches well outside the planned shift are confirmed first to catch accidental clicks
+  const handleClockIn = async () => {
+    const warning = await checkClockIn().catch(() => null);
+    if (warning) {
+      setShiftWarning(warning);
+      return;
+    }
+    await clockInNow();
+  };
+
   const handleClockOut = async () => {
     try {
       await clockOut();
@@ -113,10 +174,72 @@ function AppContent() {
   };
 
   const handleIdleResume = () => {
-    dismissIdle();
+    keepWorking();
     toast.info("Continuing work session");
   };
 
+  const handleClockOutAtLastActivity = async () => {
+    if (!clockOutPrompt?.lastActivity) return;
+
+    try {
+      await clockOutAt(clockOutPrompt.lastActivity);
+      toast.success("Clocked out at your last activity");
+      dismissPrompt();
+    } catch (error) {
+      toast.error("Failed to clock out");
+      console.error(error);
+    }
+  };
+
+  const handleClockOutNow = async () => {
+    await handleClockOut();
+    dismissPrompt();
+  };
+
+  const handleExportJournal = async () => {
+    try {
+      const path = await journal.exportJournal();
+      toast.success(`Activity log saved to ${path}`);
+    } catch (error) {
+      toast.error("Failed to export the activity log");
+      console.error(error);
+    }
+  };
+
+  const handleRevokeDevice = async (device: Device) => {
+    const name = device.name ?? "the device";
+
+    try {
+      await devices.revokeDevice(device.id);
+      if (!device.isCurrent) {
+        toast.success(`Revoked ${name}`);
+      }
+    } catch (error) {
+      toast.error("Failed to revoke the device");
+      console.error(error);
+    }
+  };
+
+  const handleRetryUnsent = async (action: UnsentAction) => {
+    try {
+      await unsent.retry(action.id);
+      toast.info("Sending it again");
+    } catch (error) {
+      toast.error("Failed to retry the action");
+      console.error(error);
+    }
+  };
+
+  const handleDiscardUnsent = async (action: UnsentAction) => {
+    try {
+      await unsent.discard(action.id);
+      toast.success("Action discarded");
+    } catch (error) {
+      toast.error("Failed to discard the action");
+      console.error(error);
+    }
+  };
+
   // Show login screen if not authenticated
   if (!isAuthenticated && !isAuthLoading) {
     return (
@@ -132,6 +255,7 @@ function AppContent() {
           settings={settings}
           onSave={saveSettings}
           onLogout={logout}
+          onReadNetworkSignals={readNetworkSignals}
           isSaving={isSaving}
           isAuthenticated={isAuthenticated}
         />
@@ -170,6 +294,20 @@ function AppContent() {
               <span>Offline</span>
             </div>
           )}
+          <button
+            type="button"
+            onClick={() => setIsNotificationsOpen(true)}
+            className="settings-button"
+            title="Notifications"
+            aria-label={`Open Notifications. ${serverNotifications.unreadCount} unread`}
+          >
+            <IconBell size={18} />
+            {serverNotifications.unreadCount > 0 && (
+              <span className="notification-badge">
+                {serverNotifications.unreadCount > 9 ? "9+" : serverNotifications.unreadCount}
+              </span>
+            )}
+          </button>
           <button
             type="button"
             onClick={cycleTheme}
@@ -197,6 +335,7 @@ function AppContent() {
           <WorkLocationSelector
             value={workLocationType}
             onChange={setWorkLocationType}
+            suggestion={locationSuggestion}
             disabled={isClockingIn || isClockingOut}
           />
         )}
@@ -207,6 +346,13 @@ function AppContent() {
           onClockOut={handleClockOut}
           isLoading={isClockingIn || isClockingOut}
         />
+        <NextShift shift={nextShift} />
+        <UnsentActions
+          actions={unsent.actions}
+          onRetry={handleRetryUnsent}
+          onDiscard={handleDiscardUnsent}
+          isUpdating={unsent.isUpdating}
+        />
       </main>
 
       {/* Footer status */}
@@ -226,6 +372,152 @@ function AppContent() {
         isLoading={isProcessingIdle}
       />
 
+      {/* Server Notifications */}
+      <NotificationsPanel
+        isOpen={isNotificationsOpen}
+        notifications={serverNotifications.notifications}
+        unreadCount={serverNotifications.unreadCount}
+        onOpen={async (notification) => {
+          try {
+            await serverNotifications.openNotification(notification);
+          } catch (error) {
+            toast.error("Failed to open notification");
+            console.error(error);
+          }
+        }}
+        onMarkAllRead={() => serverNotifications.markAllRead()}
+        onClose={() => setIsNotificationsOpen(false)}
+      />
+
+      {/* Manager Approvals */}
+      <ApprovalsDialog
+        isOpen={approvals.isInboxOpen}
+        approvals={approvals.approvals}
+        total={approvals.total}
+        rejectingId={approvals.rejectingId}
+        onApprove={async (approvalIds) => {
+          try {
+            await approvals.approve(approvalIds);
+            toast.success(approvalIds.length === 1 ? "Request approved" : "Requests approved");
+          } catch (error) {
+            toast.error("Failed to approve");
+            console.error(error);
+          }
+        }}
+        onReject={async (approvalIds, reason) => {
+          try {
+            await approvals.reject({ approvalIds, reason });
+            toast.success(approvalIds.length === 1 ? "Request rejected" : "Requests rejected");
+          } catch (error) {
+            toast.error("Failed to reject");
+            console.error(error);
+          }
+        }}
+        onStartReject={approvals.setRejectingId}
+        onClose={approvals.closeInbox}
+        isLoading={approvals.isDeciding}
+      />
+
+      {/* Off-Schedule Clock-In Confirmation */}
+      <ShiftWarningDialog
+        warning={shiftWarning}
+        onConfirm={clockInNow}
+        onCancel={() => setShiftWarning(null)}
+        isLoading={isClockingIn}
+      />
+
+      {/* Forgotten Clock-Out Dialog */}
+      <ForgottenClockOutDialog
+        isOpen={isPromptOpen && isClockedIn}
+        prompt={clockOutPrompt}
+        onClockOutAtLastActivity={handleClockOutAtLastActivity}
+        onClockOutNow={handleClockOutNow}
+        onKeepWorking={keepWorkingAfterPrompt}
+        isLoading={isClockingOut}
+      />
+
+      {/* Water Reminder */}
+      <WaterReminderDialog
+        isOpen={water.isReminderOpen && isClockedIn}
+        reminder={water.reminder}
+        onLog={async () => {
+          try {
+            const result = await water.logWater(1);
+            water.dismissReminder();
+            if (result.queued) {
+              toast.info("Saved offline. It will be logged once you are back online.");
+            } else if (result.goalJustMet) {
+              toast.success("Daily hydration goal reached!");
+            } else {
+              toast.success("Water logged");
+            }
+          } catch (error) {
+            toast.error("Failed to log water");
+            console.error(error);
+          }
+        }}
+        onSnooze={async () => {
+          try {
+            await water.snooze();
+            water.dismissReminder();
+            toast.success("Water reminders paused for today");
+          } catch (error) {
+            toast.error("Failed to snooze water reminders");
+            console.error(error);
+          }
+        }}
+        onDismiss={water.dismissReminder}
+        isLoading={water.isSaving}
+      />
+
+      {/* Automatic clock-in/out */}
+      <TriggerDialog
+        isOpen={triggers.isTriggerOpen}
+        trigger={triggers.trigger}
+        onConfirm={async () => {
+          if (!triggers.trigger) return;
+          try {
+            await triggers.confirm(triggers.trigger.id);
+            toast.success("Saved. It will be synced in the background.");
+          } catch (error) {
+            toast.error(String(error));
+            triggers.dismiss();
+          }
+        }}
+        onUndo={async () => {
+          if (!triggers.trigger) return;
+          try {
+            await triggers.undo(triggers.trigger.id);
+            toast.success("Automatic punch undone");
+          } catch (error) {
+            toast.error(String(error));
+            triggers.dismiss();
+          }
+        }}
+        onDismiss={triggers.dismiss}
+        isLoading={triggers.isSaving}
+      />
+
+      {/* Actions requested by z8:// links */}
+      <DeepLinkDialog
+        isOpen={deepLinks.isRequestOpen}
+        request={deepLinks.request}
+        organizationName={linkOrganizationName}
+        onConfirm={async () => {
+          const request = deepLinks.request;
+          if (!request) return;
+          try {
+            await deepLinks.confirm(request.id);
+            toast.success(`${request.description}: done`);
+          } catch (error) {
+            toast.error(String(error));
+            deepLinks.dismiss();
+          }
+        }}
+        onDismiss={deepLinks.dismiss}
+        isLoading={deepLinks.isSaving}
+      />
+
       {/* Settings Dialog */}
       <Settings
         isOpen={isSettingsOpen}
@@ -233,6 +525,14 @@ function AppContent() {
         settings={settings}
         onSave={saveSettings}
         onLogout={logout}
+        onReadNetworkSignals={readNetworkSignals}
+        journalEntries={journal.entries}
+        onExportJournal={handleExportJournal}
+        isExportingJournal={journal.isExporting}
+        devices={devices.devices}
+        onRevokeDevice={handleRevokeDevice}
+        isRevokingDevice={devices.isRevoking}
+        profile={profile}
         isSaving={isSaving}
         isAuthenticated={isAuthenticated}
       />
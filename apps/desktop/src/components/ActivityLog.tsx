@@ -0,0 +1,95 @@
+import { IconDownload } from "@tabler/icons-react";
+import type { EventSource, JournalEntry, JournalOutcome } from "../types";
+
+interface ActivityLogProps {
+  entries: JournalEntry[];
+  onExport: () => void;
+  isExporting: boolean;
+}
+
+const SOURCE_LABELS: Record<EventSource, string> = {
+  ui: "App",
+  tray: "Tray",
+  hotkey: "Shortcut",
+  rule: "Rule",
+  link: "Link",
+  cli: "Command line",
+  controlApi: "Control API",
+  system: "Automatic",
+};
+
+const OUTCOME_COLORS: Record<JournalOutcome, string> = {
+  accepted: "var(--color-foreground)",
+  queued: "hsl(38 92% 50%)",
+  failed: "var(--color-destructive)",
+  noted: "var(--color-muted-foreground)",
+};
+
+/** How many entries the settings dialog shows; the export has everything */
+const VISIBLE_ENTRIES = 8;
+
+function describe(entry: JournalEntry): string {
+  const action = entry.action.replace(/_/g, " ");
+  return `${action.charAt(0).toUpperCase()}${action.slice(1)}`;
+}
+
+export function ActivityLog({ entries, onExport, isExporting }: ActivityLogProps) {
+  return (
+    <div>
+      <div style={{ fontSize: "14px", fontWeight: 500, marginBottom: "8px" }}>Recent activity</div>
+      {entries.length === 0 ? (
+        <p style={{ fontSize: "12px", color: "var(--color-muted-foreground)", margin: 0 }}>
+          Nothing recorded yet.
+        </p>
+      ) : (
+        <ul style={{ listStyle: "none", margin: 0, padding: 0, display: "flex", flexDirection: "column", gap: "4px" }}>
+          {entries.slice(0, VISIBLE_ENTRIES).map((entry) => (
+            <li
+              key={entry.id}
+              title={entry.message ?? undefined}
+              style={{ display: "flex", justifyContent: "space-between", gap: "8px", fontSize: "12px" }}
+            >
+              <span style={{ color: OUTCOME_COLORS[entry.outcome] }}>
+                {describe(entry)}
+                {entry.outcome !== "accepted" && entry.outcome !== "noted" && ` (${entry.outcome})`}
+              </span>
+              <span style={{ color: "var(--color-muted-foreground)", whiteSpace: "nowrap" }}>
+                {SOURCE_LABELS[entry.source]} ·{" "}
+                {new Date(entry.at).toLocaleString([], {
+                  month: "short",
+                  day: "numeric",
+                  hour: "2-digit",
+                  minute: "2-digit",
+                })}
+              </span>
+            </li>
+          ))}
+        </ul>
+      )}
+      <button
+        type="button"
+        onClick={onExport}
+        disabled={isExporting}
+        style={{
+          display: "flex",
+          alignItems: "center",
+          justifyContent: "center",
+          gap: "6px",
+          width: "100%",
+          marginTop: "8px",
+          padding: "8px",
+          fontSize: "13px",
+          color: "var(--color-foreground)",
+          background: "transparent",
+          border: "1px solid var(--color-border)",
+          borderRadius: "8px",
+          cursor: isExporting ? "not-allowed" : "pointer",
+          opacity: isExporting ? 0.6 : 1,
+        }}
+      >
+        <IconDownload size={14} />
+        {isExporting ? "Exporting..." : "Export for support"}
+      </button>
+    </div>
+  );
+}
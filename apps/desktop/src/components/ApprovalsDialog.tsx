@@ -0,0 +1,184 @@
+import { useState } from "react";
+import { IconCheck, IconX } from "@tabler/icons-react";
+import { cn } from "../lib/utils";
+import type { ApprovalItem } from "../types";
+
+interface ApprovalsDialogProps {
+  isOpen: boolean;
+  approvals: ApprovalItem[];
+  total: number;
+  rejectingId: string | null;
+  onApprove: (approvalIds: string[]) => Promise<unknown>;
+  onReject: (approvalIds: string[], reason: string) => Promise<unknown>;
+  onStartReject: (approvalId: string | null) => void;
+  onClose: () => void;
+  isLoading?: boolean;
+}
+
+export function ApprovalsDialog({
+  isOpen,
+  approvals,
+  total,
+  rejectingId,
+  onApprove,
+  onReject,
+  onStartReject,
+  onClose,
+  isLoading,
+}: ApprovalsDialogProps) {
+  const [selected, setSelected] = useState<string[]>([]);
+  const [isBulkRejecting, setIsBulkRejecting] = useState(false);
+  const [reason, setReason] = useState("");
+
+  if (!isOpen) return null;
+
+  const rejectTargets = isBulkRejecting ? selected : rejectingId ? [rejectingId] : [];
+  const actionable = approvals.filter((item) => item.isActionable !== false);
+
+  const toggle = (id: string) =>
+    setSelected((current) =>
+      current.includes(id) ? current.filter((selectedId) => selectedId !== id) : [...current, id],
+    );
+
+  const cancelReject = () => {
+    setIsBulkRejecting(false);
+    setReason("");
+    onStartReject(null);
+  };
+
+  const submitReject = async () => {
+    await onReject(rejectTargets, reason);
+    setSelected((current) => current.filter((id) => !rejectTargets.includes(id)));
+    cancelReject();
+  };
+
+  const approveSelected = async () => {
+    await onApprove(selected);
+    setSelected([]);
+  };
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/50" />
+
+      {/* Dialog */}
+      <div className="relative bg-background rounded-lg shadow-xl p-5 mx-4 max-w-sm w-full border border-border max-h-[90vh] flex flex-col">
+        <div className="flex items-center justify-between mb-3">
+          <h2 className="text-lg font-semibold">Approvals ({total})</h2>
+          <button type="button" onClick={onClose} aria-label="Close approvals">
+            <IconX className="w-5 h-5" />
+          </button>
+        </div>
+
+        {rejectTargets.length > 0 ? (
+          <div className="flex flex-col gap-3">
+            <p className="text-sm text-muted-foreground">
+              {rejectTargets.length === 1
+                ? "Why is this request rejected?"
+                : `Why are these ${rejectTargets.length} requests rejected?`}
+            </p>
+            <textarea
+              value={reason}
+              onChange={(event) => setReason(event.target.value)}
+              rows={3}
+              className="w-full rounded-lg border border-border bg-background p-2 text-sm"
+            />
+            <button
+              type="button"
+              onClick={submitReject}
+              disabled={isLoading || reason.trim().length === 0}
+              className={cn(
+                "w-full py-2 px-4 bg-red-600 hover:bg-red-700 text-white rounded-lg",
+                "transition-colors font-medium disabled:opacity-50 disabled:cursor-not-allowed"
+              )}
+            >
+              Reject
+            </button>
+            <button type="button" onClick={cancelReject} className="text-sm text-muted-foreground">
+              Back
+            </button>
+          </div>
+        ) : (
+          <>
+            <ul className="flex flex-col gap-2 overflow-y-auto">
+              {approvals.length === 0 && (
+                <li className="text-sm text-muted-foreground text-center py-6">
+                  Nothing is waiting for you.
+                </li>
+              )}
+              {approvals.map((item) => (
+                <li key={item.id} className="flex items-start gap-2 rounded-lg border border-border p-2">
+                  <input
+                    type="checkbox"
+                    checked={selected.includes(item.id)}
+                    disabled={item.isActionable === false}
+                    onChange={() => toggle(item.id)}
+                    aria-label={`Select ${item.display.title} from ${item.requester.name}`}
+                    className="mt-1"
+                  />
+                  <div className="flex-1 min-w-0">
+                    <div className="text-sm font-medium truncate">{item.requester.name}</div>
+                    <div className="text-xs text-muted-foreground truncate">
+                      {item.display.title} · {item.display.subtitle}
+                    </div>
+                    {item.warning && <div className="text-xs text-amber-600">{item.warning}</div>}
+                  </div>
+                  {item.isActionable !== false && (
+                    <div className="flex gap-1">
+                      <button
+                        type="button"
+                        onClick={() => onApprove([item.id])}
+                        disabled={isLoading}
+                        aria-label="Approve"
+                        className="p-1 rounded hover:bg-green-600/10 text-green-600"
+                      >
+                        <IconCheck className="w-5 h-5" />
+                      </button>
+                      <button
+                        type="button"
+                        onClick={() => onStartReject(item.id)}
+                        disabled={isLoading}
+                        aria-label="Reject"
+                        className="p-1 rounded hover:bg-red-600/10 text-red-600"
+                      >
+                        <IconX className="w-5 h-5" />
+                      </button>
+                    </div>
+                  )}
+                </li>
+              ))}
+            </ul>
+
+            {actionable.length > 1 && (
+              <div className="flex gap-2 mt-3">
+                <button
+                  type="button"
+                  onClick={approveSelected}
+                  disabled={isLoading || selected.length === 0}
+                  className={cn(
+                    "flex-1 py-2 px-3 bg-primary hover:bg-primary/90 text-primary-foreground rounded-lg",
+                    "text-sm font-medium disabled:opacity-50 disabled:cursor-not-allowed"
+                  )}
+                >
+                  Approve selected
+                </button>
+                <button
+                  type="button"
+                  onClick={() => setIsBulkRejecting(true)}
+                  disabled={isLoading || selected.length === 0}
+                  className={cn(
+                    "flex-1 py-2 px-3 bg-red-600 hover:bg-red-700 text-white rounded-lg",
+                    "text-sm font-medium disabled:opacity-50 disabled:cursor-not-allowed"
+                  )}
+                >
+                  Reject selected
+                </button>
+              </div>
+            )}
+          </>
+        )}
+      </div>
+    </div>
+  );
+}
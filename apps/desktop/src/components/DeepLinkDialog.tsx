@@ -0,0 +1,75 @@
+import { IconCheck, IconLink } from "@tabler/icons-react";
+import { cn } from "../lib/utils";
+import type { PendingDeepLink } from "../types";
+
+interface DeepLinkDialogProps {
+  isOpen: boolean;
+  request: PendingDeepLink | null;
+  /** Name of the organization a switch link points to, if known */
+  organizationName?: string | null;
+  onConfirm: () => void;
+  onDismiss: () => void;
+  isLoading?: boolean;
+}
+
+export function DeepLinkDialog({
+  isOpen,
+  request,
+  organizationName,
+  onConfirm,
+  onDismiss,
+  isLoading,
+}: DeepLinkDialogProps) {
+  if (!isOpen || !request) return null;
+
+  const description =
+    request.action.kind === "switchOrganization"
+      ? `Switch to ${organizationName ?? "another organization"}`
+      : request.description;
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/50" />
+
+      {/* Dialog */}
+      <div className="relative bg-background rounded-lg shadow-xl p-5 mx-4 max-w-sm w-full border border-border">
+        <div className="text-center">
+          <div className="flex justify-center mb-2">
+            <IconLink className="w-6 h-6 text-muted-foreground" />
+          </div>
+          <h2 className="text-lg font-semibold mb-2">{description}?</h2>
+          <p className="text-sm text-muted-foreground mb-4">
+            A link asked z8 Timer to do this. Only confirm if you just opened it yourself.
+          </p>
+
+          <div className="flex flex-col gap-3">
+            <button
+              type="button"
+              onClick={onConfirm}
+              disabled={isLoading}
+              className={cn(
+                "flex items-center justify-center gap-2 w-full py-3 px-4",
+                "bg-primary hover:bg-primary/90 text-primary-foreground rounded-lg",
+                "transition-colors font-medium",
+                "disabled:opacity-50 disabled:cursor-not-allowed"
+              )}
+            >
+              <IconCheck className="w-5 h-5" />
+              {description}
+            </button>
+
+            <button
+              type="button"
+              onClick={onDismiss}
+              disabled={isLoading}
+              className="text-sm text-muted-foreground"
+            >
+              Cancel
+            </button>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,82 @@
+import { IconDeviceDesktop } from "@tabler/icons-react";
+import { useState } from "react";
+import type { Device } from "../types";
+
+interface DeviceListProps {
+  devices: Device[];
+  onRevoke: (device: Device) => void;
+  isRevoking: boolean;
+}
+
+function describeLastUse(device: Device): string {
+  if (device.isCurrent) return "This device";
+  if (!device.lastUsedAt) return "Never used";
+
+  return `Last used ${new Date(device.lastUsedAt).toLocaleDateString([], {
+    month: "short",
+    day: "numeric",
+    year: "numeric",
+  })}`;
+}
+
+export function DeviceList({ devices, onRevoke, isRevoking }: DeviceListProps) {
+  // Revoking takes a second click, so a stray one can't sign out a laptop
+  const [confirmingId, setConfirmingId] = useState<string | null>(null);
+
+  const handleClick = (device: Device) => {
+    if (confirmingId !== device.id) {
+      setConfirmingId(device.id);
+      return;
+    }
+    setConfirmingId(null);
+    onRevoke(device);
+  };
+
+  return (
+    <div>
+      <div style={{ fontSize: "14px", fontWeight: 500, marginBottom: "8px" }}>Signed-in devices</div>
+      {devices.length === 0 ? (
+        <p style={{ fontSize: "12px", color: "var(--color-muted-foreground)", margin: 0 }}>
+          No devices registered yet.
+        </p>
+      ) : (
+        <ul style={{ listStyle: "none", margin: 0, padding: 0, display: "flex", flexDirection: "column", gap: "8px" }}>
+          {devices.map((device) => (
+            <li key={device.id} style={{ display: "flex", alignItems: "center", gap: "10px" }}>
+              <IconDeviceDesktop size={16} style={{ color: "var(--color-muted-foreground)", flexShrink: 0 }} />
+              <div style={{ flex: 1, minWidth: 0 }}>
+                <div style={{ fontSize: "13px", overflow: "hidden", textOverflow: "ellipsis", whiteSpace: "nowrap" }}>
+                  {device.name ?? "Unnamed device"}
+                  {device.platform && (
+                    <span style={{ color: "var(--color-muted-foreground)" }}> · {device.platform}</span>
+                  )}
+                </div>
+                <div style={{ fontSize: "12px", color: "var(--color-muted-foreground)" }}>
+                  {describeLastUse(device)}
+                </div>
+              </div>
+              <button
+                type="button"
+                onClick={() => handleClick(device)}
+                onBlur={() => setConfirmingId(null)}
+                disabled={isRevoking}
+                style={{
+                  padding: "6px 10px",
+                  fontSize: "12px",
+                  color: "var(--color-destructive)",
+                  background: "transparent",
+                  border: "1px solid var(--color-border)",
+                  borderRadius: "8px",
+                  cursor: isRevoking ? "not-allowed" : "pointer",
+                  opacity: isRevoking ? 0.6 : 1,
+                }}
+              >
+                {confirmingId === device.id ? "Confirm" : device.isCurrent ? "Sign out" : "Revoke"}
+              </button>
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,94 @@
+import { IconBriefcase, IconLogout } from "@tabler/icons-react";
+import { cn } from "../lib/utils";
+import type { ClockOutPrompt } from "../types";
+
+interface ForgottenClockOutDialogProps {
+  isOpen: boolean;
+  prompt: ClockOutPrompt | null;
+  onClockOutAtLastActivity: () => void;
+  onClockOutNow: () => void;
+  onKeepWorking: () => void;
+  isLoading?: boolean;
+}
+
+function formatTime(value: string): string {
+  return new Date(value).toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" });
+}
+
+export function ForgottenClockOutDialog({
+  isOpen,
+  prompt,
+  onClockOutAtLastActivity,
+  onClockOutNow,
+  onKeepWorking,
+  isLoading,
+}: ForgottenClockOutDialogProps) {
+  if (!isOpen || !prompt) return null;
+
+  const lastActivity = prompt.lastActivity ? formatTime(prompt.lastActivity) : null;
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/50" />
+
+      {/* Dialog */}
+      <div className="relative bg-background rounded-lg shadow-xl p-5 mx-4 max-w-sm w-full border border-border">
+        <div className="text-center">
+          <h2 className="text-lg font-semibold mb-2">Still clocked in?</h2>
+          <p className="text-sm text-muted-foreground mb-4">
+            {lastActivity ? (
+              <>
+                There was no activity since <span className="font-medium">{lastActivity}</span>.
+                <br />
+                Did you forget to clock out?
+              </>
+            ) : (
+              "Your work day is over. Do you want to clock out?"
+            )}
+          </p>
+
+          <div className="flex flex-col gap-3">
+            <button
+              type="button"
+              onClick={lastActivity ? onClockOutAtLastActivity : onClockOutNow}
+              disabled={isLoading}
+              className={cn(
+                "flex items-center justify-center gap-2 w-full py-3 px-4",
+                "bg-amber-500 hover:bg-amber-600 text-white rounded-lg",
+                "transition-colors font-medium",
+                "disabled:opacity-50 disabled:cursor-not-allowed"
+              )}
+            >
+              <IconLogout className="w-5 h-5" />
+              {lastActivity ? `Clock out at ${lastActivity}` : "Clock out now"}
+            </button>
+
+            <button
+              type="button"
+              onClick={onKeepWorking}
+              disabled={isLoading}
+              className={cn(
+                "flex items-center justify-center gap-2 w-full py-3 px-4",
+                "bg-primary hover:bg-primary/90 text-primary-foreground rounded-lg",
+                "transition-colors font-medium",
+                "disabled:opacity-50 disabled:cursor-not-allowed"
+              )}
+            >
+              <IconBriefcase className="w-5 h-5" />
+              I'm still working
+            </button>
+          </div>
+
+          <p className="text-xs text-muted-foreground mt-4">
+            {isLoading
+              ? "Processing..."
+              : lastActivity
+                ? "Clocking out at your last activity keeps the idle time off your record."
+                : "You can change the end of day in Settings."}
+          </p>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,162 @@
+import { useState } from "react";
+import { IconPlus, IconTrash, IconWifi } from "@tabler/icons-react";
+import {
+  WORK_LOCATION_OPTIONS,
+  type LocationRule,
+  type LocationSignal,
+  type NetworkSignals,
+  type WorkLocationType,
+} from "../types";
+
+interface LocationRulesProps {
+  rules: LocationRule[];
+  managedRules: LocationRule[];
+  onChange: (rules: LocationRule[]) => void;
+  onReadNetworkSignals?: () => Promise<NetworkSignals>;
+}
+
+const SIGNAL_OPTIONS: { value: LocationSignal; label: string; placeholder: string }[] = [
+  { value: "wifiSsid", label: "Wi-Fi name", placeholder: "Office-WLAN" },
+  { value: "gatewayMac", label: "Gateway MAC", placeholder: "aa:bb:cc:dd:ee:ff" },
+  { value: "vpnInterface", label: "VPN interface", placeholder: "tun, wg, utun…" },
+];
+
+const fieldStyle = {
+  padding: "8px 10px",
+  fontSize: "13px",
+  borderRadius: "8px",
+  border: "1px solid var(--color-border)",
+  background: "var(--color-background)",
+  color: "var(--color-foreground)",
+  minWidth: 0,
+} as const;
+
+const iconButtonStyle = {
+  padding: "6px",
+  borderRadius: "8px",
+  border: "none",
+  background: "transparent",
+  color: "var(--color-muted-foreground)",
+  cursor: "pointer",
+  display: "flex",
+  alignItems: "center",
+} as const;
+
+function locationLabel(location: WorkLocationType): string {
+  return WORK_LOCATION_OPTIONS.find((option) => option.value === location)?.label ?? location;
+}
+
+function signalLabel(signal: LocationSignal): string {
+  return SIGNAL_OPTIONS.find((option) => option.value === signal)?.label ?? signal;
+}
+
+export function LocationRules({ rules, managedRules, onChange, onReadNetworkSignals }: LocationRulesProps) {
+  const [isReading, setIsReading] = useState(false);
+
+  const updateRule = (index: number, patch: Partial<LocationRule>) => {
+    onChange(rules.map((rule, ruleIndex) => (ruleIndex === index ? { ...rule, ...patch } : rule)));
+  };
+
+  // Creates a rule for the network the device is on right now
+  const addCurrentNetwork = async () => {
+    if (!onReadNetworkSignals) return;
+    setIsReading(true);
+    try {
+      const signals = await onReadNetworkSignals();
+      const rule: LocationRule = signals.ssid
+        ? { signal: "wifiSsid", value: signals.ssid, location: "office" }
+        : { signal: "gatewayMac", value: signals.gatewayMac ?? "", location: "office" };
+      onChange([...rules, rule]);
+    } finally {
+      setIsReading(false);
+    }
+  };
+
+  return (
+    <div>
+      <div style={{ fontSize: "14px", fontWeight: 500, marginBottom: "8px" }}>Work location rules</div>
+      <div style={{ display: "flex", flexDirection: "column", gap: "8px" }}>
+        {rules.map((rule, index) => (
+          <div
+            key={`${index}-${rule.signal}`}
+            style={{ display: "grid", gridTemplateColumns: "1fr 1.3fr 1fr auto", gap: "6px" }}
+          >
+            <select
+              aria-label="Network signal"
+              value={rule.signal}
+              onChange={(event) => updateRule(index, { signal: event.target.value as LocationSignal })}
+              style={fieldStyle}
+            >
+              {SIGNAL_OPTIONS.map((option) => (
+                <option key={option.value} value={option.value}>
+                  {option.label}
+                </option>
+              ))}
+            </select>
+            <input
+              aria-label="Signal value"
+              value={rule.value}
+              placeholder={SIGNAL_OPTIONS.find((option) => option.value === rule.signal)?.placeholder}
+              onChange={(event) => updateRule(index, { value: event.target.value })}
+              style={fieldStyle}
+            />
+            <select
+              aria-label="Work location"
+              value={rule.location}
+              onChange={(event) => updateRule(index, { location: event.target.value as WorkLocationType })}
+              style={fieldStyle}
+            >
+              {WORK_LOCATION_OPTIONS.map((option) => (
+                <option key={option.value} value={option.value}>
+                  {option.label}
+                </option>
+              ))}
+            </select>
+            <button
+              type="button"
+              aria-label="Remove rule"
+              onClick={() => onChange(rules.filter((_, ruleIndex) => ruleIndex !== index))}
+              style={iconButtonStyle}
+            >
+              <IconTrash size={16} />
+            </button>
+          </div>
+        ))}
+
+        {managedRules.map((rule, index) => (
+          <div
+            key={`managed-${index}`}
+            style={{ fontSize: "12px", color: "var(--color-muted-foreground)" }}
+          >
+            {signalLabel(rule.signal)} "{rule.value}" → {locationLabel(rule.location)} (set by your IT)
+          </div>
+        ))}
+
+        <div style={{ display: "flex", gap: "8px" }}>
+          <button
+            type="button"
+            onClick={() => onChange([...rules, { signal: "wifiSsid", value: "", location: "office" }])}
+            style={{ ...iconButtonStyle, gap: "4px", fontSize: "13px" }}
+          >
+            <IconPlus size={16} />
+            Add rule
+          </button>
+          {onReadNetworkSignals && (
+            <button
+              type="button"
+              onClick={addCurrentNetwork}
+              disabled={isReading}
+              style={{ ...iconButtonStyle, gap: "4px", fontSize: "13px" }}
+            >
+              <IconWifi size={16} />
+              {isReading ? "Reading network…" : "Use current network"}
+            </button>
+          )}
+        </div>
+      </div>
+      <p style={{ fontSize: "12px", color: "var(--color-muted-foreground)", marginTop: "6px" }}>
+        Pre-selects the work location on clock-in. The first matching rule wins; you can still change it.
+      </p>
+    </div>
+  );
+}
@@ -0,0 +1,39 @@
+import { IconCalendarTime } from "@tabler/icons-react";
+import type { ShiftWindow } from "../types";
+
+interface NextShiftProps {
+  shift: ShiftWindow | null;
+}
+
+function formatTime(value: string): string {
+  return new Date(value).toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" });
+}
+
+function formatDay(value: string): string {
+  const date = new Date(value);
+  const today = new Date();
+  const tomorrow = new Date();
+  tomorrow.setDate(today.getDate() + 1);
+
+  if (date.toDateString() === today.toDateString()) return "Today";
+  if (date.toDateString() === tomorrow.toDateString()) return "Tomorrow";
+  return date.toLocaleDateString([], { weekday: "short", day: "numeric", month: "short" });
+}
+
+export function NextShift({ shift }: NextShiftProps) {
+  if (!shift) return null;
+
+  const isRunning = new Date(shift.start) <= new Date();
+
+  return (
+    <div className="flex items-center gap-2 text-sm text-muted-foreground">
+      <IconCalendarTime size={16} aria-hidden="true" />
+      <span>
+        {isRunning ? "Current shift" : `Next shift: ${formatDay(shift.start)}`}{" "}
+        <span className="font-medium text-foreground">
+          {formatTime(shift.start)}–{formatTime(shift.end)}
+        </span>
+      </span>
+    </div>
+  );
+}
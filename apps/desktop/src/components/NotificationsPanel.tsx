@@ -0,0 +1,82 @@
+import { IconX } from "@tabler/icons-react";
+import { cn } from "../lib/utils";
+import type { ServerNotification } from "../types";
+
+interface NotificationsPanelProps {
+  isOpen: boolean;
+  notifications: ServerNotification[];
+  unreadCount: number;
+  onOpen: (notification: ServerNotification) => void;
+  onMarkAllRead: () => void;
+  onClose: () => void;
+}
+
+function formatWhen(value: string): string {
+  const date = new Date(value);
+  return date.toDateString() === new Date().toDateString()
+    ? date.toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" })
+    : date.toLocaleDateString([], { day: "numeric", month: "short" });
+}
+
+export function NotificationsPanel({
+  isOpen,
+  notifications,
+  unreadCount,
+  onOpen,
+  onMarkAllRead,
+  onClose,
+}: NotificationsPanelProps) {
+  if (!isOpen) return null;
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/50" />
+
+      {/* Dialog */}
+      <div className="relative bg-background rounded-lg shadow-xl p-5 mx-4 max-w-sm w-full border border-border max-h-[90vh] flex flex-col">
+        <div className="flex items-center justify-between mb-3">
+          <h2 className="text-lg font-semibold">Notifications</h2>
+          <div className="flex items-center gap-3">
+            {unreadCount > 0 && (
+              <button type="button" onClick={onMarkAllRead} className="text-xs text-muted-foreground">
+                Mark all read
+              </button>
+            )}
+            <button type="button" onClick={onClose} aria-label="Close notifications">
+              <IconX className="w-5 h-5" />
+            </button>
+          </div>
+        </div>
+
+        <ul className="flex flex-col gap-2 overflow-y-auto">
+          {notifications.length === 0 && (
+            <li className="text-sm text-muted-foreground text-center py-6">No notifications yet.</li>
+          )}
+          {notifications.map((notification) => (
+            <li key={notification.id}>
+              <button
+                type="button"
+                onClick={() => onOpen(notification)}
+                className={cn(
+                  "w-full text-left rounded-lg border border-border p-2 transition-colors hover:bg-primary/5",
+                  !notification.isRead && "border-primary/40"
+                )}
+              >
+                <div className="flex items-center justify-between gap-2">
+                  <span className={cn("text-sm truncate", !notification.isRead && "font-medium")}>
+                    {notification.title}
+                  </span>
+                  <span className="text-xs text-muted-foreground shrink-0">
+                    {formatWhen(notification.createdAt)}
+                  </span>
+                </div>
+                <div className="text-xs text-muted-foreground line-clamp-2">{notification.message}</div>
+              </button>
+            </li>
+          ))}
+        </ul>
+      </div>
+    </div>
+  );
+}
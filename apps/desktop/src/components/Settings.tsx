@@ -1,13 +1,34 @@
 import { useEffect, useId, useState } from "react";
 import { IconX, IconLogout2, IconSettings as SettingsIcon } from "@tabler/icons-react";
-import type { Settings as SettingsType } from "../types";
+import type {
+  Device,
+  JournalEntry,
+  LocationRule,
+  NetworkSignals,
+  Profile,
+  Settings as SettingsType,
+  SettingsUpdate,
+  TriggerRule,
+} from "../types";
+import { ActivityLog } from "./ActivityLog";
+import { DeviceList } from "./DeviceList";
+import { LocationRules } from "./LocationRules";
+import { TriggerRules } from "./TriggerRules";
 
 interface SettingsProps {
   isOpen: boolean;
   onClose: () => void;
   settings: SettingsType | undefined;
-  onSave: (settings: Omit<SettingsType, "version">) => Promise<void>;
+  onSave: (settings: SettingsUpdate) => Promise<void>;
   onLogout: () => void;
+  onReadNetworkSignals?: () => Promise<NetworkSignals>;
+  journalEntries?: JournalEntry[];
+  onExportJournal?: () => void;
+  isExportingJournal?: boolean;
+  devices?: Device[];
+  onRevokeDevice?: (device: Device) => void;
+  isRevokingDevice?: boolean;
+  profile?: Profile | null;
   isSaving: boolean;
   isAuthenticated: boolean;
 }
@@ -15,20 +36,42 @@ interface SettingsProps {
 interface SettingsViewModel {
   alwaysOnTop: boolean;
   autoStartup: boolean;
+  breakRemindersEnabled: boolean;
   cancelHovered: boolean;
+  controlApiEnabled: boolean;
+  controlApiFile: string;
+  devices: Device[];
+  endOfDayTime: string;
   isAuthenticated: boolean;
+  isExportingJournal: boolean;
+  isRevokingDevice: boolean;
   isSaving: boolean;
+  journalEntries: JournalEntry[];
+  locationRules: LocationRule[];
   logoutHovered: boolean;
+  managedLocationRules: LocationRule[];
+  profile: Profile | null;
+  triggerRules: TriggerRule[];
   saveHovered: boolean;
+  shiftRemindersEnabled: boolean;
   version: string;
   webappUrl: string;
   onCancelHoverChange: (hovered: boolean) => void;
+  onEndOfDayTimeChange: (value: string) => void;
+  onExportJournal?: () => void;
+  onLocationRulesChange: (rules: LocationRule[]) => void;
+  onTriggerRulesChange: (rules: TriggerRule[]) => void;
   onLogout: () => void;
   onLogoutHoverChange: (hovered: boolean) => void;
+  onReadNetworkSignals?: () => Promise<NetworkSignals>;
+  onRevokeDevice?: (device: Device) => void;
   onSave: () => void;
   onSaveHoverChange: (hovered: boolean) => void;
   onToggleAlwaysOnTop: () => void;
   onToggleAutoStartup: () => void;
+  onToggleBreakReminders: () => void;
+  onToggleControlApi: () => void;
+  onToggleShiftReminders: () => void;
   onWebappUrlChange: (value: string) => void;
 }
 
@@ -167,6 +210,7 @@ function SettingsContent({ viewModel }: { viewModel: SettingsViewModel }) {
           type="url"
           autoComplete="off"
           value={viewModel.webappUrl}
+          disabled={viewModel.isAuthenticated}
           onChange={(event) => viewModel.onWebappUrlChange(event.target.value)}
           placeholder="Example: https://ui.z8-time.app…"
           style={{
@@ -191,7 +235,9 @@ function SettingsContent({ viewModel }: { viewModel: SettingsViewModel }) {
           }}
         />
         <p style={{ fontSize: "12px", color: "var(--color-muted-foreground)", marginTop: "6px" }}>
-          The URL of your Z8 webapp instance
+          {viewModel.isAuthenticated
+            ? "Sign out to connect to a different webapp"
+            : "The URL of your Z8 webapp instance"}
         </p>
       </div>
 
@@ -207,9 +253,101 @@ function SettingsContent({ viewModel }: { viewModel: SettingsViewModel }) {
         label="Start with Windows"
         onToggle={viewModel.onToggleAutoStartup}
       />
+      <SettingsToggle
+        checked={viewModel.breakRemindersEnabled}
+        description="Notify before required breaks and daily limits"
+        label="Break reminders"
+        onToggle={viewModel.onToggleBreakReminders}
+      />
+      <SettingsToggle
+        checked={viewModel.shiftRemindersEnabled}
+        description="Notify shortly before your shift starts and ends"
+        label="Shift reminders"
+        onToggle={viewModel.onToggleShiftReminders}
+      />
+      <div>
+        <SettingsToggle
+          checked={viewModel.controlApiEnabled}
+          description="Let scripts and editor plugins clock in through a local API"
+          label="Local control API"
+          onToggle={viewModel.onToggleControlApi}
+        />
+        {viewModel.controlApiEnabled && viewModel.controlApiFile && (
+          <p style={{ fontSize: "12px", color: "var(--color-muted-foreground)", marginTop: "6px" }}>
+            Address and token: <code style={{ wordBreak: "break-all" }}>{viewModel.controlApiFile}</code>
+          </p>
+        )}
+      </div>
+
+      <div>
+        <label
+          htmlFor="end-of-day-time"
+          style={{
+            display: "block",
+            fontSize: "14px",
+            fontWeight: 500,
+            marginBottom: "8px",
+            color: "var(--color-foreground)",
+          }}
+        >
+          End of day
+        </label>
+        <input
+          id="end-of-day-time"
+          name="endOfDayTime"
+          type="time"
+          value={viewModel.endOfDayTime}
+          onChange={(event) => viewModel.onEndOfDayTimeChange(event.target.value)}
+          style={{
+            width: "100%",
+            padding: "12px 14px",
+            fontSize: "14px",
+            borderRadius: "10px",
+            border: "2px solid var(--color-border)",
+            background: "var(--color-background)",
+            color: "var(--color-foreground)",
+            outline: "none",
+            boxSizing: "border-box",
+          }}
+        />
+        <p style={{ fontSize: "12px", color: "var(--color-muted-foreground)", marginTop: "6px" }}>
+          Ask to clock out if the timer is still running after this time. Leave empty to turn off.
+        </p>
+      </div>
+
+      <LocationRules
+        rules={viewModel.locationRules}
+        managedRules={viewModel.managedLocationRules}
+        onChange={viewModel.onLocationRulesChange}
+        onReadNetworkSignals={viewModel.onReadNetworkSignals}
+      />
+
+      <TriggerRules rules={viewModel.triggerRules} onChange={viewModel.onTriggerRulesChange} />
+
+      {viewModel.isAuthenticated && viewModel.onRevokeDevice && (
+        <DeviceList
+          devices={viewModel.devices}
+          onRevoke={viewModel.onRevokeDevice}
+          isRevoking={viewModel.isRevokingDevice}
+        />
+      )}
+
+      {viewModel.onExportJournal && (
+        <ActivityLog
+          entries={viewModel.journalEntries}
+          onExport={viewModel.onExportJournal}
+          isExporting={viewModel.isExportingJournal}
+        />
+      )}
 
       <hr style={{ border: "none", borderTop: "1px solid var(--color-border)", margin: 0 }} />
 
+      {viewModel.isAuthenticated && viewModel.profile && (
+        <div style={{ fontSize: "12px", color: "var(--color-muted-foreground)", textAlign: "center" }}>
+          Signed in as {viewModel.profile.name} ({viewModel.profile.email})
+        </div>
+      )}
+
       {viewModel.isAuthenticated && (
         <button
           type="button"
@@ -306,12 +444,30 @@ export function Settings({
   settings,
   onSave,
   onLogout,
+  onReadNetworkSignals,
+  journalEntries,
+  onExportJournal,
+  isExportingJournal,
+  devices,
+  onRevokeDevice,
+  isRevokingDevice,
+  profile,
   isSaving,
   isAuthenticated,
 }: SettingsProps) {
   const [webappUrl, setWebappUrl] = useState(settings?.webappUrl ?? "");
   const [alwaysOnTop, setAlwaysOnTop] = useState(settings?.alwaysOnTop ?? true);
   const [autoStartup, setAutoStartup] = useState(settings?.autoStartup ?? false);
+  const [breakRemindersEnabled, setBreakRemindersEnabled] = useState(
+    settings?.breakRemindersEnabled ?? true,
+  );
+  const [shiftRemindersEnabled, setShiftRemindersEnabled] = useState(
+    settings?.shiftRemindersEnabled ?? true,
+  );
+  const [endOfDayTime, setEndOfDayTime] = useState(settings?.endOfDayTime ?? "");
+  const [controlApiEnabled, setControlApiEnabled] = useState(settings?.controlApiEnabled ?? false);
+  const [locationRules, setLocationRules] = useState<LocationRule[]>(settings?.locationRules ?? []);
+  const [triggerRules, setTriggerRules] = useState<TriggerRule[]>(settings?.triggerRules ?? []);
   const [saveHovered, setSaveHovered] = useState(false);
   const [cancelHovered, setCancelHovered] = useState(false);
   const [logoutHovered, setLogoutHovered] = useState(false);
@@ -321,13 +477,29 @@ export function Settings({
       setWebappUrl(settings.webappUrl);
       setAlwaysOnTop(settings.alwaysOnTop);
       setAutoStartup(settings.autoStartup);
+      setBreakRemindersEnabled(settings.breakRemindersEnabled);
+      setShiftRemindersEnabled(settings.shiftRemindersEnabled);
+      setEndOfDayTime(settings.endOfDayTime ?? "");
+      setLocationRules(settings.locationRules);
+      setControlApiEnabled(settings.controlApiEnabled);
+      setTriggerRules(settings.triggerRules);
     }
   }, [settings]);
 
   if (!isOpen) return null;
 
   const handleSave = async () => {
-    await onSave({ webappUrl, alwaysOnTop, autoStartup });
+    await onSave({
+      webappUrl,
+      alwaysOnTop,
+      autoStartup,
+      breakRemindersEnabled,
+      shiftRemindersEnabled,
+      endOfDayTime,
+      locationRules,
+      triggerRules,
+      controlApiEnabled,
+    });
     onClose();
   };
 
@@ -339,20 +511,42 @@ export function Settings({
   const viewModel: SettingsViewModel = {
     alwaysOnTop,
     autoStartup,
+    breakRemindersEnabled,
     cancelHovered,
+    controlApiEnabled,
+    controlApiFile: settings?.controlApiFile ?? "",
+    devices: devices ?? [],
+    endOfDayTime,
     isAuthenticated,
+    isExportingJournal: isExportingJournal ?? false,
+    isRevokingDevice: isRevokingDevice ?? false,
     isSaving,
+    journalEntries: journalEntries ?? [],
+    locationRules,
     logoutHovered,
+    managedLocationRules: settings?.managedLocationRules ?? [],
+    profile: profile ?? null,
+    triggerRules,
     saveHovered,
+    shiftRemindersEnabled,
     version: settings?.version ?? "0.1.0",
     webappUrl,
     onCancelHoverChange: setCancelHovered,
+    onEndOfDayTimeChange: setEndOfDayTime,
+    onExportJournal,
+    onLocationRulesChange: setLocationRules,
+    onTriggerRulesChange: setTriggerRules,
     onLogout: handleLogout,
     onLogoutHoverChange: setLogoutHovered,
+    onReadNetworkSignals,
+    onRevokeDevice,
     onSave: handleSave,
     onSaveHoverChange: setSaveHovered,
     onToggleAlwaysOnTop: () => setAlwaysOnTop(!alwaysOnTop),
     onToggleAutoStartup: () => setAutoStartup(!autoStartup),
+    onToggleBreakReminders: () => setBreakRemindersEnabled(!breakRemindersEnabled),
+    onToggleShiftReminders: () => setShiftRemindersEnabled(!shiftRemindersEnabled),
+    onToggleControlApi: () => setControlApiEnabled(!controlApiEnabled),
     onWebappUrlChange: setWebappUrl,
   };
 
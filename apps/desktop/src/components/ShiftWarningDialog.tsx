@@ -0,0 +1,85 @@
+import { IconAlertTriangle } from "@tabler/icons-react";
+import { cn } from "../lib/utils";
+import type { PunchWarning } from "../types";
+
+interface ShiftWarningDialogProps {
+  warning: PunchWarning | null;
+  onConfirm: () => void;
+  onCancel: () => void;
+  isLoading?: boolean;
+}
+
+function formatTime(value: string): string {
+  return new Date(value).toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" });
+}
+
+function formatMinutes(minutes: number): string {
+  if (minutes < 60) return `${minutes} min`;
+  const hours = Math.floor(minutes / 60);
+  const rest = minutes % 60;
+  return rest > 0 ? `${hours} h ${rest} min` : `${hours} h`;
+}
+
+export function ShiftWarningDialog({
+  warning,
+  onConfirm,
+  onCancel,
+  isLoading,
+}: ShiftWarningDialogProps) {
+  if (!warning) return null;
+
+  const shiftTime = `${formatTime(warning.shift.start)}–${formatTime(warning.shift.end)}`;
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/50" />
+
+      {/* Dialog */}
+      <div className="relative bg-background rounded-lg shadow-xl p-5 mx-4 max-w-sm w-full border border-border">
+        <div className="text-center">
+          <IconAlertTriangle className="w-8 h-8 mx-auto mb-2 text-amber-500" />
+          <h2 className="text-lg font-semibold mb-2">Outside your shift</h2>
+          <p className="text-sm text-muted-foreground mb-4">
+            {warning.timing === "early"
+              ? `Your shift starts in ${formatMinutes(warning.minutes)}`
+              : `Your shift ended ${formatMinutes(warning.minutes)} ago`}{" "}
+            (<span className="font-medium">{shiftTime}</span>).
+            <br />
+            Clock in anyway?
+          </p>
+
+          <div className="flex flex-col gap-3">
+            <button
+              type="button"
+              onClick={onConfirm}
+              disabled={isLoading}
+              className={cn(
+                "w-full py-3 px-4",
+                "bg-amber-500 hover:bg-amber-600 text-white rounded-lg",
+                "transition-colors font-medium",
+                "disabled:opacity-50 disabled:cursor-not-allowed"
+              )}
+            >
+              Clock in now
+            </button>
+
+            <button
+              type="button"
+              onClick={onCancel}
+              disabled={isLoading}
+              className={cn(
+                "w-full py-3 px-4",
+                "bg-primary hover:bg-primary/90 text-primary-foreground rounded-lg",
+                "transition-colors font-medium",
+                "disabled:opacity-50 disabled:cursor-not-allowed"
+              )}
+            >
+              Cancel
+            </button>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
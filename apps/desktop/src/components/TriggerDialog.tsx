@@ -0,0 +1,91 @@
+import { IconArrowBackUp, IconCheck } from "@tabler/icons-react";
+import { cn } from "../lib/utils";
+import type { FiredTrigger, TriggerAction } from "../types";
+
+interface TriggerDialogProps {
+  isOpen: boolean;
+  trigger: FiredTrigger | null;
+  onConfirm: () => void;
+  onUndo: () => void;
+  onDismiss: () => void;
+  isLoading?: boolean;
+}
+
+const TITLES: Record<TriggerAction, { performed: string; proposed: string; confirm: string }> = {
+  clockIn: { performed: "Clocked in automatically", proposed: "Clock in?", confirm: "Clock in" },
+  clockOut: { performed: "Clocked out automatically", proposed: "Clock out?", confirm: "Clock out" },
+  pause: { performed: "Paused automatically", proposed: "Pause?", confirm: "Pause" },
+};
+
+function formatTime(value: string): string {
+  return new Date(value).toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" });
+}
+
+export function TriggerDialog({ isOpen, trigger, onConfirm, onUndo, onDismiss, isLoading }: TriggerDialogProps) {
+  if (!isOpen || !trigger) return null;
+
+  const titles = TITLES[trigger.action];
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/50" />
+
+      {/* Dialog */}
+      <div className="relative bg-background rounded-lg shadow-xl p-5 mx-4 max-w-sm w-full border border-border">
+        <div className="text-center">
+          <h2 className="text-lg font-semibold mb-2">
+            {trigger.performed ? titles.performed : titles.proposed}
+          </h2>
+          <p className="text-sm text-muted-foreground mb-4">
+            {trigger.reason}
+            {trigger.performed && ` (at ${formatTime(trigger.at)})`}.
+          </p>
+
+          <div className="flex flex-col gap-3">
+            {trigger.performed ? (
+              <button
+                type="button"
+                onClick={onUndo}
+                disabled={isLoading}
+                className={cn(
+                  "flex items-center justify-center gap-2 w-full py-3 px-4",
+                  "bg-primary hover:bg-primary/90 text-primary-foreground rounded-lg",
+                  "transition-colors font-medium",
+                  "disabled:opacity-50 disabled:cursor-not-allowed"
+                )}
+              >
+                <IconArrowBackUp className="w-5 h-5" />
+                Undo
+              </button>
+            ) : (
+              <button
+                type="button"
+                onClick={onConfirm}
+                disabled={isLoading}
+                className={cn(
+                  "flex items-center justify-center gap-2 w-full py-3 px-4",
+                  "bg-primary hover:bg-primary/90 text-primary-foreground rounded-lg",
+                  "transition-colors font-medium",
+                  "disabled:opacity-50 disabled:cursor-not-allowed"
+                )}
+              >
+                <IconCheck className="w-5 h-5" />
+                {titles.confirm}
+              </button>
+            )}
+
+            <button
+              type="button"
+              onClick={onDismiss}
+              disabled={isLoading}
+              className="text-sm text-muted-foreground"
+            >
+              {trigger.performed ? "Keep it" : "Not now"}
+            </button>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,193 @@
+import { IconPlus, IconTrash } from "@tabler/icons-react";
+import type { LocationSignal, TriggerAction, TriggerEvent, TriggerMode, TriggerRule } from "../types";
+
+interface TriggerRulesProps {
+  rules: TriggerRule[];
+  onChange: (rules: TriggerRule[]) => void;
+}
+
+const EVENT_OPTIONS: { value: TriggerEvent; label: string }[] = [
+  { value: "activity", label: "First activity" },
+  { value: "idle", label: "Away / session locked" },
+  { value: "networkJoined", label: "Network connected" },
+  { value: "networkLeft", label: "Network disconnected" },
+];
+
+const ACTION_OPTIONS: { value: TriggerAction; label: string }[] = [
+  { value: "clockIn", label: "Clock in" },
+  { value: "clockOut", label: "Clock out" },
+  { value: "pause", label: "Pause" },
+];
+
+const MODE_OPTIONS: { value: TriggerMode; label: string }[] = [
+  { value: "propose", label: "Ask first" },
+  { value: "perform", label: "Do it, allow undo" },
+];
+
+const SIGNAL_OPTIONS: { value: LocationSignal; label: string }[] = [
+  { value: "wifiSsid", label: "Wi-Fi name" },
+  { value: "gatewayMac", label: "Gateway MAC" },
+  { value: "vpnInterface", label: "VPN interface" },
+];
+
+const fieldStyle = {
+  padding: "8px 10px",
+  fontSize: "13px",
+  borderRadius: "8px",
+  border: "1px solid var(--color-border)",
+  background: "var(--color-background)",
+  color: "var(--color-foreground)",
+  minWidth: 0,
+} as const;
+
+const iconButtonStyle = {
+  padding: "6px",
+  borderRadius: "8px",
+  border: "none",
+  background: "transparent",
+  color: "var(--color-muted-foreground)",
+  cursor: "pointer",
+  display: "flex",
+  alignItems: "center",
+} as const;
+
+const NEW_RULE: TriggerRule = {
+  event: "activity",
+  network: null,
+  after: "07:00",
+  before: null,
+  action: "clockIn",
+  mode: "propose",
+};
+
+function isNetworkEvent(event: TriggerEvent): boolean {
+  return event === "networkJoined" || event === "networkLeft";
+}
+
+export function TriggerRules({ rules, onChange }: TriggerRulesProps) {
+  const updateRule = (index: number, patch: Partial<TriggerRule>) => {
+    onChange(rules.map((rule, ruleIndex) => (ruleIndex === index ? { ...rule, ...patch } : rule)));
+  };
+
+  return (
+    <div>
+      <div style={{ fontSize: "14px", fontWeight: 500, marginBottom: "8px" }}>Automatic clock-in/out</div>
+      <div style={{ display: "flex", flexDirection: "column", gap: "12px" }}>
+        {rules.map((rule, index) => (
+          <div key={`${index}-${rule.event}`} style={{ display: "flex", flexDirection: "column", gap: "6px" }}>
+            <div style={{ display: "grid", gridTemplateColumns: "1.3fr 1fr 1.2fr auto", gap: "6px" }}>
+              <select
+                aria-label="When"
+                value={rule.event}
+                onChange={(event) => {
+                  const value = event.target.value as TriggerEvent;
+                  updateRule(index, {
+                    event: value,
+                    network: isNetworkEvent(value) ? (rule.network ?? { signal: "vpnInterface", value: "" }) : rule.network,
+                  });
+                }}
+                style={fieldStyle}
+              >
+                {EVENT_OPTIONS.map((option) => (
+                  <option key={option.value} value={option.value}>
+                    {option.label}
+                  </option>
+                ))}
+              </select>
+              <select
+                aria-label="Action"
+                value={rule.action}
+                onChange={(event) => updateRule(index, { action: event.target.value as TriggerAction })}
+                style={fieldStyle}
+              >
+                {ACTION_OPTIONS.map((option) => (
+                  <option key={option.value} value={option.value}>
+                    {option.label}
+                  </option>
+                ))}
+              </select>
+              <select
+                aria-label="Mode"
+                value={rule.mode}
+                onChange={(event) => updateRule(index, { mode: event.target.value as TriggerMode })}
+                style={fieldStyle}
+              >
+                {MODE_OPTIONS.map((option) => (
+                  <option key={option.value} value={option.value}>
+                    {option.label}
+                  </option>
+                ))}
+              </select>
+              <button
+                type="button"
+                aria-label="Remove rule"
+                onClick={() => onChange(rules.filter((_, ruleIndex) => ruleIndex !== index))}
+                style={iconButtonStyle}
+              >
+                <IconTrash size={16} />
+              </button>
+            </div>
+            <div style={{ display: "grid", gridTemplateColumns: "1fr 1.3fr 0.8fr 0.8fr", gap: "6px" }}>
+              <select
+                aria-label="Network"
+                value={rule.network?.signal ?? ""}
+                onChange={(event) => {
+                  const signal = event.target.value as LocationSignal | "";
+                  updateRule(index, {
+                    network: signal ? { signal, value: rule.network?.value ?? "" } : null,
+                  });
+                }}
+                style={fieldStyle}
+              >
+                {!isNetworkEvent(rule.event) && <option value="">Any network</option>}
+                {SIGNAL_OPTIONS.map((option) => (
+                  <option key={option.value} value={option.value}>
+                    {option.label}
+                  </option>
+                ))}
+              </select>
+              <input
+                aria-label="Network value"
+                value={rule.network?.value ?? ""}
+                disabled={!rule.network}
+                placeholder={rule.network ? "Office-WLAN, wg, …" : ""}
+                onChange={(event) =>
+                  rule.network && updateRule(index, { network: { ...rule.network, value: event.target.value } })
+                }
+                style={fieldStyle}
+              />
+              <input
+                type="time"
+                aria-label="After"
+                title="After"
+                value={rule.after ?? ""}
+                onChange={(event) => updateRule(index, { after: event.target.value || null })}
+                style={fieldStyle}
+              />
+              <input
+                type="time"
+                aria-label="Before"
+                title="Before"
+                value={rule.before ?? ""}
+                onChange={(event) => updateRule(index, { before: event.target.value || null })}
+                style={fieldStyle}
+              />
+            </div>
+          </div>
+        ))}
+
+        <button
+          type="button"
+          onClick={() => onChange([...rules, NEW_RULE])}
+          style={{ ...iconButtonStyle, gap: "4px", fontSize: "13px", alignSelf: "flex-start" }}
+        >
+          <IconPlus size={16} />
+          Add rule
+        </button>
+      </div>
+      <p style={{ fontSize: "12px", color: "var(--color-muted-foreground)", marginTop: "6px" }}>
+        Automatic punches are saved through the offline queue and can be undone from the notification dialog.
+      </p>
+    </div>
+  );
+}
@@ -0,0 +1,102 @@
+import { IconAlertTriangle } from "@tabler/icons-react";
+import { useState } from "react";
+import type { UnsentAction } from "../types";
+
+interface UnsentActionsProps {
+  actions: UnsentAction[];
+  onRetry: (action: UnsentAction) => void;
+  onDiscard: (action: UnsentAction) => void;
+  isUpdating: boolean;
+}
+
+function describe(action: UnsentAction): string {
+  const name = action.action.replace(/_/g, " ");
+  const label = `${name.charAt(0).toUpperCase()}${name.slice(1)}`;
+  if (!action.queuedAt) return label;
+
+  return `${label} · ${new Date(action.queuedAt).toLocaleString([], {
+    month: "short",
+    day: "numeric",
+    hour: "2-digit",
+    minute: "2-digit",
+  })}`;
+}
+
+const buttonStyle = (isUpdating: boolean, color: string) => ({
+  padding: "4px 8px",
+  fontSize: "12px",
+  color,
+  background: "transparent",
+  border: "1px solid var(--color-border)",
+  borderRadius: "8px",
+  cursor: isUpdating ? "not-allowed" : "pointer",
+  opacity: isUpdating ? 0.6 : 1,
+});
+
+/**
+ * Queued actions the webapp kept refusing. A refused punch holds back the punches
+ * after it, so the user decides whether to send it again or drop it.
+ */
+export function UnsentActions({ actions, onRetry, onDiscard, isUpdating }: UnsentActionsProps) {
+  // Discarding takes a second click, so a stray one can't drop a punch
+  const [confirmingId, setConfirmingId] = useState<number | null>(null);
+
+  if (actions.length === 0) return null;
+
+  const handleDiscard = (action: UnsentAction) => {
+    if (confirmingId !== action.id) {
+      setConfirmingId(action.id);
+      return;
+    }
+    setConfirmingId(null);
+    onDiscard(action);
+  };
+
+  const holdsPunches = actions.some((action) => action.isPunch);
+
+  return (
+    <div
+      role="alert"
+      style={{
+        width: "100%",
+        padding: "10px 12px",
+        border: "1px solid hsl(38 92% 50%)",
+        borderRadius: "10px",
+        fontSize: "13px",
+      }}
+    >
+      <div style={{ display: "flex", alignItems: "center", gap: "8px", fontWeight: 500 }}>
+        <IconAlertTriangle size={16} style={{ color: "hsl(38 92% 50%)", flexShrink: 0 }} aria-hidden="true" />
+        {actions.length === 1 ? "An action could not be sent" : `${actions.length} actions could not be sent`}
+      </div>
+      <p style={{ fontSize: "12px", color: "var(--color-muted-foreground)", margin: "4px 0 8px" }}>
+        The webapp refused {actions.length === 1 ? "it" : "them"} several times.
+        {holdsPunches && " Punches recorded after a refused punch wait until it is sent or discarded."}
+      </p>
+      <ul style={{ listStyle: "none", margin: 0, padding: 0, display: "flex", flexDirection: "column", gap: "6px" }}>
+        {actions.map((action) => (
+          <li key={action.id} style={{ display: "flex", alignItems: "center", gap: "8px" }}>
+            <span style={{ flex: 1, minWidth: 0 }}>{describe(action)}</span>
+            <button
+              type="button"
+              onClick={() => onRetry(action)}
+              disabled={isUpdating}
+              style={buttonStyle(isUpdating, "var(--color-foreground)")}
+            >
+              Retry
+            </button>
+            <button
+              type="button"
+              onClick={() => handleDiscard(action)}
+              onBlur={() => setConfirmingId(null)}
+              disabled={isUpdating}
+              style={buttonStyle(isUpdating, "var(--color-destructive)")}
+            >
+              {confirmingId === action.id ? "Confirm" : "Discard"}
+            </button>
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+}
@@ -0,0 +1,81 @@
+import { IconBellOff, IconDroplet } from "@tabler/icons-react";
+import { cn } from "../lib/utils";
+import type { WaterReminderStatus } from "../types";
+
+interface WaterReminderDialogProps {
+  isOpen: boolean;
+  reminder: WaterReminderStatus | null;
+  onLog: () => void;
+  onSnooze: () => void;
+  onDismiss: () => void;
+  isLoading?: boolean;
+}
+
+export function WaterReminderDialog({
+  isOpen,
+  reminder,
+  onLog,
+  onSnooze,
+  onDismiss,
+  isLoading,
+}: WaterReminderDialogProps) {
+  if (!isOpen || !reminder) return null;
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/50" />
+
+      {/* Dialog */}
+      <div className="relative bg-background rounded-lg shadow-xl p-5 mx-4 max-w-sm w-full border border-border">
+        <div className="text-center">
+          <h2 className="text-lg font-semibold mb-2">Time to hydrate!</h2>
+          <p className="text-sm text-muted-foreground mb-4">
+            Stay healthy - drink some water. Your goal is {reminder.dailyGoal} glasses a day.
+          </p>
+
+          <div className="flex flex-col gap-3">
+            <button
+              type="button"
+              onClick={onLog}
+              disabled={isLoading}
+              className={cn(
+                "flex items-center justify-center gap-2 w-full py-3 px-4",
+                "bg-sky-500 hover:bg-sky-600 text-white rounded-lg",
+                "transition-colors font-medium",
+                "disabled:opacity-50 disabled:cursor-not-allowed"
+              )}
+            >
+              <IconDroplet className="w-5 h-5" />
+              Done it!
+            </button>
+
+            <button
+              type="button"
+              onClick={onSnooze}
+              disabled={isLoading}
+              className={cn(
+                "flex items-center justify-center gap-2 w-full py-3 px-4",
+                "bg-primary hover:bg-primary/90 text-primary-foreground rounded-lg",
+                "transition-colors font-medium",
+                "disabled:opacity-50 disabled:cursor-not-allowed"
+              )}
+            >
+              <IconBellOff className="w-5 h-5" />
+              Snooze today
+            </button>
+
+            <button
+              type="button"
+              onClick={onDismiss}
+              disabled={isLoading}
+              className="text-sm text-muted-foreground"
+            >
+              Later
+            </button>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,13 +1,20 @@
 import { IconMapPin } from "@tabler/icons-react";
-import { WORK_LOCATION_OPTIONS, type WorkLocationType } from "../types";
+import { WORK_LOCATION_OPTIONS, type LocationSuggestion, type WorkLocationType } from "../types";
 
 interface WorkLocationSelectorProps {
   value: WorkLocationType;
   onChange: (value: WorkLocationType) => void;
+  suggestion?: LocationSuggestion | null;
   disabled?: boolean;
 }
 
-export function WorkLocationSelector({ value, onChange, disabled }: WorkLocationSelectorProps) {
+const SIGNAL_LABELS: Record<LocationSuggestion["signal"], string> = {
+  wifiSsid: "Wi-Fi",
+  gatewayMac: "network",
+  vpnInterface: "VPN",
+};
+
+export function WorkLocationSelector({ value, onChange, suggestion, disabled }: WorkLocationSelectorProps) {
   return (
     <div className="work-location-selector" aria-label="Work location">
       <div className="work-location-label">
@@ -32,6 +39,13 @@ export function WorkLocationSelector({ value, onChange, disabled }: WorkLocation
           </label>
         ))}
       </div>
+      {suggestion && (
+        <div className="work-location-hint">
+          {value === suggestion.location
+            ? `Detected from ${SIGNAL_LABELS[suggestion.signal]} "${suggestion.value}"`
+            : `${SIGNAL_LABELS[suggestion.signal]} "${suggestion.value}" suggests ${suggestion.location}`}
+        </div>
+      )}
     </div>
   );
 }
@@ -0,0 +1,64 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect } from "react";
+import type { Absence, AbsenceOverview, AbsenceRequest, MyRequests } from "../types";
+
+export function useAbsences({ enabled = true }: { enabled?: boolean } = {}) {
+  const queryClient = useQueryClient();
+
+  const absencesQuery = useQuery({
+    queryKey: ["absences"],
+    queryFn: () => invoke<AbsenceOverview>("list_absences"),
+    refetchOnWindowFocus: true,
+    enabled,
+  });
+
+  const myRequestsQuery = useQuery({
+    queryKey: ["myRequests"],
+    queryFn: () => invoke<MyRequests>("list_my_requests"),
+    refetchOnWindowFocus: true,
+    enabled,
+  });
+
+  const invalidate = () => {
+    queryClient.invalidateQueries({ queryKey: ["absences"] });
+    queryClient.invalidateQueries({ queryKey: ["myRequests"] });
+  };
+
+  useEffect(() => {
+    const unlisten = listen<Absence>("absence_decided", () => {
+      queryClient.invalidateQueries({ queryKey: ["absences"] });
+      queryClient.invalidateQueries({ queryKey: ["myRequests"] });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+
+  const requestAbsenceMutation = useMutation({
+    mutationFn: (request: AbsenceRequest) =>
+      invoke<string>("request_absence", {
+        request: { ...request, notes: request.notes || null },
+      }),
+    onSuccess: invalidate,
+  });
+
+  const cancelAbsenceMutation = useMutation({
+    mutationFn: (absenceId: string) => invoke<void>("cancel_absence", { absenceId }),
+    onSuccess: invalidate,
+  });
+
+  return {
+    categories: absencesQuery.data?.categories ?? [],
+    absences: absencesQuery.data?.absences ?? [],
+    vacationBalance: absencesQuery.data?.vacationBalance ?? null,
+    requests: myRequestsQuery.data?.items ?? [],
+    pendingCount: myRequestsQuery.data?.counts.pending ?? 0,
+    isLoading: absencesQuery.isLoading,
+    requestAbsence: requestAbsenceMutation.mutateAsync,
+    cancelAbsence: cancelAbsenceMutation.mutateAsync,
+    isSubmitting: requestAbsenceMutation.isPending || cancelAbsenceMutation.isPending,
+  };
+}
@@ -0,0 +1,79 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import type { ApprovalItem, ApprovalPage, BulkDecisionResult } from "../types";
+
+export function useApprovals({ enabled = true }: { enabled?: boolean } = {}) {
+  const queryClient = useQueryClient();
+  const [isInboxOpen, setIsInboxOpen] = useState(false);
+  const [rejectingId, setRejectingId] = useState<string | null>(null);
+
+  const approvalsQuery = useQuery({
+    queryKey: ["approvals"],
+    queryFn: () => invoke<ApprovalPage>("list_approvals", { cursor: null, limit: 50 }),
+    enabled: enabled && isInboxOpen,
+  });
+
+  useEffect(() => {
+    const listeners = [
+      listen("open_approvals", () => setIsInboxOpen(true)),
+      listen<string>("open_approval_reject", (event) => {
+        setIsInboxOpen(true);
+        setRejectingId(event.payload);
+      }),
+      listen<ApprovalItem>("approval_requested", () => {
+        queryClient.invalidateQueries({ queryKey: ["approvals"] });
+      }),
+      listen("approvals_updated", () => {
+        queryClient.invalidateQueries({ queryKey: ["approvals"] });
+      }),
+    ];
+
+    return () => {
+      for (const unlisten of listeners) {
+        unlisten.then((fn) => fn());
+      }
+    };
+  }, [queryClient]);
+
+  const invalidate = () => {
+    queryClient.invalidateQueries({ queryKey: ["approvals"] });
+  };
+
+  const approveMutation = useMutation({
+    mutationFn: (approvalIds: string[]) =>
+      approvalIds.length === 1
+        ? invoke<void>("approve_request", { approvalId: approvalIds[0] })
+        : invoke<BulkDecisionResult>("bulk_approve_requests", { approvalIds }),
+    onSuccess: invalidate,
+  });
+
+  const rejectMutation = useMutation({
+    mutationFn: ({ approvalIds, reason }: { approvalIds: string[]; reason: string }) =>
+      approvalIds.length === 1
+        ? invoke<void>("reject_request", { approvalId: approvalIds[0], reason })
+        : invoke<BulkDecisionResult>("bulk_reject_requests", { approvalIds, reason }),
+    onSuccess: () => {
+      setRejectingId(null);
+      invalidate();
+    },
+  });
+
+  return {
+    approvals: approvalsQuery.data?.items ?? [],
+    total: approvalsQuery.data?.total ?? 0,
+    isLoading: approvalsQuery.isLoading,
+    isInboxOpen,
+    openInbox: () => setIsInboxOpen(true),
+    closeInbox: () => {
+      setIsInboxOpen(false);
+      setRejectingId(null);
+    },
+    rejectingId,
+    setRejectingId,
+    approve: approveMutation.mutateAsync,
+    reject: rejectMutation.mutateAsync,
+    isDeciding: approveMutation.isPending || rejectMutation.isPending,
+  };
+}
@@ -57,6 +57,7 @@ export function useAuth() {
 
   return {
     isAuthenticated: sessionQuery.data?.isAuthenticated ?? false,
+    profile: sessionQuery.data?.profile ?? null,
     isLoading: sessionQuery.isLoading,
     login,
     logout,
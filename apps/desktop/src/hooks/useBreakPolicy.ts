@@ -0,0 +1,33 @@
+import { useQuery } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import type { BreakPolicy, BreakReminder } from "../types";
+
+export function useBreakPolicy({ enabled = true }: { enabled?: boolean } = {}) {
+  const [lastReminder, setLastReminder] = useState<BreakReminder | null>(null);
+
+  const policyQuery = useQuery({
+    queryKey: ["breakPolicy"],
+    queryFn: () => invoke<BreakPolicy | null>("get_break_policy"),
+    staleTime: 60 * 60000,
+    enabled,
+  });
+
+  useEffect(() => {
+    const unlisten = listen<BreakReminder>("break_reminder", (event) => {
+      setLastReminder(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return {
+    policy: policyQuery.data ?? null,
+    isLoading: policyQuery.isLoading,
+    lastReminder,
+    dismissReminder: () => setLastReminder(null),
+  };
+}
@@ -1,7 +1,8 @@
 import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
-import { useCallback } from "react";
-import type { ClockStatus, WorkLocationType } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import { useCallback, useEffect } from "react";
+import type { ClockStatus, LocationSuggestion, WorkLocationType } from "../types";
 
 export function useClock() {
   const queryClient = useQueryClient();
@@ -14,9 +15,29 @@ export function useClock() {
     retry: 1,
   });
 
+  // Punches made in the webapp or on a phone, picked up by the background sync
+  useEffect(() => {
+    const unlisten = listen<ClockStatus>("clock_status_changed", (event) => {
+      queryClient.setQueryData(["clock-status"], event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+
   const clockInMutation = useMutation({
-    mutationFn: (workLocationType: WorkLocationType) =>
-      invoke<ClockStatus>("clock_in", { workLocationType }),
+    mutationFn: ({
+      workLocationType,
+      locationSuggestion,
+    }: {
+      workLocationType: WorkLocationType;
+      locationSuggestion?: LocationSuggestion | null;
+    }) =>
+      invoke<ClockStatus>("clock_in", {
+        workLocationType,
+        locationSuggestion: locationSuggestion ?? null,
+      }),
     onSuccess: (data) => {
       queryClient.setQueryData(["clock-status"], data);
     },
@@ -29,6 +50,13 @@ export function useClock() {
     },
   });
 
+  const clockOutAtMutation = useMutation({
+    mutationFn: (clockOutTime: string) => invoke<ClockStatus>("clock_out_at", { clockOutTime }),
+    onSuccess: (data) => {
+      queryClient.setQueryData(["clock-status"], data);
+    },
+  });
+
   const clockOutWithBreakMutation = useMutation({
     mutationFn: ({
       breakStartTime,
@@ -42,6 +70,29 @@ export function useClock() {
     },
   });
 
+  const switchProjectMutation = useMutation({
+    mutationFn: ({
+      workLocationType,
+      projectId,
+      workCategoryId,
+      notes,
+    }: {
+      workLocationType: WorkLocationType;
+      projectId?: string | null;
+      workCategoryId?: string | null;
+      notes?: string | null;
+    }) =>
+      invoke<ClockStatus>("switch_project", {
+        workLocationType,
+        projectId: projectId ?? null,
+        workCategoryId: workCategoryId ?? null,
+        notes: notes ?? null,
+      }),
+    onSuccess: (data) => {
+      queryClient.setQueryData(["clock-status"], data);
+    },
+  });
+
   const refetch = useCallback(() => {
     return queryClient.invalidateQueries({ queryKey: ["clock-status"] });
   }, [queryClient]);
@@ -58,14 +109,22 @@ export function useClock() {
 
     clockIn: clockInMutation.mutateAsync,
     clockOut: clockOutMutation.mutateAsync,
+    clockOutAt: clockOutAtMutation.mutateAsync,
     clockOutWithBreak: clockOutWithBreakMutation.mutateAsync,
+    switchProject: switchProjectMutation.mutateAsync,
 
     isClockingIn: clockInMutation.isPending,
-    isClockingOut: clockOutMutation.isPending || clockOutWithBreakMutation.isPending,
+    isClockingOut:
+      clockOutMutation.isPending ||
+      clockOutAtMutation.isPending ||
+      clockOutWithBreakMutation.isPending,
+    isSwitchingProject: switchProjectMutation.isPending,
     isMutating:
       clockInMutation.isPending ||
       clockOutMutation.isPending ||
-      clockOutWithBreakMutation.isPending,
+      clockOutAtMutation.isPending ||
+      clockOutWithBreakMutation.isPending ||
+      switchProjectMutation.isPending,
 
     refetch,
   };
@@ -0,0 +1,92 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect } from "react";
+import type { Correction, CorrectionSubmission, WorkLocationType } from "../types";
+
+export function useCorrections({ enabled = true }: { enabled?: boolean } = {}) {
+  const queryClient = useQueryClient();
+
+  const correctionsQuery = useQuery({
+    queryKey: ["corrections"],
+    queryFn: () => invoke<Correction[]>("list_corrections"),
+    refetchOnWindowFocus: true,
+    enabled,
+  });
+
+  useEffect(() => {
+    const unlisten = listen<Correction>("correction_decided", () => {
+      queryClient.invalidateQueries({ queryKey: ["corrections"] });
+      queryClient.invalidateQueries({ queryKey: ["timeline"] });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+
+  const onSubmitted = () => {
+    queryClient.invalidateQueries({ queryKey: ["corrections"] });
+    queryClient.invalidateQueries({ queryKey: ["timeline"] });
+  };
+
+  const submitCorrectionMutation = useMutation({
+    mutationFn: ({
+      replacesEntryId,
+      timestamp,
+      reason,
+      workLocationType,
+      workCategoryId,
+    }: {
+      replacesEntryId: string;
+      timestamp: string;
+      reason: string;
+      workLocationType: WorkLocationType;
+      workCategoryId?: string | null;
+    }) =>
+      invoke<CorrectionSubmission>("submit_correction", {
+        replacesEntryId,
+        timestamp,
+        reason,
+        workLocationType,
+        workCategoryId: workCategoryId ?? null,
+      }),
+    onSuccess: onSubmitted,
+  });
+
+  const submitMissingPeriodMutation = useMutation({
+    mutationFn: ({
+      date,
+      clockInTime,
+      clockOutTime,
+      reason,
+      projectId,
+      workCategoryId,
+    }: {
+      date: string;
+      clockInTime: string;
+      clockOutTime: string;
+      reason: string;
+      projectId?: string | null;
+      workCategoryId?: string | null;
+    }) =>
+      invoke<CorrectionSubmission>("submit_missing_period", {
+        date,
+        clockInTime,
+        clockOutTime,
+        reason,
+        projectId: projectId ?? null,
+        workCategoryId: workCategoryId ?? null,
+      }),
+    onSuccess: onSubmitted,
+  });
+
+  return {
+    corrections: correctionsQuery.data ?? [],
+    pending: (correctionsQuery.data ?? []).filter((correction) => correction.status === "pending"),
+    isLoading: correctionsQuery.isLoading,
+    submitCorrection: submitCorrectionMutation.mutateAsync,
+    submitMissingPeriod: submitMissingPeriodMutation.mutateAsync,
+    isSubmitting: submitCorrectionMutation.isPending || submitMissingPeriodMutation.isPending,
+  };
+}
@@ -0,0 +1,43 @@
+import { useMutation, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import type { PendingDeepLink } from "../types";
+
+export function useDeepLinks() {
+  const queryClient = useQueryClient();
+  const [request, setRequest] = useState<PendingDeepLink | null>(null);
+
+  useEffect(() => {
+    const unlisten = listen<PendingDeepLink>("deep_link_requested", (event) => {
+      setRequest(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const confirmMutation = useMutation({
+    mutationFn: (id: number) => invoke<void>("confirm_deep_link", { id }),
+    onSuccess: () => {
+      setRequest(null);
+      queryClient.invalidateQueries({ queryKey: ["clock-status"] });
+    },
+  });
+
+  const dismiss = () => {
+    if (request) {
+      invoke("dismiss_deep_link", { id: request.id }).catch(console.error);
+    }
+    setRequest(null);
+  };
+
+  return {
+    request,
+    isRequestOpen: request !== null,
+    dismiss,
+    confirm: confirmMutation.mutateAsync,
+    isSaving: confirmMutation.isPending,
+  };
+}
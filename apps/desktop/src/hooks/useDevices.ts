@@ -0,0 +1,27 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import type { Device } from "../types";
+
+export function useDevices({ enabled = true }: { enabled?: boolean } = {}) {
+  const queryClient = useQueryClient();
+
+  const devicesQuery = useQuery({
+    queryKey: ["devices"],
+    queryFn: () => invoke<Device[]>("list_devices"),
+    enabled,
+  });
+
+  const revokeMutation = useMutation({
+    mutationFn: (deviceId: string) => invoke<void>("revoke_device", { deviceId }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["devices"] });
+    },
+  });
+
+  return {
+    devices: devicesQuery.data ?? [],
+    isLoading: devicesQuery.isLoading,
+    revokeDevice: revokeMutation.mutateAsync,
+    isRevoking: revokeMutation.isPending,
+  };
+}
@@ -0,0 +1,35 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import type { ClockOutPrompt } from "../types";
+
+export function useForgottenClockOut() {
+  const [prompt, setPrompt] = useState<ClockOutPrompt | null>(null);
+
+  useEffect(() => {
+    const unlisten = listen<ClockOutPrompt>("forgotten_clock_out", (event) => {
+      console.log("Forgotten clock-out prompt:", event.payload);
+      setPrompt(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const keepWorking = () => {
+    if (prompt) {
+      invoke("record_keep_working", { prompt: prompt.reason, since: prompt.lastActivity }).catch(
+        console.error,
+      );
+    }
+    setPrompt(null);
+  };
+
+  return {
+    prompt,
+    isPromptOpen: prompt !== null,
+    dismissPrompt: () => setPrompt(null),
+    keepWorking,
+  };
+}
@@ -1,3 +1,4 @@
+import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { useEffect, useState } from "react";
 import type { IdleEvent } from "../types";
@@ -23,9 +24,20 @@ export function useIdle() {
     setIdleEvent(null);
   };
 
+  /** Records that the idle time stays on the clock, then closes the dialog */
+  const keepWorking = () => {
+    if (idleEvent) {
+      invoke("record_keep_working", { prompt: "idle", since: idleEvent.idleStartTime }).catch(
+        console.error,
+      );
+    }
+    dismissIdle();
+  };
+
   return {
     idleEvent,
     isIdleDialogOpen,
     dismissIdle,
+    keepWorking,
   };
 }
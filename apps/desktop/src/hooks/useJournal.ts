@@ -0,0 +1,24 @@
+import { useMutation, useQuery } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import type { JournalEntry, JournalQuery } from "../types";
+
+export function useJournal({ enabled = true }: { enabled?: boolean } = {}) {
+  const journalQuery = useQuery({
+    queryKey: ["journal"],
+    queryFn: () => invoke<JournalEntry[]>("get_journal", { query: { limit: 50 } }),
+    refetchOnWindowFocus: true,
+    enabled,
+  });
+
+  const exportMutation = useMutation({
+    mutationFn: (query?: JournalQuery) => invoke<string>("export_journal", { query: query ?? null }),
+  });
+
+  return {
+    entries: journalQuery.data ?? [],
+    isLoading: journalQuery.isLoading,
+    refetch: journalQuery.refetch,
+    exportJournal: exportMutation.mutateAsync,
+    isExporting: exportMutation.isPending,
+  };
+}
@@ -0,0 +1,37 @@
+import { useQuery, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useCallback, useEffect } from "react";
+import type { PunchWarning, ScheduleView } from "../types";
+
+export function useSchedule({ enabled = true }: { enabled?: boolean } = {}) {
+  const queryClient = useQueryClient();
+
+  const scheduleQuery = useQuery({
+    queryKey: ["schedule"],
+    queryFn: () => invoke<ScheduleView>("get_schedule"),
+    refetchInterval: 5 * 60000,
+    refetchOnWindowFocus: true,
+    enabled,
+  });
+
+  useEffect(() => {
+    const unlisten = listen("schedule_updated", () => {
+      queryClient.invalidateQueries({ queryKey: ["schedule"] });
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+
+  // Checked against the cached schedule, so it answers instantly and offline
+  const checkClockIn = useCallback(() => invoke<PunchWarning | null>("check_clock_in"), []);
+
+  return {
+    nextShift: scheduleQuery.data?.nextShift ?? null,
+    shifts: scheduleQuery.data?.shifts ?? [],
+    isLoading: scheduleQuery.isLoading,
+    checkClockIn,
+  };
+}
@@ -0,0 +1,59 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect } from "react";
+import type { NotificationPage, ServerNotification } from "../types";
+
+export function useServerNotifications({ enabled = true }: { enabled?: boolean } = {}) {
+  const queryClient = useQueryClient();
+
+  const notificationsQuery = useQuery({
+    queryKey: ["serverNotifications"],
+    queryFn: () => invoke<NotificationPage>("list_notifications", { unreadOnly: false, limit: 20 }),
+    refetchOnWindowFocus: true,
+    enabled,
+  });
+
+  useEffect(() => {
+    const listeners = [
+      listen<ServerNotification>("server_notification", () => {
+        queryClient.invalidateQueries({ queryKey: ["serverNotifications"] });
+      }),
+      listen<number>("server_notification_count", () => {
+        queryClient.invalidateQueries({ queryKey: ["serverNotifications"] });
+      }),
+    ];
+
+    return () => {
+      for (const unlisten of listeners) {
+        unlisten.then((fn) => fn());
+      }
+    };
+  }, [queryClient]);
+
+  const openMutation = useMutation({
+    mutationFn: (notification: ServerNotification) =>
+      invoke<void>("open_notification", {
+        notificationId: notification.id,
+        actionUrl: notification.actionUrl,
+      }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["serverNotifications"] });
+    },
+  });
+
+  const markAllReadMutation = useMutation({
+    mutationFn: () => invoke<void>("mark_all_notifications_read"),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["serverNotifications"] });
+    },
+  });
+
+  return {
+    notifications: notificationsQuery.data?.notifications ?? [],
+    unreadCount: notificationsQuery.data?.unreadCount ?? 0,
+    isLoading: notificationsQuery.isLoading,
+    openNotification: openMutation.mutateAsync,
+    markAllRead: markAllReadMutation.mutateAsync,
+  };
+}
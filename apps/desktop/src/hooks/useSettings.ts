@@ -2,7 +2,13 @@ import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { useEffect, useState } from "react";
-import type { Settings } from "../types";
+import type {
+  ConnectionTestResult,
+  NetworkSettings,
+  NetworkSignals,
+  Settings,
+  SettingsUpdate,
+} from "../types";
 
 export function useSettings() {
   const queryClient = useQueryClient();
@@ -25,19 +31,47 @@ export function useSettings() {
     };
   }, []);
 
-  const saveMutation = useMutation<void, Error, Omit<Settings, "version">>({
-    mutationFn: (settings: Omit<Settings, "version">) =>
-      invoke<void>("save_settings", settings),
+  const saveMutation = useMutation<void, Error, SettingsUpdate>({
+    mutationFn: (settings: SettingsUpdate) =>
+      invoke<void>("save_settings", {
+        ...settings,
+        network: settings.network ?? null,
+        switchProjectShortcut: settings.switchProjectShortcut ?? null,
+        weeklyTargetHours: settings.weeklyTargetHours ?? null,
+        breakRemindersEnabled: settings.breakRemindersEnabled ?? null,
+        shiftRemindersEnabled: settings.shiftRemindersEnabled ?? null,
+        endOfDayTime: settings.endOfDayTime ?? null,
+        endOfDayIdleMinutes: settings.endOfDayIdleMinutes ?? null,
+        locationRules: settings.locationRules ?? null,
+        triggerRules: settings.triggerRules ?? null,
+        controlApiEnabled: settings.controlApiEnabled ?? null,
+      }),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ["settings"] });
+      queryClient.invalidateQueries({ queryKey: ["work-location-suggestion"] });
     },
   });
 
+  const testConnectionMutation = useMutation<
+    ConnectionTestResult,
+    Error,
+    { webappUrl?: string; network?: NetworkSettings }
+  >({
+    mutationFn: ({ webappUrl, network }) =>
+      invoke<ConnectionTestResult>("test_connection", {
+        webappUrl: webappUrl ?? null,
+        network: network ?? null,
+      }),
+  });
+
   return {
     settings: settingsQuery.data,
     isLoading: settingsQuery.isLoading,
     saveSettings: saveMutation.mutateAsync,
     isSaving: saveMutation.isPending,
+    testConnection: testConnectionMutation.mutateAsync,
+    isTestingConnection: testConnectionMutation.isPending,
+    readNetworkSignals: () => invoke<NetworkSignals>("get_network_signals"),
     isSettingsOpen,
     setIsSettingsOpen,
   };
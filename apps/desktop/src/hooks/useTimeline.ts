@@ -0,0 +1,30 @@
+import { useQuery } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import type { DayTimeline, WeekSummary } from "../types";
+
+export function useTimeline({ enabled = true }: { enabled?: boolean } = {}) {
+  const todayQuery = useQuery({
+    queryKey: ["timeline", "today"],
+    queryFn: () => invoke<DayTimeline>("get_today_timeline"),
+    refetchInterval: 60000,
+    refetchOnWindowFocus: true,
+    enabled,
+  });
+
+  const weekQuery = useQuery({
+    queryKey: ["timeline", "week"],
+    queryFn: () => invoke<WeekSummary>("get_week_summary"),
+    refetchInterval: 5 * 60000,
+    refetchOnWindowFocus: true,
+    enabled,
+  });
+
+  return {
+    today: todayQuery.data,
+    week: weekQuery.data,
+    isLoading: todayQuery.isLoading || weekQuery.isLoading,
+    isError: todayQuery.isError || weekQuery.isError,
+    error: todayQuery.error ?? weekQuery.error,
+    refetch: () => Promise.all([todayQuery.refetch(), weekQuery.refetch()]),
+  };
+}
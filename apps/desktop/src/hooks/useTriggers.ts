@@ -0,0 +1,59 @@
+import { useMutation, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import type { FiredTrigger } from "../types";
+
+export function useTriggers() {
+  const queryClient = useQueryClient();
+  const [trigger, setTrigger] = useState<FiredTrigger | null>(null);
+
+  useEffect(() => {
+    const listeners = [
+      listen<FiredTrigger>("trigger_proposed", (event) => {
+        setTrigger(event.payload);
+      }),
+      listen<FiredTrigger>("trigger_performed", (event) => {
+        setTrigger(event.payload);
+        queryClient.invalidateQueries({ queryKey: ["clock-status"] });
+      }),
+    ];
+
+    return () => {
+      for (const unlisten of listeners) {
+        unlisten.then((fn) => fn());
+      }
+    };
+  }, [queryClient]);
+
+  const confirmMutation = useMutation({
+    mutationFn: (id: number) => invoke<FiredTrigger>("confirm_trigger", { id }),
+    onSuccess: () => {
+      setTrigger(null);
+    },
+  });
+
+  const undoMutation = useMutation({
+    mutationFn: (id: number) => invoke<void>("undo_trigger", { id }),
+    onSuccess: () => {
+      setTrigger(null);
+      queryClient.invalidateQueries({ queryKey: ["clock-status"] });
+    },
+  });
+
+  const dismiss = () => {
+    if (trigger && !trigger.performed) {
+      invoke("dismiss_trigger", { id: trigger.id }).catch(console.error);
+    }
+    setTrigger(null);
+  };
+
+  return {
+    trigger,
+    isTriggerOpen: trigger !== null,
+    dismiss,
+    confirm: confirmMutation.mutateAsync,
+    undo: undoMutation.mutateAsync,
+    isSaving: confirmMutation.isPending || undoMutation.isPending,
+  };
+}
@@ -0,0 +1,37 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import type { UnsentAction } from "../types";
+
+export function useUnsentActions({ enabled = true }: { enabled?: boolean } = {}) {
+  const queryClient = useQueryClient();
+
+  const unsentQuery = useQuery({
+    queryKey: ["unsent-actions"],
+    queryFn: () => invoke<UnsentAction[]>("list_unsent_actions"),
+    refetchInterval: 30000, // The queue processor runs every 30 seconds
+    enabled,
+  });
+
+  // Both change what the queue holds, and so the local clock status
+  const onSettled = () => {
+    queryClient.invalidateQueries({ queryKey: ["unsent-actions"] });
+    queryClient.invalidateQueries({ queryKey: ["clock-status"] });
+  };
+
+  const retryMutation = useMutation({
+    mutationFn: (id: number) => invoke<void>("retry_unsent_action", { id }),
+    onSettled,
+  });
+
+  const discardMutation = useMutation({
+    mutationFn: (id: number) => invoke<void>("discard_unsent_action", { id }),
+    onSettled,
+  });
+
+  return {
+    actions: unsentQuery.data ?? [],
+    retry: retryMutation.mutateAsync,
+    discard: discardMutation.mutateAsync,
+    isUpdating: retryMutation.isPending || discardMutation.isPending,
+  };
+}
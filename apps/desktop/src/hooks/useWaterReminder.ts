@@ -0,0 +1,44 @@
+import { useMutation } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import type { WaterActionResult, WaterReminderStatus } from "../types";
+
+export function useWaterReminder() {
+  const [reminder, setReminder] = useState<WaterReminderStatus | null>(null);
+
+  useEffect(() => {
+    const listeners = [
+      listen<WaterReminderStatus>("water_reminder", (event) => {
+        setReminder(event.payload);
+      }),
+      // Logged from the tray, nothing left to answer
+      listen<WaterActionResult>("water_action", () => {
+        setReminder(null);
+      }),
+    ];
+
+    return () => {
+      for (const unlisten of listeners) {
+        unlisten.then((fn) => fn());
+      }
+    };
+  }, []);
+
+  const logMutation = useMutation({
+    mutationFn: (amount: number) => invoke<WaterActionResult>("log_water", { amount }),
+  });
+
+  const snoozeMutation = useMutation({
+    mutationFn: () => invoke<WaterActionResult>("snooze_water_reminders"),
+  });
+
+  return {
+    reminder,
+    isReminderOpen: reminder !== null,
+    dismissReminder: () => setReminder(null),
+    logWater: logMutation.mutateAsync,
+    snooze: snoozeMutation.mutateAsync,
+    isSaving: logMutation.isPending || snoozeMutation.isPending,
+  };
+}
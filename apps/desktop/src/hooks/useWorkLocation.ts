@@ -1,5 +1,7 @@
-import { useState } from "react";
-import { isWorkLocationType, type WorkLocationType } from "../types";
+import { useQuery } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { useEffect, useRef, useState } from "react";
+import { isWorkLocationType, type LocationSuggestion, type WorkLocationType } from "../types";
 
 const WORK_LOCATION_KEY = "z8-work-location-type";
 const DEFAULT_WORK_LOCATION: WorkLocationType = "office";
@@ -17,10 +19,34 @@ function getStoredWorkLocation(): WorkLocationType {
   }
 }
 
-export function useWorkLocation() {
+function suggestionKey(suggestion: LocationSuggestion | null | undefined): string | null {
+  return suggestion ? `${suggestion.signal}:${suggestion.value}:${suggestion.location}` : null;
+}
+
+export function useWorkLocation({ enabled = true }: { enabled?: boolean } = {}) {
   const [workLocationType, setWorkLocationTypeState] = useState<WorkLocationType>(
     getStoredWorkLocation,
   );
+  const appliedSuggestion = useRef<string | null>(null);
+
+  // Network rules suggest a location; re-checked as the laptop moves between networks
+  const suggestionQuery = useQuery({
+    queryKey: ["work-location-suggestion"],
+    queryFn: () => invoke<LocationSuggestion | null>("suggest_work_location"),
+    refetchInterval: 60000,
+    refetchOnWindowFocus: true,
+    enabled,
+  });
+  const suggestion = suggestionQuery.data ?? null;
+
+  // Pre-select each new suggestion once, so a manual override sticks until the network changes
+  useEffect(() => {
+    const key = suggestionKey(suggestion);
+    if (suggestion && key !== appliedSuggestion.current) {
+      setWorkLocationTypeState(suggestion.location);
+    }
+    appliedSuggestion.current = key;
+  }, [suggestion]);
 
   const setWorkLocationType = (nextWorkLocationType: WorkLocationType) => {
     setWorkLocationTypeState(nextWorkLocationType);
@@ -32,5 +58,5 @@ export function useWorkLocation() {
     }
   };
 
-  return { workLocationType, setWorkLocationType };
+  return { workLocationType, setWorkLocationType, suggestion };
 }
@@ -11,6 +11,102 @@ export function isWorkLocationType(value: string | null | undefined): value is W
   return WORK_LOCATION_OPTIONS.some((option) => option.value === value);
 }
 
+export type LocationSignal = "wifiSsid" | "gatewayMac" | "vpnInterface";
+
+/** Maps a network signal to the work location it implies */
+export interface LocationRule {
+  signal: LocationSignal;
+  /** SSID, gateway MAC address or interface name prefix */
+  value: string;
+  location: WorkLocationType;
+}
+
+export interface NetworkSignals {
+  ssid: string | null;
+  gatewayMac: string | null;
+  interfaces: string[];
+}
+
+export interface LocationSuggestion {
+  location: WorkLocationType;
+  signal: LocationSignal;
+  /** The observed value that matched, e.g. the SSID */
+  value: string;
+}
+
+export type TriggerEvent = "activity" | "idle" | "networkJoined" | "networkLeft";
+export type TriggerAction = "clockIn" | "clockOut" | "pause";
+export type TriggerMode = "propose" | "perform";
+
+/** Punches automatically, or proposes a punch, when a device or network event happens */
+export interface TriggerRule {
+  event: TriggerEvent;
+  /** Required for network events; otherwise the device must also be on this network */
+  network: { signal: LocationSignal; value: string } | null;
+  /** Local `HH:MM` window in which the rule applies */
+  after: string | null;
+  before: string | null;
+  action: TriggerAction;
+  mode: TriggerMode;
+}
+
+/** A rule that fired, to confirm or undo */
+export interface FiredTrigger {
+  id: number;
+  action: TriggerAction;
+  mode: TriggerMode;
+  reason: string;
+  at: string;
+  performed: boolean;
+}
+
+/** Action requested by a `z8://` link */
+export type DeepLinkAction =
+  | { kind: "clockIn"; location: WorkLocationType | null }
+  | { kind: "clockOut" }
+  | { kind: "startBreak" }
+  | { kind: "switchOrganization"; id: string }
+  | { kind: "openSettings" };
+
+/** A link action waiting for the user to confirm it */
+export interface PendingDeepLink {
+  id: number;
+  action: DeepLinkAction;
+  description: string;
+  receivedAt: string;
+}
+
+export type JournalKind = "punch" | "idle" | "break" | "sync";
+export type EventSource =
+  | "ui"
+  | "tray"
+  | "hotkey"
+  | "rule"
+  | "link"
+  | "cli"
+  | "controlApi"
+  | "system";
+export type JournalOutcome = "accepted" | "queued" | "failed" | "noted";
+
+/** A recorded punch, idle decision, break decision or sync result */
+export interface JournalEntry {
+  id: number;
+  at: string;
+  kind: JournalKind;
+  source: EventSource;
+  action: string;
+  outcome: JournalOutcome;
+  message: string | null;
+  details: Record<string, unknown> | null;
+}
+
+export interface JournalQuery {
+  from?: string | null;
+  to?: string | null;
+  kinds?: JournalKind[];
+  limit?: number | null;
+}
+
 export interface ClockStatus {
   hasEmployee: boolean;
   employeeId: string | null;
@@ -21,16 +117,361 @@ export interface ClockStatus {
   } | null;
 }
 
+export interface NetworkSettings {
+  proxyUrl: string | null;
+  noProxy: string | null;
+  caCertificatePath: string | null;
+  useSystemRoots: boolean;
+}
+
 export interface Settings {
   webappUrl: string;
   alwaysOnTop: boolean;
   autoStartup: boolean;
+  network: NetworkSettings;
+  switchProjectShortcut: string | null;
+  weeklyTargetHours: number;
+  breakRemindersEnabled: boolean;
+  shiftRemindersEnabled: boolean;
+  /** Local `HH:MM` after which a running clock prompts for clock-out; null disables it */
+  endOfDayTime: string | null;
+  endOfDayIdleMinutes: number;
+  locationRules: LocationRule[];
+  /** Rules deployed by IT; read-only */
+  managedLocationRules: LocationRule[];
+  triggerRules: TriggerRule[];
+  controlApiEnabled: boolean;
+  /** File with the local control API's address and token */
+  controlApiFile: string;
   version: string;
 }
 
+export type SettingsUpdate = Omit<
+  Settings,
+  | "version"
+  | "network"
+  | "switchProjectShortcut"
+  | "weeklyTargetHours"
+  | "breakRemindersEnabled"
+  | "shiftRemindersEnabled"
+  | "endOfDayTime"
+  | "endOfDayIdleMinutes"
+  | "locationRules"
+  | "managedLocationRules"
+  | "triggerRules"
+  | "controlApiEnabled"
+  | "controlApiFile"
+> & {
+  network?: NetworkSettings;
+  /** An empty string disables the shortcut; null keeps the current one */
+  switchProjectShortcut?: string | null;
+  weeklyTargetHours?: number;
+  breakRemindersEnabled?: boolean;
+  shiftRemindersEnabled?: boolean;
+  /** An empty string disables the prompt; null keeps the current time */
+  endOfDayTime?: string | null;
+  endOfDayIdleMinutes?: number;
+  locationRules?: LocationRule[];
+  triggerRules?: TriggerRule[];
+  controlApiEnabled?: boolean;
+};
+
+export interface ConnectionTestResult {
+  ok: boolean;
+  stage: "ok" | "config" | "proxy" | "tls" | "dns" | "connect" | "timeout" | "http";
+  message: string;
+  status: number | null;
+  proxy: string | null;
+  elapsedMs: number;
+}
+
+export interface TimelineEntry {
+  id: string | null;
+  type: "clock_in" | "clock_out" | "correction";
+  timestamp: string;
+  notes: string | null;
+  /** Still waiting in the offline queue */
+  pending: boolean;
+}
+
+export interface DayTimeline {
+  date: string;
+  entries: TimelineEntry[];
+  workedMinutes: number;
+  breakMinutes: number;
+  isClockedIn: boolean;
+  /** Minutes since the last clock-in while a period is running */
+  currentSessionMinutes: number | null;
+  pendingCount: number;
+}
+
+export interface WeekSummary {
+  weekStart: string;
+  days: { date: string; workedMinutes: number }[];
+  workedMinutes: number;
+  targetMinutes: number;
+  differenceMinutes: number;
+  pendingCount: number;
+}
+
+export interface BreakRule {
+  workingMinutesThreshold: number;
+  requiredBreakMinutes: number;
+}
+
+export interface BreakPolicy {
+  id: string;
+  name: string;
+  maxDailyMinutes: number | null;
+  maxUninterruptedMinutes: number | null;
+  alertBeforeLimitMinutes: number;
+  breakRules: BreakRule[];
+}
+
+export type BreakReminder =
+  | {
+      kind: "breakDue";
+      requiredBreakMinutes: number;
+      missingBreakMinutes: number;
+      minutesLeft: number;
+    }
+  | { kind: "maxUninterrupted"; limitMinutes: number; minutesLeft: number }
+  | { kind: "maxDaily"; limitMinutes: number; minutesLeft: number };
+
+/** The signed-in user; the session token itself stays in the Rust side */
+export interface Profile {
+  id: string;
+  name: string;
+  email: string;
+  image: string | null;
+}
+
 export interface Session {
-  token: string | null;
   isAuthenticated: boolean;
+  profile: Profile | null;
+}
+
+/** A device signed in to the user's account */
+export interface Device {
+  id: string;
+  name: string | null;
+  platform: string | null;
+  createdAt: string;
+  lastUsedAt: string | null;
+  /** The device this app runs on */
+  isCurrent: boolean;
+}
+
+/** A queued action the webapp refused too often to be retried automatically */
+export interface UnsentAction {
+  id: number;
+  /** e.g. `clock_in` */
+  action: string;
+  queuedAt: string | null;
+  retryCount: number;
+  /** Punches hold back the punches queued after them */
+  isPunch: boolean;
+}
+
+export interface CorrectionSubmission {
+  requiresApproval: boolean;
+  message: string;
+  /** Saved in the offline queue; submitted once the webapp is reachable */
+  queued: boolean;
+}
+
+export interface Correction {
+  id: string;
+  workPeriodId: string;
+  status: "pending" | "approved" | "rejected";
+  reason: string | null;
+  rejectionReason: string | null;
+  createdAt: string;
+  decidedAt: string | null;
+}
+
+export type DayPeriod = "full_day" | "am" | "pm";
+
+export interface AbsenceCategory {
+  id: string;
+  name: string;
+  type: string;
+  description: string | null;
+  color: string | null;
+  requiresApproval: boolean;
+  countsAgainstVacation: boolean;
+}
+
+export interface Absence {
+  id: string;
+  startDate: string;
+  startPeriod: DayPeriod;
+  endDate: string;
+  endPeriod: DayPeriod;
+  status: "pending" | "approved" | "rejected";
+  notes: string | null;
+  category: AbsenceCategory;
+  approvedAt: string | null;
+  rejectionReason: string | null;
+  createdAt: string;
+}
+
+export interface VacationBalance {
+  year: number;
+  totalDays: number;
+  usedDays: number;
+  pendingDays: number;
+  remainingDays: number;
+  carryoverDays: number | null;
+  carryoverExpiryDate: string | null;
+}
+
+export interface AbsenceOverview {
+  categories: AbsenceCategory[];
+  absences: Absence[];
+  /** null when the organization has no vacation allowance configured */
+  vacationBalance: VacationBalance | null;
+}
+
+export interface AbsenceRequest {
+  categoryId: string;
+  startDate: string;
+  startPeriod: DayPeriod;
+  endDate: string;
+  endPeriod: DayPeriod;
+  notes?: string | null;
+}
+
+export interface SelfServiceRequest {
+  id: string;
+  sourceType: "time_correction" | "absence" | "travel_expense";
+  sourceId: string;
+  status: "pending" | "approved" | "rejected" | "cancelled";
+  submittedAt: string;
+  resolvedAt: string | null;
+  title: string;
+  subtitle: string;
+  decisionReason: string | null;
+  availableActions: ("view" | "fix" | "cancel")[];
+}
+
+export interface MyRequests {
+  items: SelfServiceRequest[];
+  counts: {
+    pending: number;
+    requiredFixes: number;
+    recentDecisions: number;
+    total: number;
+  };
+}
+
+export interface ShiftWindow {
+  id: string;
+  start: string;
+  end: string;
+  notes: string | null;
+  color: string | null;
+}
+
+export interface ScheduleView {
+  timezone: string;
+  shifts: ShiftWindow[];
+  /** The shift currently running, or else the next one */
+  nextShift: ShiftWindow | null;
+}
+
+export interface PunchWarning {
+  timing: "early" | "afterShift";
+  /** Distance to the shift window */
+  minutes: number;
+  shift: ShiftWindow;
+}
+
+export interface ShiftReminder {
+  kind: "startsSoon" | "endsSoon";
+  minutesLeft: number;
+  shift: ShiftWindow;
+}
+
+export interface ApprovalItem {
+  id: string;
+  approvalType: string;
+  entityId: string;
+  typeName: string;
+  requester: {
+    id: string;
+    name: string;
+    email: string;
+    image: string | null;
+  };
+  status: string;
+  createdAt: string;
+  priority: "urgent" | "high" | "normal" | "low";
+  display: {
+    title: string;
+    subtitle: string;
+    summary: string;
+  };
+  /** false when the request must be reconciled in the webapp first */
+  isActionable: boolean | null;
+  warning: string | null;
+}
+
+export interface ApprovalPage {
+  items: ApprovalItem[];
+  nextCursor: string | null;
+  hasMore: boolean;
+  total: number;
+}
+
+export interface BulkDecisionResult {
+  succeeded: { id: string; approvalType: string; status: string }[];
+  failed: { id: string; code: string; message: string }[];
+}
+
+export interface WaterReminderStatus {
+  enabled: boolean;
+  intervalMinutes: number;
+  dailyGoal: number;
+  snoozedUntil: string | null;
+  lastIntakeTime: string | null;
+}
+
+export interface WaterActionResult {
+  todayIntake: number | null;
+  goalProgress: number | null;
+  currentStreak: number | null;
+  goalJustMet: boolean;
+  snoozedUntil: string | null;
+  /** Saved offline; sent once the webapp is reachable again */
+  queued: boolean;
+}
+
+export interface ServerNotification {
+  id: string;
+  type: string;
+  title: string;
+  message: string;
+  entityType: string | null;
+  entityId: string | null;
+  /** Webapp path opened when the notification is clicked */
+  actionUrl: string | null;
+  isRead: boolean;
+  createdAt: string;
+}
+
+export interface NotificationPage {
+  notifications: ServerNotification[];
+  total: number;
+  unreadCount: number;
+  hasMore: boolean;
+}
+
+export interface ClockOutPrompt {
+  reason: "endOfDay" | "longIdle";
+  /** Suggested clock-out time; null while the user is still active */
+  lastActivity: string | null;
+  idleMinutes: number;
 }
 
 export interface IdleEvent {
@@ -0,0 +1,7 @@
+ALTER TABLE "time_entry" ADD COLUMN IF NOT EXISTS "desktop_device_id" uuid;
+--> statement-breakpoint
+DO $$ BEGIN
+ ALTER TABLE "time_entry" ADD CONSTRAINT "time_entry_desktop_device_id_desktop_device_id_fk" FOREIGN KEY ("desktop_device_id") REFERENCES "public"."desktop_device"("id") ON DELETE set null ON UPDATE no action;
+EXCEPTION
+ WHEN duplicate_object THEN null;
+END $$;
@@ -2,6 +2,8 @@ import { beforeEach, describe, expect, it, vi } from "vitest";
 
 const mockState = vi.hoisted(() => ({
 	consumeAppAuthCode: vi.fn(),
+	registerDesktopDevice: vi.fn(),
+	verifyDeviceSignature: vi.fn(),
 	checkRateLimit: vi.fn(),
 	createRateLimitResponse: vi.fn(),
 	getClientIp: vi.fn(),
@@ -11,6 +13,11 @@ vi.mock("@/lib/auth/app-auth-code", () => ({
 	consumeAppAuthCode: mockState.consumeAppAuthCode,
 }));
 
+vi.mock("@/lib/auth/desktop-device", () => ({
+	registerDesktopDevice: mockState.registerDesktopDevice,
+	verifyDeviceSignature: mockState.verifyDeviceSignature,
+}));
+
 vi.mock("@/lib/rate-limit", () => ({
 	checkRateLimit: mockState.checkRateLimit,
 	createRateLimitResponse: mockState.createRateLimitResponse,
@@ -120,4 +127,64 @@ describe("POST /api/auth/app-exchange", () => {
 		expect(mockState.consumeAppAuthCode).not.toHaveBeenCalled();
 		expect(await response.json()).toEqual({ error: "Code and verifier are required" });
 	});
+
+	it("registers the desktop device that signed the verifier", async () => {
+		mockState.verifyDeviceSignature.mockReturnValue(true);
+		mockState.consumeAppAuthCode.mockResolvedValue({
+			status: "success",
+			sessionToken: "session-token",
+			userId: "user-1",
+		});
+		mockState.registerDesktopDevice.mockResolvedValue("device-1");
+		const device = {
+			publicKey: "PUBLIC-KEY",
+			signature: "SIGNATURE",
+			name: "Desk",
+			platform: "linux",
+		};
+
+		const response = await POST(
+			new Request("https://app.example.com/api/auth/app-exchange", {
+				body: JSON.stringify({ code: "ONE-TIME-CODE", verifier: "VERIFIER", device }),
+				headers: {
+					"Content-Type": "application/json",
+					"X-Z8-App-Type": "desktop",
+				},
+				method: "POST",
+			}),
+		);
+
+		expect(response.status).toBe(200);
+		expect(mockState.verifyDeviceSignature).toHaveBeenCalledWith(device, "VERIFIER");
+		expect(mockState.registerDesktopDevice).toHaveBeenCalledWith({
+			userId: "user-1",
+			sessionToken: "session-token",
+			device,
+		});
+		expect(await response.json()).toEqual({ token: "session-token", deviceId: "device-1" });
+	});
+
+	it("keeps the code when the device signature does not match", async () => {
+		mockState.verifyDeviceSignature.mockReturnValue(false);
+
+		const response = await POST(
+			new Request("https://app.example.com/api/auth/app-exchange", {
+				body: JSON.stringify({
+					code: "ONE-TIME-CODE",
+					verifier: "VERIFIER",
+					device: { publicKey: "PUBLIC-KEY", signature: "FORGED" },
+				}),
+				headers: {
+					"Content-Type": "application/json",
+					"X-Z8-App-Type": "desktop",
+				},
+				method: "POST",
+			}),
+		);
+
+		expect(response.status).toBe(400);
+		expect(mockState.consumeAppAuthCode).not.toHaveBeenCalled();
+		expect(mockState.registerDesktopDevice).not.toHaveBeenCalled();
+		expect(await response.json()).toEqual({ error: "Invalid device signature" });
+	});
 });
@@ -1,11 +1,21 @@
 import { NextResponse } from "next/server";
 import { z } from "zod";
 import { consumeAppAuthCode, type SupportedApp } from "@/lib/auth/app-auth-code";
+import { registerDesktopDevice, verifyDeviceSignature } from "@/lib/auth/desktop-device";
 import { checkRateLimit, createRateLimitResponse, getClientIp } from "@/lib/rate-limit";
 
 const bodySchema = z.object({
 	code: z.string().trim().min(1),
 	verifier: z.string().trim().min(1),
+	// Desktop installations register their Ed25519 key with a signature over the verifier
+	device: z
+		.object({
+			publicKey: z.string().min(1),
+			signature: z.string().min(1),
+			name: z.string().optional(),
+			platform: z.string().optional(),
+		})
+		.optional(),
 });
 
 function resolveAppType(request: Request): SupportedApp | null {
@@ -32,6 +42,12 @@ export async function POST(request: Request) {
 		return NextResponse.json({ error: "Code and verifier are required" }, { status: 400 });
 	}
 
+	// Checked before the code is consumed, so a bad signature doesn't burn the login
+	const device = app === "desktop" ? parsed.data.device : undefined;
+	if (device && !verifyDeviceSignature(device, parsed.data.verifier)) {
+		return NextResponse.json({ error: "Invalid device signature" }, { status: 400 });
+	}
+
 	const result = await consumeAppAuthCode({
 		app,
 		code: parsed.data.code,
@@ -41,5 +57,15 @@ export async function POST(request: Request) {
 		return NextResponse.json({ error: "Invalid or expired code" }, { status: 401 });
 	}
 
+	if (device) {
+		const deviceId = await registerDesktopDevice({
+			userId: result.userId,
+			sessionToken: result.sessionToken,
+			device,
+		});
+
+		return NextResponse.json({ token: result.sessionToken, deviceId });
+	}
+
 	return NextResponse.json({ token: result.sessionToken });
 }
@@ -0,0 +1,68 @@
+import { and, eq } from "drizzle-orm";
+import { headers } from "next/headers";
+import { connection, NextResponse } from "next/server";
+import { cancelAbsenceRequestForExpectedEmployee } from "@/app/[locale]/(app)/absences/cancel-absence-service";
+import { db } from "@/db";
+import { employee } from "@/db/schema";
+import { auth } from "@/lib/auth";
+
+/**
+ * POST /api/desktop/absences/[absenceId]/cancel
+ * Cancels one of the current employee's absence requests
+ * Used by desktop app to withdraw a request
+ */
+export async function POST(
+	_request: Request,
+	{ params }: { params: Promise<{ absenceId: string }> },
+) {
+	await connection();
+
+	try {
+		const session = await auth.api.getSession({ headers: await headers() });
+		if (!session?.user) {
+			return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+		}
+
+		const activeOrgId = session.session.activeOrganizationId;
+		if (!activeOrgId) {
+			return NextResponse.json({ error: "No active organization" }, { status: 400 });
+		}
+
+		const employeeRecord = await db.query.employee.findFirst({
+			where: and(
+				eq(employee.userId, session.user.id),
+				eq(employee.organizationId, activeOrgId),
+				eq(employee.isActive, true),
+			),
+		});
+		if (!employeeRecord) {
+			return NextResponse.json(
+				{ error: "Employee record not found in this organization" },
+				{ status: 404 },
+			);
+		}
+
+		const { absenceId } = await params;
+		const result = await cancelAbsenceRequestForExpectedEmployee(absenceId, employeeRecord);
+
+		if (!result.success) {
+			if (result.error === "billing_required") {
+				const reason =
+					"reason" in result && typeof result.reason === "string"
+						? result.reason
+						: "subscription_required";
+
+				return NextResponse.json({ error: "billing_required", reason }, { status: 402 });
+			}
+
+			return NextResponse.json(
+				{ error: result.error ?? "Failed to cancel absence" },
+				{ status: 400 },
+			);
+		}
+
+		return NextResponse.json(result);
+	} catch (_error) {
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
@@ -0,0 +1,154 @@
+import { and, eq } from "drizzle-orm";
+import { DateTime } from "luxon";
+import { headers } from "next/headers";
+import { connection, type NextRequest, NextResponse } from "next/server";
+import { z } from "zod";
+import {
+	getAbsenceCategories,
+	getAbsenceEntries,
+	getVacationBalance,
+} from "@/app/[locale]/(app)/absences/actions";
+import { requestAbsenceForEmployeeEffect } from "@/app/[locale]/(app)/absences/request-absence-effect";
+import { db } from "@/db";
+import { organization } from "@/db/auth-schema";
+import { employee } from "@/db/schema";
+import { auth } from "@/lib/auth";
+
+function isRealIsoDate(value: string) {
+	return DateTime.fromISO(value, { zone: "utc" }).toISODate() === value;
+}
+
+const absenceDateSchema = z
+	.string()
+	.regex(/^\d{4}-\d{2}-\d{2}$/, "Date must be in YYYY-MM-DD format")
+	.refine(isRealIsoDate, "Date must be a real calendar date");
+
+const absenceRequestSchema = z.object({
+	categoryId: z.string().trim().min(1, "categoryId is required"),
+	startDate: absenceDateSchema,
+	startPeriod: z.enum(["full_day", "am", "pm"]),
+	endDate: absenceDateSchema,
+	endPeriod: z.enum(["full_day", "am", "pm"]),
+	notes: z.string().trim().min(1).optional(),
+});
+
+async function findActiveEmployee(userId: string, organizationId: string) {
+	return db.query.employee.findFirst({
+		where: and(
+			eq(employee.userId, userId),
+			eq(employee.organizationId, organizationId),
+			eq(employee.isActive, true),
+		),
+	});
+}
+
+/**
+ * GET /api/desktop/absences
+ * Returns absence categories, this year's absences and the vacation balance
+ * Used by desktop app to show balances and the status of absence requests
+ */
+export async function GET() {
+	await connection();
+
+	try {
+		const session = await auth.api.getSession({ headers: await headers() });
+		if (!session?.user) {
+			return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+		}
+
+		const activeOrgId = session.session.activeOrganizationId;
+		if (!activeOrgId) {
+			return NextResponse.json({ error: "No active organization" }, { status: 400 });
+		}
+
+		const employeeRecord = await findActiveEmployee(session.user.id, activeOrgId);
+		if (!employeeRecord) {
+			return NextResponse.json(
+				{ error: "Employee record not found in this organization" },
+				{ status: 404 },
+			);
+		}
+
+		const org = await db.query.organization.findFirst({
+			where: eq(organization.id, activeOrgId),
+			columns: { timezone: true },
+		});
+		const timezone = org?.timezone || "UTC";
+		const year = DateTime.now().setZone(timezone).year;
+
+		const [categories, absences, vacationBalance] = await Promise.all([
+			getAbsenceCategories(activeOrgId),
+			getAbsenceEntries(employeeRecord.id, `${year}-01-01`, `${year}-12-31`),
+			getVacationBalance(employeeRecord.id, year, timezone),
+		]);
+
+		return NextResponse.json({ categories, absences, vacationBalance });
+	} catch (_error) {
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
+
+/**
+ * POST /api/desktop/absences
+ * Requests an absence for the current employee
+ * Used by desktop app's absence request form
+ */
+export async function POST(request: NextRequest) {
+	await connection();
+
+	try {
+		const session = await auth.api.getSession({ headers: await headers() });
+		if (!session?.user) {
+			return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+		}
+
+		const activeOrgId = session.session.activeOrganizationId;
+		if (!activeOrgId) {
+			return NextResponse.json({ error: "No active organization" }, { status: 400 });
+		}
+
+		let body: unknown;
+		try {
+			body = await request.json();
+		} catch {
+			return NextResponse.json({ error: "Invalid JSON body" }, { status: 400 });
+		}
+
+		const parsedBody = absenceRequestSchema.safeParse(body);
+		if (!parsedBody.success) {
+			const firstIssue = parsedBody.error.issues[0];
+			const fieldName = typeof firstIssue?.path[0] === "string" ? firstIssue.path[0] : "date";
+			const message = firstIssue?.message ?? "Invalid request body";
+
+			return NextResponse.json(
+				{ error: message.startsWith("Date must") ? message.replace("Date", fieldName) : message },
+				{ status: 400 },
+			);
+		}
+
+		const employeeRecord = await findActiveEmployee(session.user.id, activeOrgId);
+		if (!employeeRecord) {
+			return NextResponse.json(
+				{ error: "Employee record not found in this organization" },
+				{ status: 404 },
+			);
+		}
+
+		const result = await requestAbsenceForEmployeeEffect(
+			parsedBody.data,
+			employeeRecord,
+			session.user.id,
+		);
+
+		if (!result.success) {
+			return NextResponse.json(
+				{ error: result.error ?? "Failed to request absence" },
+				{ status: 400 },
+			);
+		}
+
+		return NextResponse.json(result);
+	} catch (_error) {
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
@@ -0,0 +1,118 @@
+import { headers } from "next/headers";
+import { connection, NextResponse } from "next/server";
+import { auth } from "@/lib/auth";
+import { revokedDeviceResponse } from "@/lib/auth/desktop-device";
+import { getClockStatus } from "@/lib/time-tracking/clock-status";
+import { ClockingAccessError, clockingService } from "@/lib/time-tracking/clocking-service";
+
+// How often the status is re-read while the stream is open
+const CHECK_INTERVAL_MS = 15_000;
+// Comments keep proxies from closing the connection; the app treats 2 minutes of silence as dead
+const KEEP_ALIVE_INTERVAL_MS = 30_000;
+// Streams are closed after this long so sessions are re-checked; the app reconnects
+const MAX_STREAM_MS = 30 * 60_000;
+
+/**
+ * GET /api/desktop/clock-status/stream
+ * Server-sent events with the current user's clock status
+ *
+ * Used by desktop app to follow punches made in the webapp or on a phone.
+ * Sends a `clock_status` event with the same body as /api/time-entries/status
+ * when the stream opens and whenever the status changes. Switching the active
+ * organization closes the stream.
+ */
+export async function GET(request: Request) {
+	await connection();
+
+	try {
+		const resolvedHeaders = await headers();
+		const session = await auth.api.getSession({ headers: resolvedHeaders });
+		if (!session?.user) {
+			return (
+				(await revokedDeviceResponse(request)) ??
+				NextResponse.json({ error: "Unauthorized" }, { status: 401 })
+			);
+		}
+
+		const activeOrgId = session.session.activeOrganizationId;
+		if (!activeOrgId) {
+			return NextResponse.json({ error: "No active organization" }, { status: 400 });
+		}
+		await clockingService.requireActor({ userId: session.user.id, activeOrganizationId: activeOrgId });
+
+		const userId = session.user.id;
+		const encoder = new TextEncoder();
+		const timers: ReturnType<typeof setInterval>[] = [];
+		let closed = false;
+		let last: string | null = null;
+
+		const stream = new ReadableStream<Uint8Array>({
+			async start(controller) {
+				const close = () => {
+					if (closed) {
+						return;
+					}
+					closed = true;
+					for (const timer of timers) {
+						clearInterval(timer);
+					}
+					controller.close();
+				};
+				const send = (chunk: string) => {
+					if (!closed) {
+						controller.enqueue(encoder.encode(chunk));
+					}
+				};
+				const check = async () => {
+					try {
+						// Signed out, revoked or switched to another organization since the
+						// stream opened; the app reconnects and follows the new organization
+						const current = await auth.api.getSession({ headers: resolvedHeaders });
+						if (!current?.user || current.session.activeOrganizationId !== activeOrgId) {
+							close();
+							return;
+						}
+
+						const status = await getClockStatus(userId, activeOrgId);
+						const data = JSON.stringify(status);
+						if (data !== last) {
+							last = data;
+							send(`event: clock_status\ndata: ${data}\n\n`);
+						}
+					} catch (_error) {
+						close();
+					}
+				};
+
+				request.signal.addEventListener("abort", close);
+				await check();
+				if (closed) {
+					return;
+				}
+				timers.push(setInterval(check, CHECK_INTERVAL_MS));
+				timers.push(setInterval(() => send(": keep-alive\n\n"), KEEP_ALIVE_INTERVAL_MS));
+				timers.push(setInterval(close, MAX_STREAM_MS));
+			},
+			cancel() {
+				closed = true;
+				for (const timer of timers) {
+					clearInterval(timer);
+				}
+			},
+		});
+
+		return new Response(stream, {
+			headers: {
+				"Content-Type": "text/event-stream",
+				"Cache-Control": "no-cache, no-transform",
+				Connection: "keep-alive",
+				"X-Accel-Buffering": "no",
+			},
+		});
+	} catch (error) {
+		if (error instanceof ClockingAccessError) {
+			return NextResponse.json({ error: error.message }, { status: 403 });
+		}
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
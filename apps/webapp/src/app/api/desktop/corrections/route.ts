@@ -0,0 +1,78 @@
+import { and, desc, eq } from "drizzle-orm";
+import { headers } from "next/headers";
+import { connection, NextResponse } from "next/server";
+import { db } from "@/db";
+import { approvalRequest, employee } from "@/db/schema";
+import { auth } from "@/lib/auth";
+
+const CORRECTIONS_LIMIT = 50;
+
+/**
+ * GET /api/desktop/corrections
+ * Lists the user's time correction and manual entry requests with their approval outcome
+ * Used by desktop app to show pending requests and notify when a manager decides
+ */
+export async function GET() {
+	await connection();
+
+	try {
+		const session = await auth.api.getSession({ headers: await headers() });
+		if (!session?.user) {
+			return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+		}
+
+		const activeOrgId = session.session.activeOrganizationId;
+		if (!activeOrgId) {
+			return NextResponse.json({ error: "No active organization" }, { status: 400 });
+		}
+
+		const [currentEmployee] = await db
+			.select({ id: employee.id })
+			.from(employee)
+			.where(
+				and(
+					eq(employee.userId, session.user.id),
+					eq(employee.organizationId, activeOrgId),
+					eq(employee.isActive, true),
+				),
+			)
+			.limit(1);
+
+		if (!currentEmployee) {
+			return NextResponse.json(
+				{ error: "Employee record not found in this organization" },
+				{ status: 404 },
+			);
+		}
+
+		const requests = await db
+			.select()
+			.from(approvalRequest)
+			.where(
+				and(
+					eq(approvalRequest.organizationId, activeOrgId),
+					eq(approvalRequest.requestedBy, currentEmployee.id),
+					eq(approvalRequest.entityType, "time_entry"),
+				),
+			)
+			.orderBy(desc(approvalRequest.createdAt))
+			.limit(CORRECTIONS_LIMIT);
+
+		return NextResponse.json({
+			corrections: requests.map((request) => ({
+				id: request.id,
+				workPeriodId: request.entityId,
+				status: request.status,
+				reason: request.reason,
+				rejectionReason: request.rejectionReason,
+				createdAt: request.createdAt.toISOString(),
+				decidedAt:
+					request.status === "pending"
+						? null
+						: (request.approvedAt ?? request.updatedAt).toISOString(),
+			})),
+		});
+	} catch (_error) {
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
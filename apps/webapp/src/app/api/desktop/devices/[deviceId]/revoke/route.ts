@@ -0,0 +1,33 @@
+import { headers } from "next/headers";
+import { connection, NextResponse } from "next/server";
+import { auth } from "@/lib/auth";
+import { revokeDesktopDevice } from "@/lib/auth/desktop-device";
+
+/**
+ * POST /api/desktop/devices/[deviceId]/revoke
+ * Revokes one of the user's desktop installations and ends its session
+ * Used by desktop app to sign out a lost or replaced computer
+ */
+export async function POST(
+	_request: Request,
+	{ params }: { params: Promise<{ deviceId: string }> },
+) {
+	await connection();
+
+	try {
+		const session = await auth.api.getSession({ headers: await headers() });
+		if (!session?.user) {
+			return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+		}
+
+		const { deviceId } = await params;
+		const revoked = await revokeDesktopDevice(session.user.id, deviceId);
+		if (!revoked) {
+			return NextResponse.json({ error: "Device not found" }, { status: 404 });
+		}
+
+		return NextResponse.json({ success: true });
+	} catch (_error) {
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
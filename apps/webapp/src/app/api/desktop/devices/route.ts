@@ -0,0 +1,26 @@
+import { headers } from "next/headers";
+import { connection, NextResponse } from "next/server";
+import { auth } from "@/lib/auth";
+import { listDesktopDevices } from "@/lib/auth/desktop-device";
+
+/**
+ * GET /api/desktop/devices
+ * Returns the signed-in user's desktop installations that have not been revoked
+ * Used by desktop app to show where the account is signed in
+ */
+export async function GET() {
+	await connection();
+
+	try {
+		const session = await auth.api.getSession({ headers: await headers() });
+		if (!session?.user) {
+			return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+		}
+
+		const devices = await listDesktopDevices(session.user.id);
+
+		return NextResponse.json({ devices });
+	} catch (_error) {
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
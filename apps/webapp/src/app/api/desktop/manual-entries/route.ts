@@ -0,0 +1,81 @@
+import { headers } from "next/headers";
+import { connection, type NextRequest, NextResponse } from "next/server";
+import { createManualTimeEntry } from "@/app/[locale]/(app)/time-tracking/actions";
+import { auth } from "@/lib/auth";
+
+const UUID = /^[0-9a-f]{8}-[0-9a-f]{4}-[1-8][0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$/i;
+const DATE = /^\d{4}-\d{2}-\d{2}$/;
+const TIME = /^\d{2}:\d{2}$/;
+
+function optionalString(value: unknown): string | undefined {
+	return typeof value === "string" && value.length > 0 ? value : undefined;
+}
+
+/**
+ * POST /api/desktop/manual-entries
+ * Adds a work period the user forgot to punch, subject to the organization's change policy
+ * Used by desktop app for missing-punch corrections; submissionId makes queued replays idempotent
+ */
+export async function POST(request: NextRequest) {
+	await connection();
+
+	try {
+		const session = await auth.api.getSession({ headers: await headers() });
+		if (!session?.user) {
+			return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+		}
+
+		const body = await request.json();
+		const { submissionId, date, clockInTime, clockOutTime, reason } = body;
+
+		if (typeof submissionId !== "string" || !UUID.test(submissionId)) {
+			return NextResponse.json({ error: "submissionId must be a valid UUID" }, { status: 400 });
+		}
+		if (typeof date !== "string" || !DATE.test(date)) {
+			return NextResponse.json({ error: "date must be YYYY-MM-DD" }, { status: 400 });
+		}
+		if (
+			typeof clockInTime !== "string" ||
+			!TIME.test(clockInTime) ||
+			typeof clockOutTime !== "string" ||
+			!TIME.test(clockOutTime)
+		) {
+			return NextResponse.json(
+				{ error: "clockInTime and clockOutTime must be HH:MM" },
+				{ status: 400 },
+			);
+		}
+		if (typeof reason !== "string" || reason.trim().length === 0) {
+			return NextResponse.json({ error: "reason is required" }, { status: 400 });
+		}
+
+		const result = await createManualTimeEntry({
+			submissionId: submissionId.toLowerCase(),
+			date,
+			clockInTime,
+			clockOutTime,
+			reason,
+			timezone: optionalString(body.timezone),
+			browserTimezone: optionalString(body.browserTimezone) ?? null,
+			projectId: optionalString(body.projectId),
+			workCategoryId: optionalString(body.workCategoryId),
+		});
+
+		if (!result.success) {
+			return NextResponse.json({ error: result.error }, { status: 400 });
+		}
+
+		return NextResponse.json(
+			{
+				workPeriodId: result.data.workPeriodId,
+				requiresApproval: result.data.requiresApproval,
+				message: result.data.requiresApproval
+					? "Entry submitted. Awaiting manager approval."
+					: "Entry added successfully.",
+			},
+			{ status: 201 },
+		);
+	} catch (_error) {
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
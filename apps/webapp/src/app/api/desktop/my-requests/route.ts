@@ -0,0 +1,56 @@
+import { and, eq } from "drizzle-orm";
+import { headers } from "next/headers";
+import { connection, NextResponse } from "next/server";
+import { db } from "@/db";
+import { employee } from "@/db/schema";
+import { auth } from "@/lib/auth";
+import { getSelfServiceRequests } from "@/lib/self-service-requests/get-self-service-requests";
+
+/**
+ * GET /api/desktop/my-requests
+ * Lists the user's absence, correction and expense requests with their status
+ * Used by desktop app's request overview
+ */
+export async function GET() {
+	await connection();
+
+	try {
+		const session = await auth.api.getSession({ headers: await headers() });
+		if (!session?.user) {
+			return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+		}
+
+		const activeOrgId = session.session.activeOrganizationId;
+		if (!activeOrgId) {
+			return NextResponse.json({ error: "No active organization" }, { status: 400 });
+		}
+
+		const [currentEmployee] = await db
+			.select({ id: employee.id })
+			.from(employee)
+			.where(
+				and(
+					eq(employee.userId, session.user.id),
+					eq(employee.organizationId, activeOrgId),
+					eq(employee.isActive, true),
+				),
+			)
+			.limit(1);
+
+		if (!currentEmployee) {
+			return NextResponse.json(
+				{ error: "Employee record not found in this organization" },
+				{ status: 404 },
+			);
+		}
+
+		const result = await getSelfServiceRequests({
+			employeeId: currentEmployee.id,
+			organizationId: activeOrgId,
+		});
+
+		return NextResponse.json(result);
+	} catch (_error) {
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
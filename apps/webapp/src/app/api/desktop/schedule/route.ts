@@ -0,0 +1,92 @@
+import { and, asc, eq, gte, lte } from "drizzle-orm";
+import { DateTime } from "luxon";
+import { headers } from "next/headers";
+import { connection, NextResponse } from "next/server";
+import { db } from "@/db";
+import { organization } from "@/db/auth-schema";
+import { employee, shift } from "@/db/schema";
+import { auth } from "@/lib/auth";
+
+/**
+ * GET /api/desktop/schedule
+ * Returns the user's published shifts from yesterday through the next four weeks
+ * Shift times are wall-clock times in the returned organization timezone
+ * Used by desktop app to show the next shift, remind before it and flag off-schedule punches
+ */
+export async function GET() {
+	await connection();
+
+	try {
+		const session = await auth.api.getSession({ headers: await headers() });
+		if (!session?.user) {
+			return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+		}
+
+		const activeOrgId = session.session.activeOrganizationId;
+		if (!activeOrgId) {
+			return NextResponse.json({ error: "No active organization" }, { status: 400 });
+		}
+
+		const [currentEmployee] = await db
+			.select({ id: employee.id })
+			.from(employee)
+			.where(
+				and(
+					eq(employee.userId, session.user.id),
+					eq(employee.organizationId, activeOrgId),
+					eq(employee.isActive, true),
+				),
+			)
+			.limit(1);
+
+		if (!currentEmployee) {
+			return NextResponse.json(
+				{ error: "Employee record not found in this organization" },
+				{ status: 404 },
+			);
+		}
+
+		// Yesterday is included so a night shift that started then is still visible
+		const startDate = DateTime.now().toUTC().startOf("day").minus({ days: 1 });
+		const endDate = startDate.plus({ days: 28 }).endOf("day");
+
+		const [org, shifts] = await Promise.all([
+			db.query.organization.findFirst({
+				where: eq(organization.id, activeOrgId),
+				columns: { timezone: true },
+			}),
+			db.query.shift.findMany({
+				columns: {
+					id: true,
+					date: true,
+					startTime: true,
+					endTime: true,
+					notes: true,
+					color: true,
+				},
+				where: and(
+					eq(shift.organizationId, activeOrgId),
+					eq(shift.employeeId, currentEmployee.id),
+					eq(shift.status, "published"),
+					gte(shift.date, startDate.toJSDate()),
+					lte(shift.date, endDate.toJSDate()),
+				),
+				orderBy: [asc(shift.date), asc(shift.startTime)],
+			}),
+		]);
+
+		return NextResponse.json({
+			timezone: org?.timezone || "UTC",
+			shifts: shifts.map((scheduledShift) => ({
+				id: scheduledShift.id,
+				date: DateTime.fromJSDate(scheduledShift.date, { zone: "utc" }).toISODate(),
+				startTime: scheduledShift.startTime,
+				endTime: scheduledShift.endTime,
+				notes: scheduledShift.notes,
+				color: scheduledShift.color,
+			})),
+		});
+	} catch (_error) {
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
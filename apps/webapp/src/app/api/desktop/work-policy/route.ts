@@ -0,0 +1,118 @@
+import { and, eq } from "drizzle-orm";
+import { headers } from "next/headers";
+import { DateTime } from "luxon";
+import { connection, NextResponse } from "next/server";
+import { db } from "@/db";
+import { employee } from "@/db/schema";
+import { auth } from "@/lib/auth";
+import { getDailyWorkRequirementsForEmployee } from "@/lib/calendar/work-policy-requirements";
+import { runEmployeePolicyLookup } from "@/lib/effect/work-policy-runtime";
+import { isValidIanaTimezone } from "@/lib/time-tracking/timezone-capture";
+
+/**
+ * Minutes the employee's contract or work schedule asks for in one week
+ * Null when neither sets a target
+ */
+async function getWeeklyTargetMinutes(
+	employeeId: string,
+	organizationId: string,
+	weekStart: string,
+	timezone: string | null,
+) {
+	const zone = isValidIanaTimezone(timezone) ? timezone : "utc";
+	const requested = DateTime.fromISO(weekStart, { zone });
+	const start = (requested.isValid ? requested : DateTime.now().setZone(zone)).startOf("week");
+
+	const requirements = await getDailyWorkRequirementsForEmployee({
+		organizationId,
+		employeeId,
+		startDate: start.toJSDate(),
+		endDate: start.plus({ days: 6 }).endOf("day").toJSDate(),
+		timezone: zone,
+	});
+
+	const days = Object.values(requirements);
+	return days.length > 0 ? days.reduce((total, day) => total + day.requiredMinutes, 0) : null;
+}
+
+/**
+ * GET /api/desktop/work-policy
+ * Returns the break rules and working-time limits of the employee's effective work policy
+ * Used by desktop app to remind about mandatory breaks, including while offline
+ *
+ * With `weekStart` (and optionally `timezone`), also returns `weeklyTargetMinutes` for
+ * that week, so the desktop weekly summary matches the webapp
+ */
+export async function GET(request: Request) {
+	await connection();
+
+	try {
+		const resolvedHeaders = await headers();
+		const session = await auth.api.getSession({ headers: resolvedHeaders });
+
+		if (!session?.user) {
+			return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+		}
+
+		const activeOrgId = session.session.activeOrganizationId;
+		if (!activeOrgId) {
+			return NextResponse.json({ error: "No active organization" }, { status: 400 });
+		}
+
+		const [currentEmployee] = await db
+			.select({ id: employee.id })
+			.from(employee)
+			.where(
+				and(
+					eq(employee.userId, session.user.id),
+					eq(employee.organizationId, activeOrgId),
+					eq(employee.isActive, true),
+				),
+			)
+			.limit(1);
+
+		if (!currentEmployee) {
+			return NextResponse.json(
+				{ error: "Employee record not found in this organization" },
+				{ status: 404 },
+			);
+		}
+
+		const policy = await runEmployeePolicyLookup(currentEmployee.id, activeOrgId);
+		const regulation = policy?.regulation ?? null;
+
+		const searchParams = new URL(request.url).searchParams;
+		const weekStart = searchParams.get("weekStart");
+		const weeklyTargetMinutes = weekStart
+			? await getWeeklyTargetMinutes(
+					currentEmployee.id,
+					activeOrgId,
+					weekStart,
+					searchParams.get("timezone"),
+				)
+			: undefined;
+
+		return NextResponse.json({
+			...(weeklyTargetMinutes !== undefined ? { weeklyTargetMinutes } : {}),
+			policy: policy
+				? {
+						id: policy.policyId,
+						name: policy.policyName,
+						regulation: regulation
+							? {
+									maxDailyMinutes: regulation.maxDailyMinutes,
+									maxUninterruptedMinutes: regulation.maxUninterruptedMinutes,
+									alertBeforeLimitMinutes: regulation.alertBeforeLimitMinutes,
+									breakRules: regulation.breakRules.map((rule) => ({
+										workingMinutesThreshold: rule.workingMinutesThreshold,
+										requiredBreakMinutes: rule.requiredBreakMinutes,
+									})),
+								}
+							: null,
+					}
+				: null,
+		});
+	} catch (_error) {
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
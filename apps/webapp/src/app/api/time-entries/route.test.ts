@@ -1,3 +1,4 @@
+import { createHash, generateKeyPairSync, sign } from "node:crypto";
 import { readFileSync } from "node:fs";
 import { beforeEach, describe, expect, it, vi } from "vitest";
 
@@ -41,6 +42,7 @@ const mockState = vi.hoisted(() => {
 		clockingClockIn: vi.fn(),
 		clockingClockOut: vi.fn(),
 		employeeHasAccessToCategory: vi.fn(),
+		findDesktopDevice: vi.fn(),
 		getAbility: vi.fn(),
 		getUtcOffsetMinutesForZone: vi.fn(),
 		getSession: vi.fn(),
@@ -50,6 +52,7 @@ const mockState = vi.hoisted(() => {
 		limit,
 		requireBillingForMutation: vi.fn(),
 		requireActor: vi.fn(),
+		revokedDeviceResponse: vi.fn(),
 		resolveTimeEntryTimezoneCapture: vi.fn(),
 		runPromise: vi.fn(),
 		select,
@@ -142,6 +145,14 @@ vi.mock("@/lib/auth", () => ({
 	},
 }));
 
+vi.mock("@/lib/auth/desktop-device", async () => ({
+	...(await vi.importActual<typeof import("@/lib/auth/desktop-device")>(
+		"@/lib/auth/desktop-device",
+	)),
+	findDesktopDevice: mockState.findDesktopDevice,
+	revokedDeviceResponse: mockState.revokedDeviceResponse,
+}));
+
 vi.mock("@/lib/auth-helpers", () => ({
 	getAbility: mockState.getAbility,
 }));
@@ -201,6 +212,47 @@ vi.mock("drizzle-orm", () => ({
 
 const { GET, POST } = await import("./route");
 
+const DEVICE_ID = "5f0c6a3e-8d1b-4c2a-9e7f-1a2b3c4d5e6f";
+const ACTION_ID = "0b7e2c4d-1f3a-4b5c-8d9e-0f1a2b3c4d5e";
+
+/** Signs queue links the way the desktop app's chain.rs does */
+function desktopChain(payloads: { action: string; timestamp: number; payload: string }[]) {
+	const { publicKey, privateKey } = generateKeyPairSync("ed25519");
+	const { x } = publicKey.export({ format: "jwk" });
+	let previousHash = "genesis";
+	const links = payloads.map(({ action, timestamp, payload }) => {
+		const hash = createHash("sha256")
+			.update(`${action}|${timestamp}|${timestamp}|${previousHash}|${payload}`)
+			.digest("hex");
+		const link = {
+			sequence: 1,
+			action,
+			timestamp,
+			payload,
+			createdAt: timestamp,
+			previousHash,
+			hash,
+			signature: sign(null, Buffer.from(hash), privateKey).toString("base64"),
+		};
+		previousHash = hash;
+		return link;
+	});
+
+	return { publicKey: Buffer.from(x ?? "", "base64url").toString("base64"), links };
+}
+
+function replayedClockIn(chain: ReturnType<typeof desktopChain>) {
+	return new Request("https://z8.test/api/time-entries", {
+		body: JSON.stringify({
+			type: "clock_in",
+			timestamp: "2026-05-04T09:00:00.000Z",
+			chain,
+		}),
+		headers: { "x-z8-device-id": DEVICE_ID },
+		method: "POST",
+	}) as never;
+}
+
 function createGetRequest(employeeId: string) {
 	return {
 		nextUrl: new URL(`https://z8.test/api/time-entries?employeeId=${employeeId}`),
@@ -380,6 +432,10 @@ describe("POST /api/time-entries", () => {
 		mockState.isBillingMutationAllowed.mockReset();
 		mockState.createBillingForbiddenResponse.mockReset();
 		mockState.employeeHasAccessToCategory.mockReset();
+		mockState.findDesktopDevice.mockReset();
+		mockState.findDesktopDevice.mockResolvedValue(null);
+		mockState.revokedDeviceResponse.mockReset();
+		mockState.revokedDeviceResponse.mockResolvedValue(null);
 		mockState.headers.mockResolvedValue(new Headers());
 		mockState.getSession.mockResolvedValue({
 			session: { activeOrganizationId: "org-1" },
@@ -835,4 +891,103 @@ describe("POST /api/time-entries", () => {
 			}),
 		);
 	});
+
+	it("records replayed punches whose chain the device signed", async () => {
+		const chain = desktopChain([
+			{
+				action: "clock_in",
+				timestamp: 1777885200,
+				payload: JSON.stringify({
+					workLocationType: "office",
+					capture: { actionId: ACTION_ID, timestamp: "2026-05-04T09:00:00Z" },
+				}),
+			},
+		]);
+		mockState.findDesktopDevice.mockResolvedValue({ id: DEVICE_ID, publicKey: chain.publicKey });
+
+		const response = await POST(replayedClockIn(chain));
+
+		expect(response.status).toBe(201);
+		expect(mockState.clockingClockIn).toHaveBeenCalledTimes(1);
+	});
+
+	it("rejects replayed punches whose queued payload was edited after signing", async () => {
+		const chain = desktopChain([
+			{
+				action: "clock_in",
+				timestamp: 1777885200,
+				payload: JSON.stringify({
+					workLocationType: "office",
+					capture: { actionId: ACTION_ID, timestamp: "2026-05-04T10:00:00Z" },
+				}),
+			},
+		]);
+		chain.links[0].payload = JSON.stringify({
+			workLocationType: "office",
+			capture: { actionId: ACTION_ID, timestamp: "2026-05-04T09:00:00Z" },
+		});
+		mockState.findDesktopDevice.mockResolvedValue({ id: DEVICE_ID, publicKey: chain.publicKey });
+
+		const response = await POST(replayedClockIn(chain));
+
+		expect(response.status).toBe(400);
+		expect(await response.json()).toEqual({
+			error: "Offline punch rejected: Chain link does not match its hash",
+		});
+		expect(mockState.clockingClockIn).not.toHaveBeenCalled();
+	});
+
+	it("rejects replayed punches that differ from their signed chain link", async () => {
+		const chain = desktopChain([
+			{
+				action: "clock_in",
+				timestamp: 1777888800,
+				payload: JSON.stringify({
+					workLocationType: "office",
+					capture: { actionId: ACTION_ID, timestamp: "2026-05-04T10:00:00Z" },
+				}),
+			},
+		]);
+		mockState.findDesktopDevice.mockResolvedValue({ id: DEVICE_ID, publicKey: chain.publicKey });
+
+		const response = await POST(replayedClockIn(chain));
+
+		expect(response.status).toBe(400);
+		expect(mockState.clockingClockIn).not.toHaveBeenCalled();
+	});
+
+	it("rejects replayed chains from devices that are not registered to the user", async () => {
+		const chain = desktopChain([]);
+
+		const response = await POST(replayedClockIn(chain));
+
+		expect(response.status).toBe(400);
+		expect(mockState.clockingClockIn).not.toHaveBeenCalled();
+	});
+
+	it("refuses punches from a revoked desktop device", async () => {
+		mockState.revokedDeviceResponse.mockResolvedValue(
+			Response.json({ error: "Device revoked", code: "device_revoked" }, { status: 401 }),
+		);
+
+		const response = await POST(replayedClockIn(desktopChain([])));
+
+		expect(response.status).toBe(401);
+		expect(await response.json()).toEqual({ error: "Device revoked", code: "device_revoked" });
+		expect(mockState.clockingClockIn).not.toHaveBeenCalled();
+	});
+
+	it("records the desktop device a punch came from", async () => {
+		mockState.findDesktopDevice.mockResolvedValue({ id: DEVICE_ID, publicKey: "unused" });
+
+		const response = await POST(replayedClockIn(desktopChain([])));
+
+		expect(response.status).toBe(201);
+		expect(mockState.findDesktopDevice).toHaveBeenCalledWith(expect.anything(), "user-1");
+		expect(mockState.clockingClockIn).toHaveBeenCalledWith(
+			expect.objectContaining({
+				source: expect.objectContaining({ desktopDeviceId: DEVICE_ID }),
+			}),
+		);
+	});
 });
@@ -7,6 +7,11 @@ import { db } from "@/db";
 import { member } from "@/db/auth-schema";
 import { employee, project, timeEntry, userSettings, workCategory } from "@/db/schema";
 import { auth } from "@/lib/auth";
+import {
+	findDesktopDevice,
+	revokedDeviceResponse,
+	verifyReplayChain,
+} from "@/lib/auth/desktop-device";
 import { getAbility } from "@/lib/auth-helpers";
 import {
 	accessibleByDrizzle,
@@ -201,6 +206,12 @@ export async function POST(request: NextRequest) {
 		// Await headers and body in parallel
 		const [resolvedHeaders, body] = await Promise.all([headers(), request.json()]);
 
+		// A revoked desktop app is refused even while it still holds a session
+		const revoked = await revokedDeviceResponse(request);
+		if (revoked) {
+			return revoked;
+		}
+
 		// With Bearer plugin, getSession handles both cookie and Bearer token auth
 		const session = await auth.api.getSession({ headers: resolvedHeaders });
 
@@ -210,6 +221,7 @@ export async function POST(request: NextRequest) {
 		if (body && typeof body === "object" && "employeeId" in body) {
 			return NextResponse.json({ error: "employeeId is server-derived" }, { status: 400 });
 		}
+		const device = await findDesktopDevice(request, session.user.id);
 
 		const {
 			id,
@@ -224,6 +236,7 @@ export async function POST(request: NextRequest) {
 			utcOffsetMinutes,
 			replay,
 			organizationId,
+			chain,
 		} = body;
 
 		// Validate required fields
@@ -315,6 +328,15 @@ export async function POST(request: NextRequest) {
 		if (Number.isNaN(entryTime.getTime())) {
 			return NextResponse.json({ error: "Invalid clock instant" }, { status: 400 });
 		}
+		// Punches replayed from the desktop offline queue carry its signed hash chain
+		if (chain !== undefined) {
+			const chainError = device
+				? verifyReplayChain(chain, device.publicKey, { actionId, instant: entryTime })
+				: "Unknown device";
+			if (chainError) {
+				return NextResponse.json({ error: `Offline punch rejected: ${chainError}` }, { status: 400 });
+			}
+		}
 		if (hasCapturedEvidence) {
 			const ageMs = Date.now() - entryTime.getTime();
 			const maxAgeMs = isReplay ? 7 * 24 * 60 * 60_000 : 5 * 60_000;
@@ -399,7 +421,11 @@ export async function POST(request: NextRequest) {
 			createdBy: session.user.id,
 			actionId,
 			action: { instant: instantFromDate(entryTime), ...timezoneCapture },
-			source: { ipAddress: null, deviceInfo: isReplay ? "extension-replay" : "api" },
+			source: {
+				ipAddress: null,
+				deviceInfo: isReplay ? "extension-replay" : "api",
+				desktopDeviceId: device?.id ?? null,
+			},
 			notes,
 			location,
 		};
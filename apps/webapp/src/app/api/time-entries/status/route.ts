@@ -1,9 +1,8 @@
-import { and, eq, isNull } from "drizzle-orm";
 import { headers } from "next/headers";
 import { connection, NextResponse } from "next/server";
-import { db } from "@/db";
-import { employee, workPeriod } from "@/db/schema";
 import { auth } from "@/lib/auth";
+import { revokedDeviceResponse, touchDesktopDevice } from "@/lib/auth/desktop-device";
+import { getClockStatus, NO_EMPLOYEE_CLOCK_STATUS } from "@/lib/time-tracking/clock-status";
 import { ClockingAccessError, clockingService } from "@/lib/time-tracking/clocking-service";
 
 /**
@@ -20,7 +19,7 @@ import { ClockingAccessError, clockingService } from "@/lib/time-tracking/clocki
  *   activeWorkPeriod: { id: string, startTime: string } | null
  * }
  */
-export async function GET() {
+export async function GET(request: Request) {
 	// Opt out of caching - must be awaited immediately, not stored as promise
 	await connection();
 
@@ -31,17 +30,17 @@ export async function GET() {
 		const session = await auth.api.getSession({ headers: resolvedHeaders });
 
 		if (!session?.user) {
-			return NextResponse.json(
-				{
-					hasEmployee: false,
-					employeeId: null,
-					isClockedIn: false,
-					activeWorkPeriod: null,
-				},
-				{ status: 401 },
-			);
+			// A revoked desktop app lost its session; tell it why so it signs out
+			const revoked = await revokedDeviceResponse(request);
+			if (revoked) {
+				return revoked;
+			}
+
+			return NextResponse.json(NO_EMPLOYEE_CLOCK_STATUS, { status: 401 });
 		}
 
+		await touchDesktopDevice(request, session.user.id);
+
 		// Get employee record for the active organization
 		const activeOrgId = session.session.activeOrganizationId;
 		if (!activeOrgId) {
@@ -49,45 +48,7 @@ export async function GET() {
 		}
 		await clockingService.requireActor({ userId: session.user.id, activeOrganizationId: activeOrgId });
 
-		const emp = await db.query.employee.findFirst({
-			where: and(
-				eq(employee.userId, session.user.id),
-				eq(employee.organizationId, activeOrgId),
-				eq(employee.isActive, true),
-			),
-		});
-
-		if (!emp) {
-			return NextResponse.json({
-				hasEmployee: false,
-				employeeId: null,
-				isClockedIn: false,
-				activeWorkPeriod: null,
-			});
-		}
-
-		// Check for active work period in this organization
-		const period = await db.query.workPeriod.findFirst({
-			where: and(
-				eq(workPeriod.employeeId, emp.id),
-				eq(workPeriod.organizationId, activeOrgId),
-				isNull(workPeriod.endTime),
-			),
-		});
-
-		const response = {
-			hasEmployee: true,
-			employeeId: emp.id,
-			isClockedIn: !!period,
-			activeWorkPeriod: period
-				? {
-						id: period.id,
-						startTime: period.startTime.toISOString(),
-					}
-				: null,
-		};
-
-		return NextResponse.json(response);
+		return NextResponse.json(await getClockStatus(session.user.id, activeOrgId));
 	} catch (error) {
 		if (error instanceof ClockingAccessError) {
 			return NextResponse.json({ error: error.message }, { status: 403 });
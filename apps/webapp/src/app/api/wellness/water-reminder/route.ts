@@ -1,8 +1,35 @@
 import { headers } from "next/headers";
 import { connection, NextResponse } from "next/server";
+import { getWaterReminderStatus } from "@/app/[locale]/(app)/wellness/actions";
 import { auth } from "@/lib/auth";
 import { sendPushToUser } from "@/lib/notifications/push-service";
 
+/**
+ * GET /api/wellness/water-reminder
+ * Returns the reminder settings, snooze state and last intake for the current user
+ *
+ * Used by desktop app to schedule reminders locally while the user is clocked in
+ */
+export async function GET() {
+	await connection();
+	try {
+		const session = await auth.api.getSession({ headers: await headers() });
+		if (!session?.user) {
+			return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+		}
+
+		const result = await getWaterReminderStatus();
+		if (!result.success) {
+			return NextResponse.json({ error: result.error }, { status: 500 });
+		}
+
+		return NextResponse.json(result.data);
+	} catch (error) {
+		console.error("Error fetching water reminder status:", error);
+		return NextResponse.json({ error: "Internal server error" }, { status: 500 });
+	}
+}
+
 /**
  * POST /api/wellness/water-reminder
  * Trigger a water reminder push notification for the current user
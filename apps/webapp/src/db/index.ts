@@ -183,6 +183,8 @@ export {
 	// Data export tables
 	dataExport,
 	dataExportRelations,
+	// Desktop app installations
+	desktopDevice,
 	employee,
 	employeeManagers,
 	employeeManagersRelations,
@@ -0,0 +1,24 @@
+import { index, pgTable, text, timestamp, uuid } from "drizzle-orm/pg-core";
+import { session, user } from "../auth-schema";
+
+/**
+ * Desktop app installations, registered with their Ed25519 key at sign-in.
+ * Revoking one deletes its session and answers its later requests with `device_revoked`.
+ */
+export const desktopDevice = pgTable(
+	"desktop_device",
+	{
+		id: uuid("id").defaultRandom().primaryKey(),
+		userId: text("user_id")
+			.notNull()
+			.references(() => user.id, { onDelete: "cascade" }),
+		sessionId: text("session_id").references(() => session.id, { onDelete: "set null" }),
+		publicKey: text("public_key").notNull(),
+		name: text("name"),
+		platform: text("platform"),
+		createdAt: timestamp("created_at").defaultNow().notNull(),
+		lastUsedAt: timestamp("last_used_at"),
+		revokedAt: timestamp("revoked_at"),
+	},
+	(table) => [index("desktopDevice_userId_idx").on(table.userId)],
+);
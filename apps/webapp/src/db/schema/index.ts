@@ -31,6 +31,8 @@ export * from "./cron-job";
 export * from "./custom-role";
 export * from "./customer";
 export * from "./daily-digest-delivery";
+// Desktop app installations
+export * from "./desktop-device";
 // Discord integration
 export * from "./discord-integration";
 export * from "./email-template";
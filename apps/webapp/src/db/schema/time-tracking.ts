@@ -16,6 +16,7 @@ import {
 import { organization, user } from "../auth-schema";
 import { approvalRequest } from "./approval";
 import { approvalWorkflow } from "./approval-workflow";
+import { desktopDevice } from "./desktop-device";
 import {
 	approvalStatusEnum,
 	timeEntryTypeEnum,
@@ -72,6 +73,10 @@ export const timeEntry = pgTable(
 		location: text("location"), // GPS coordinates or location name
 		ipAddress: text("ip_address"),
 		deviceInfo: text("device_info"),
+		// Desktop installation that recorded the punch, see desktop-device.ts
+		desktopDeviceId: uuid("desktop_device_id").references(() => desktopDevice.id, {
+			onDelete: "set null",
+		}),
 
 		createdAt: timestamp("created_at").defaultNow().notNull(),
 		createdBy: text("created_by")
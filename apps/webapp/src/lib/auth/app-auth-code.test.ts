@@ -70,6 +70,7 @@ describe("app auth code service", () => {
 			codeChallenge: TEST_CHALLENGE,
 			sessionToken: "session-token",
 			status: "pending",
+			userId: "user-1",
 			expiresAt: new Date(Date.now() + 60_000),
 		});
 		mockState.updateReturning.mockResolvedValue([{ id: "code-1" }]);
@@ -79,6 +80,7 @@ describe("app auth code service", () => {
 		).resolves.toEqual({
 			sessionToken: "session-token",
 			status: "success",
+			userId: "user-1",
 		});
 
 		expect(mockState.updateSet).toHaveBeenCalledWith(expect.objectContaining({ status: "used" }));
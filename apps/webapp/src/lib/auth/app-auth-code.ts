@@ -83,5 +83,5 @@ export async function consumeAppAuthCode(input: {
 		return { status: "invalid_code" } as const;
 	}
 
-	return { sessionToken: record.sessionToken, status: "success" } as const;
+	return { sessionToken: record.sessionToken, status: "success", userId: record.userId } as const;
 }
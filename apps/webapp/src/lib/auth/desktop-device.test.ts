@@ -0,0 +1,101 @@
+import { generateKeyPairSync, sign } from "node:crypto";
+import { describe, expect, it, vi } from "vitest";
+
+vi.mock("@/db", () => ({
+	db: {},
+	desktopDevice: {},
+	session: {},
+}));
+
+import { hashChainLink, verifyDeviceSignature, verifyReplayChain } from "./desktop-device";
+
+function desktopKey() {
+	const { publicKey, privateKey } = generateKeyPairSync("ed25519");
+	const { x } = publicKey.export({ format: "jwk" });
+
+	return {
+		publicKey: Buffer.from(x ?? "", "base64url").toString("base64"),
+		sign: (message: string) => sign(null, Buffer.from(message), privateKey).toString("base64"),
+	};
+}
+
+describe("desktop device signatures", () => {
+	it("accepts a signature over the verifier from the registered key", () => {
+		const key = desktopKey();
+
+		expect(
+			verifyDeviceSignature(
+				{ publicKey: key.publicKey, signature: key.sign("VERIFIER") },
+				"VERIFIER",
+			),
+		).toBe(true);
+	});
+
+	it("rejects signatures over another verifier, from another key, or malformed", () => {
+		const key = desktopKey();
+		const other = desktopKey();
+
+		expect(
+			verifyDeviceSignature({ publicKey: key.publicKey, signature: key.sign("OTHER") }, "VERIFIER"),
+		).toBe(false);
+		expect(
+			verifyDeviceSignature(
+				{ publicKey: key.publicKey, signature: other.sign("VERIFIER") },
+				"VERIFIER",
+			),
+		).toBe(false);
+		expect(
+			verifyDeviceSignature({ publicKey: "bm90IGEga2V5", signature: "c2ln" }, "VERIFIER"),
+		).toBe(false);
+	});
+});
+
+describe("desktop replay chains", () => {
+	function chainOf(key: ReturnType<typeof desktopKey>, payloads: string[]) {
+		let previousHash = "genesis";
+		return {
+			links: payloads.map((payload, index) => {
+				const fields = {
+					action: "clock_in",
+					timestamp: 100 + index,
+					payload,
+					createdAt: 100 + index,
+					previousHash,
+				};
+				const hash = hashChainLink(fields);
+				previousHash = hash;
+				return { ...fields, hash, signature: key.sign(hash) };
+			}),
+		};
+	}
+	const punch = {
+		actionId: "0b7e2c4d-1f3a-4b5c-8d9e-0f1a2b3c4d5e",
+		instant: new Date("2026-05-04T09:00:00Z"),
+	};
+	const payload = JSON.stringify({
+		capture: { actionId: punch.actionId, timestamp: "2026-05-04T09:00:00.000000000Z" },
+	});
+
+	it("accepts links in order, signed by the device and ending in the punch", () => {
+		const key = desktopKey();
+
+		expect(verifyReplayChain(chainOf(key, ["{}", payload]), key.publicKey, punch)).toBeNull();
+		expect(verifyReplayChain({ links: [] }, key.publicKey, punch)).toBeNull();
+	});
+
+	it("rejects reordered, foreign or unrelated links", () => {
+		const key = desktopKey();
+		const chain = chainOf(key, ["{}", payload]);
+
+		expect(
+			verifyReplayChain({ links: [...chain.links].reverse() }, key.publicKey, punch),
+		).toBe("Chain links are out of order");
+		expect(verifyReplayChain(chain, desktopKey().publicKey, punch)).toBe(
+			"Chain link is not signed by this device",
+		);
+		expect(verifyReplayChain(chainOf(key, [payload, "{}"]), key.publicKey, punch)).toBe(
+			"Punch does not match its chain link",
+		);
+		expect(verifyReplayChain({ links: "none" }, key.publicKey, punch)).toBe("Malformed chain");
+	});
+});
@@ -0,0 +1,287 @@
+import { createHash, createPublicKey, verify } from "node:crypto";
+import { and, desc, eq, isNotNull, isNull, lt, or } from "drizzle-orm";
+import { NextResponse } from "next/server";
+import { db, desktopDevice, session } from "@/db";
+
+export const DESKTOP_DEVICE_HEADER = "x-z8-device-id";
+export const DEVICE_REVOKED_CODE = "device_revoked";
+
+// DER prefix that wraps a raw 32-byte Ed25519 key as SubjectPublicKeyInfo
+const ED25519_SPKI_PREFIX = Buffer.from("302a300506032b6570032100", "hex");
+const UUID_PATTERN = /^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$/i;
+// Requests touch the device at most this often
+const LAST_USED_RESOLUTION_MS = 5 * 60 * 1000;
+
+export type DesktopDeviceRegistration = {
+	publicKey: string;
+	signature: string;
+	name?: string;
+	platform?: string;
+};
+
+/** A link of the desktop app's offline queue chain, see its `chain.rs` */
+export type ReplayChainLink = {
+	action: string;
+	timestamp: number;
+	payload: string | null;
+	createdAt: number;
+	previousHash: string;
+	hash: string;
+	signature: string;
+};
+
+/** Verifies a base64 Ed25519 signature against a base64 raw public key */
+function verifyEd25519(publicKey: string, message: string, signature: string) {
+	try {
+		const rawKey = Buffer.from(publicKey, "base64");
+		if (rawKey.length !== 32) {
+			return false;
+		}
+
+		const key = createPublicKey({
+			key: Buffer.concat([ED25519_SPKI_PREFIX, rawKey]),
+			format: "der",
+			type: "spki",
+		});
+
+		return verify(null, Buffer.from(message), key, Buffer.from(signature, "base64"));
+	} catch {
+		return false;
+	}
+}
+
+/**
+ * Checks that the device holds the private key for `publicKey` by verifying its
+ * Ed25519 signature over the PKCE verifier of the login it started.
+ */
+export function verifyDeviceSignature(device: DesktopDeviceRegistration, verifier: string) {
+	return verifyEd25519(device.publicKey, verifier, device.signature);
+}
+
+/**
+ * Hex SHA-256 of `action|timestamp|createdAt|previousHash|payload`, as the desktop
+ * app hashes its links
+ */
+export function hashChainLink(link: Omit<ReplayChainLink, "hash" | "signature">) {
+	const data = [link.action, link.timestamp, link.createdAt, link.previousHash, link.payload ?? ""];
+	return createHash("sha256").update(data.join("|")).digest("hex");
+}
+
+function isChainLink(value: unknown): value is ReplayChainLink {
+	const link = value as ReplayChainLink | null;
+	return (
+		typeof link === "object" &&
+		link !== null &&
+		typeof link.action === "string" &&
+		Number.isInteger(link.timestamp) &&
+		(link.payload === null || typeof link.payload === "string") &&
+		Number.isInteger(link.createdAt) &&
+		typeof link.previousHash === "string" &&
+		typeof link.hash === "string" &&
+		typeof link.signature === "string"
+	);
+}
+
+/** The punch captures (`actionId` and `timestamp`) a queued action's payload holds */
+function payloadCaptures(value: unknown): { actionId: string; timestamp: string }[] {
+	if (typeof value !== "object" || value === null) {
+		return [];
+	}
+	const record = value as Record<string, unknown>;
+	const own =
+		typeof record.actionId === "string" && typeof record.timestamp === "string"
+			? [{ actionId: record.actionId, timestamp: record.timestamp }]
+			: [];
+	return [...own, ...Object.values(record).flatMap(payloadCaptures)];
+}
+
+/**
+ * Checks the offline queue links a desktop punch was replayed with: every link hashes
+ * its own fields, follows the link before it and is signed with the device's
+ * registered key, and the last one recorded this punch. Links the webapp accepted
+ * with earlier replays are not sent again, so the first may follow any hash. Rows
+ * queued before the chain existed come without links.
+ * Returns why the chain fails, or null when it holds.
+ */
+export function verifyReplayChain(
+	chain: unknown,
+	publicKey: string,
+	punch: { actionId?: string; instant: Date },
+) {
+	const links = (chain as { links?: unknown } | null)?.links;
+	if (!Array.isArray(links) || !links.every(isChainLink)) {
+		return "Malformed chain";
+	}
+	if (links.length === 0) {
+		return null;
+	}
+
+	for (const [index, link] of links.entries()) {
+		if (index > 0 && link.previousHash !== links[index - 1].hash) {
+			return "Chain links are out of order";
+		}
+		if (hashChainLink(link) !== link.hash) {
+			return "Chain link does not match its hash";
+		}
+		if (!verifyEd25519(publicKey, link.hash, link.signature)) {
+			return "Chain link is not signed by this device";
+		}
+	}
+
+	let payload: unknown;
+	try {
+		payload = JSON.parse(links[links.length - 1].payload ?? "null");
+	} catch {
+		return "Malformed chain payload";
+	}
+	const recorded = payloadCaptures(payload).some(
+		(capture) =>
+			(punch.actionId === undefined || capture.actionId === punch.actionId) &&
+			new Date(capture.timestamp).getTime() === punch.instant.getTime(),
+	);
+	return recorded ? null : "Punch does not match its chain link";
+}
+
+/**
+ * Records a desktop installation for the session it just signed in with
+ */
+export async function registerDesktopDevice(input: {
+	userId: string;
+	sessionToken: string;
+	device: DesktopDeviceRegistration;
+}) {
+	const sessionRecord = await db.query.session.findFirst({
+		where: eq(session.token, input.sessionToken),
+		columns: { id: true },
+	});
+
+	const [record] = await db
+		.insert(desktopDevice)
+		.values({
+			userId: input.userId,
+			sessionId: sessionRecord?.id ?? null,
+			publicKey: input.device.publicKey,
+			name: input.device.name?.slice(0, 255) ?? null,
+			platform: input.device.platform?.slice(0, 64) ?? null,
+		})
+		.returning({ id: desktopDevice.id });
+
+	return record.id;
+}
+
+/**
+ * Lists the user's desktop installations that have not been revoked, newest first
+ */
+export async function listDesktopDevices(userId: string) {
+	return db
+		.select({
+			id: desktopDevice.id,
+			name: desktopDevice.name,
+			platform: desktopDevice.platform,
+			createdAt: desktopDevice.createdAt,
+			lastUsedAt: desktopDevice.lastUsedAt,
+		})
+		.from(desktopDevice)
+		.where(and(eq(desktopDevice.userId, userId), isNull(desktopDevice.revokedAt)))
+		.orderBy(desc(desktopDevice.createdAt));
+}
+
+/**
+ * Revokes one of the user's devices and signs it out by deleting its session
+ * Returns false when the device is unknown, someone else's, or already revoked
+ */
+export async function revokeDesktopDevice(userId: string, deviceId: string) {
+	if (!UUID_PATTERN.test(deviceId)) {
+		return false;
+	}
+
+	const [revoked] = await db
+		.update(desktopDevice)
+		.set({ revokedAt: new Date() })
+		.where(
+			and(
+				eq(desktopDevice.id, deviceId),
+				eq(desktopDevice.userId, userId),
+				isNull(desktopDevice.revokedAt),
+			),
+		)
+		.returning({ sessionId: desktopDevice.sessionId });
+
+	if (!revoked) {
+		return false;
+	}
+	if (revoked.sessionId) {
+		await db.delete(session).where(eq(session.id, revoked.sessionId));
+	}
+
+	return true;
+}
+
+/**
+ * The signed-in user's device a request came from, with its registered key. Null
+ * when the request names no device, or one that is someone else's or revoked.
+ */
+export async function findDesktopDevice(request: Request, userId: string) {
+	const deviceId = request.headers.get(DESKTOP_DEVICE_HEADER);
+	if (!deviceId || !UUID_PATTERN.test(deviceId)) {
+		return null;
+	}
+
+	const device = await db.query.desktopDevice.findFirst({
+		where: and(
+			eq(desktopDevice.id, deviceId),
+			eq(desktopDevice.userId, userId),
+			isNull(desktopDevice.revokedAt),
+		),
+		columns: { id: true, publicKey: true },
+	});
+	return device ?? null;
+}
+
+/**
+ * Answers a request from a revoked desktop installation with `device_revoked`,
+ * so the app signs out instead of retrying. Returns null for any other request.
+ */
+export async function revokedDeviceResponse(request: Request) {
+	const deviceId = request.headers.get(DESKTOP_DEVICE_HEADER);
+	if (!deviceId || !UUID_PATTERN.test(deviceId)) {
+		return null;
+	}
+
+	const revoked = await db.query.desktopDevice.findFirst({
+		where: and(eq(desktopDevice.id, deviceId), isNotNull(desktopDevice.revokedAt)),
+		columns: { id: true },
+	});
+	if (!revoked) {
+		return null;
+	}
+
+	return NextResponse.json(
+		{ error: "Device revoked", code: DEVICE_REVOKED_CODE },
+		{ status: 401 },
+	);
+}
+
+/**
+ * Notes that the user's device is in use, for the device list
+ */
+export async function touchDesktopDevice(request: Request, userId: string) {
+	const deviceId = request.headers.get(DESKTOP_DEVICE_HEADER);
+	if (!deviceId || !UUID_PATTERN.test(deviceId)) {
+		return;
+	}
+
+	await db
+		.update(desktopDevice)
+		.set({ lastUsedAt: new Date() })
+		.where(
+			and(
+				eq(desktopDevice.id, deviceId),
+				eq(desktopDevice.userId, userId),
+				or(
+					isNull(desktopDevice.lastUsedAt),
+					lt(desktopDevice.lastUsedAt, new Date(Date.now() - LAST_USED_RESOLUTION_MS)),
+				),
+			),
+		);
+}
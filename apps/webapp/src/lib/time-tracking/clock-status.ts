@@ -0,0 +1,58 @@
+import "server-only";
+
+import { and, eq, isNull } from "drizzle-orm";
+import { db } from "@/db";
+import { employee, workPeriod } from "@/db/schema";
+
+export type ClockStatus = {
+	hasEmployee: boolean;
+	employeeId: string | null;
+	isClockedIn: boolean;
+	activeWorkPeriod: { id: string; startTime: string } | null;
+};
+
+export const NO_EMPLOYEE_CLOCK_STATUS: ClockStatus = {
+	hasEmployee: false,
+	employeeId: null,
+	isClockedIn: false,
+	activeWorkPeriod: null,
+};
+
+/**
+ * Current clock status of the user's employee record in an organization
+ * Shared by the status endpoint and the desktop push stream
+ */
+export async function getClockStatus(userId: string, organizationId: string): Promise<ClockStatus> {
+	const emp = await db.query.employee.findFirst({
+		where: and(
+			eq(employee.userId, userId),
+			eq(employee.organizationId, organizationId),
+			eq(employee.isActive, true),
+		),
+	});
+
+	if (!emp) {
+		return NO_EMPLOYEE_CLOCK_STATUS;
+	}
+
+	// Check for active work period in this organization
+	const period = await db.query.workPeriod.findFirst({
+		where: and(
+			eq(workPeriod.employeeId, emp.id),
+			eq(workPeriod.organizationId, organizationId),
+			isNull(workPeriod.endTime),
+		),
+	});
+
+	return {
+		hasEmployee: true,
+		employeeId: emp.id,
+		isClockedIn: !!period,
+		activeWorkPeriod: period
+			? {
+					id: period.id,
+					startTime: period.startTime.toISOString(),
+				}
+			: null,
+	};
+}
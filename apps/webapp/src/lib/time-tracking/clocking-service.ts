@@ -47,7 +47,12 @@ type ClockingInput = {
 	createdBy: string;
 	actionId?: string;
 	action: ClockingAction;
-	source: { ipAddress: string | null; deviceInfo: string | null };
+	source: {
+		ipAddress: string | null;
+		deviceInfo: string | null;
+		/** The registered desktop installation the punch came from */
+		desktopDeviceId?: string | null;
+	};
 	notes?: string;
 	location?: string;
 	transaction?: unknown;
@@ -163,6 +168,7 @@ function entryValues(
 		createdBy: input.createdBy,
 		ipAddress: input.source.ipAddress,
 		deviceInfo: input.source.deviceInfo,
+		...(input.source.desktopDeviceId ? { desktopDeviceId: input.source.desktopDeviceId } : {}),
 		notes: input.notes,
 		location: input.location,
 		utcOffsetMinutes: input.action.utcOffsetMinutes,